@@ -0,0 +1,21 @@
+-- rows created before multi-tenancy belong to the nil tenant
+ALTER TABLE todo ADD COLUMN IF NOT EXISTS tenant_id uuid;
+UPDATE todo SET tenant_id = '00000000-0000-0000-0000-000000000000' WHERE tenant_id IS NULL;
+ALTER TABLE todo ALTER COLUMN tenant_id SET NOT NULL;
+
+-- titles only need to be unique inside the same tenant
+ALTER TABLE todo DROP CONSTRAINT IF EXISTS todo_ak_title;
+ALTER TABLE todo ADD CONSTRAINT todo_ak_tenant_title UNIQUE (tenant_id, title);
+
+CREATE INDEX IF NOT EXISTS todo_tenant_id_idx ON todo(tenant_id);
+
+-- `app.tenant_id` is set per transaction by the application, when it's missing
+-- no row is visible nor writable. `FORCE` makes the policy apply to the table
+-- owner too, but superusers and roles with `BYPASSRLS` still bypass it
+ALTER TABLE todo ENABLE ROW LEVEL SECURITY;
+ALTER TABLE todo FORCE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS todo_tenant_isolation ON todo;
+CREATE POLICY todo_tenant_isolation ON todo
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
@@ -0,0 +1,121 @@
+DO $$ BEGIN
+    CREATE TYPE webhook_delivery_status AS ENUM ('pending', 'delivered', 'dead');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+CREATE TABLE IF NOT EXISTS webhook (
+    id uuid NOT NULL,
+    tenant_id uuid NOT NULL,
+    url varchar(2048) NOT NULL,
+    -- empty means every event
+    events text[] NOT NULL,
+    secret varchar(256) NOT NULL,
+    created_at timestamptz NOT NULL,
+    CONSTRAINT webhook_pk PRIMARY KEY (id)
+);
+
+CREATE INDEX IF NOT EXISTS webhook_tenant_id_idx ON webhook(tenant_id);
+
+CREATE TABLE IF NOT EXISTS webhook_delivery (
+    id uuid NOT NULL,
+    tenant_id uuid NOT NULL,
+    webhook_id uuid NOT NULL,
+    event varchar(64) NOT NULL,
+    payload jsonb NOT NULL,
+    status webhook_delivery_status NOT NULL,
+    -- attempts since the delivery was created or last replayed
+    attempt_count integer NOT NULL DEFAULT 0,
+    next_attempt_at timestamptz,
+    last_error text,
+    created_at timestamptz NOT NULL,
+    updated_at timestamptz NOT NULL,
+    CONSTRAINT webhook_delivery_pk PRIMARY KEY (id),
+    CONSTRAINT webhook_delivery_webhook_fk FOREIGN KEY (webhook_id)
+        REFERENCES webhook(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS webhook_delivery_webhook_id_idx
+    ON webhook_delivery(webhook_id, created_at);
+CREATE INDEX IF NOT EXISTS webhook_delivery_due_idx
+    ON webhook_delivery(next_attempt_at) WHERE status = 'pending';
+
+CREATE TABLE IF NOT EXISTS webhook_attempt (
+    id uuid NOT NULL,
+    tenant_id uuid NOT NULL,
+    delivery_id uuid NOT NULL,
+    attempted_at timestamptz NOT NULL,
+    -- missing when no response was received
+    status_code integer,
+    error text,
+    duration_ms integer NOT NULL,
+    CONSTRAINT webhook_attempt_pk PRIMARY KEY (id),
+    CONSTRAINT webhook_attempt_delivery_fk FOREIGN KEY (delivery_id)
+        REFERENCES webhook_delivery(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS webhook_attempt_delivery_id_idx
+    ON webhook_attempt(delivery_id, attempted_at);
+
+-- same tenant isolation as todos. Only the delivery worker works across
+-- tenants, through functions owned by `todo_api_system` that bypass row level
+-- security on their own, so no setting of a connection can lift the isolation
+ALTER TABLE webhook ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook FORCE ROW LEVEL SECURITY;
+ALTER TABLE webhook_delivery ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_delivery FORCE ROW LEVEL SECURITY;
+ALTER TABLE webhook_attempt ENABLE ROW LEVEL SECURITY;
+ALTER TABLE webhook_attempt FORCE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS webhook_tenant_isolation ON webhook;
+CREATE POLICY webhook_tenant_isolation ON webhook
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
+
+DROP POLICY IF EXISTS webhook_delivery_tenant_isolation ON webhook_delivery;
+CREATE POLICY webhook_delivery_tenant_isolation ON webhook_delivery
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
+
+DROP POLICY IF EXISTS webhook_attempt_tenant_isolation ON webhook_attempt;
+CREATE POLICY webhook_attempt_tenant_isolation ON webhook_attempt
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
+
+-- owner of the functions working across tenants, which cannot log in nor be
+-- switched to by the server. Creating a role that bypasses row level security
+-- takes a superuser, which migrations are run by
+DO $$ BEGIN
+    CREATE ROLE todo_api_system NOLOGIN NOINHERIT BYPASSRLS;
+EXCEPTION
+    WHEN duplicate_object OR unique_violation THEN null;
+END $$;
+
+GRANT SELECT ON webhook TO todo_api_system;
+GRANT SELECT, UPDATE ON webhook_delivery TO todo_api_system;
+
+-- claim up to `max_deliveries` due deliveries of every tenant, hiding them from
+-- other claims for `lease_secs` so the same delivery is not attempted concurrently.
+-- Attempts are then recorded as the tenant of each delivery
+CREATE OR REPLACE FUNCTION claim_webhook_deliveries(max_deliveries bigint, lease_secs double precision)
+RETURNS TABLE (
+    id uuid, tenant_id uuid, event varchar, payload text, attempt_count integer,
+    url varchar, secret varchar
+)
+LANGUAGE sql SECURITY DEFINER SET search_path = public, pg_temp
+AS $$
+    WITH due AS (
+        SELECT id FROM webhook_delivery
+        WHERE status = 'pending' AND next_attempt_at <= now()
+        ORDER BY next_attempt_at
+        LIMIT max_deliveries
+        FOR UPDATE SKIP LOCKED
+    )
+    UPDATE webhook_delivery AS d
+    SET next_attempt_at = now() + make_interval(secs => lease_secs)
+    FROM due, webhook AS w
+    WHERE d.id = due.id AND w.id = d.webhook_id
+    RETURNING d.id, d.tenant_id, d.event, d.payload::text, d.attempt_count, w.url, w.secret
+$$;
+
+ALTER FUNCTION claim_webhook_deliveries(bigint, double precision) OWNER TO todo_api_system;
@@ -0,0 +1,78 @@
+-- changes to todos, written in the same transaction as the change itself and
+-- removed once the relay dispatched them to every sink
+CREATE TABLE IF NOT EXISTS outbox (
+    -- also the deduplication id, as events are dispatched at least once
+    id uuid NOT NULL,
+    -- keeps events in the order they were written
+    seq bigserial NOT NULL,
+    tenant_id uuid NOT NULL,
+    event varchar(64) NOT NULL,
+    payload jsonb NOT NULL,
+    attempt_count integer NOT NULL DEFAULT 0,
+    next_attempt_at timestamptz NOT NULL,
+    last_error text,
+    created_at timestamptz NOT NULL,
+    CONSTRAINT outbox_pk PRIMARY KEY (id)
+);
+
+CREATE INDEX IF NOT EXISTS outbox_due_idx ON outbox(next_attempt_at, seq);
+
+-- wake up the relay once the transaction that wrote events commits
+CREATE OR REPLACE FUNCTION outbox_notify() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('outbox', '');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS outbox_notify ON outbox;
+CREATE TRIGGER outbox_notify AFTER INSERT ON outbox
+    FOR EACH STATEMENT EXECUTE FUNCTION outbox_notify();
+
+-- same tenant isolation as todos. The relay claims the events of every tenant
+-- through a function owned by `todo_api_system`, like the webhook worker does,
+-- and then completes each event as its tenant
+ALTER TABLE outbox ENABLE ROW LEVEL SECURITY;
+ALTER TABLE outbox FORCE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS outbox_tenant_isolation ON outbox;
+CREATE POLICY outbox_tenant_isolation ON outbox
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
+
+GRANT SELECT, UPDATE ON outbox TO todo_api_system;
+
+-- claim up to `max_events` due events of every tenant in the order they were
+-- written, hiding them from other claims for `lease_secs` so they are not
+-- relayed concurrently
+CREATE OR REPLACE FUNCTION claim_outbox_events(max_events bigint, lease_secs double precision)
+RETURNS TABLE (
+    id uuid, seq bigint, tenant_id uuid, event varchar, payload text,
+    attempt_count integer, created_at timestamptz
+)
+LANGUAGE sql SECURITY DEFINER SET search_path = public, pg_temp
+AS $$
+    WITH due AS (
+        SELECT id FROM outbox
+        WHERE next_attempt_at <= now()
+        ORDER BY seq
+        LIMIT max_events
+        FOR UPDATE SKIP LOCKED
+    ), claimed AS (
+        UPDATE outbox AS o
+        SET next_attempt_at = now() + make_interval(secs => lease_secs)
+        FROM due
+        WHERE o.id = due.id
+        RETURNING o.id, o.seq, o.tenant_id, o.event, o.payload::text, o.attempt_count,
+            o.created_at
+    )
+    SELECT * FROM claimed ORDER BY seq
+$$;
+
+ALTER FUNCTION claim_outbox_events(bigint, double precision) OWNER TO todo_api_system;
+
+-- webhook deliveries remember the event they were created from, so relaying
+-- the same event again does not deliver it twice
+ALTER TABLE webhook_delivery ADD COLUMN IF NOT EXISTS outbox_id uuid;
+CREATE UNIQUE INDEX IF NOT EXISTS webhook_delivery_outbox_id_idx
+    ON webhook_delivery(webhook_id, outbox_id);
@@ -0,0 +1,23 @@
+-- todos created by imports remember the entry they were imported from, such
+-- as the UID of an iCalendar VTODO, so importing the same file again skips them.
+-- Deleting a todo forgets its entry, so a later import creates it again
+CREATE TABLE IF NOT EXISTS todo_import (
+    tenant_id uuid NOT NULL,
+    -- format of the imported file, such as `ics`
+    format varchar(16) NOT NULL,
+    uid text NOT NULL,
+    todo_id uuid NOT NULL,
+    created_at timestamptz NOT NULL,
+    CONSTRAINT todo_import_pk PRIMARY KEY (tenant_id, format, uid),
+    CONSTRAINT todo_import_fk_todo FOREIGN KEY (todo_id) REFERENCES todo(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS todo_import_todo_id_idx ON todo_import(todo_id);
+
+ALTER TABLE todo_import ENABLE ROW LEVEL SECURITY;
+ALTER TABLE todo_import FORCE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS todo_import_tenant_isolation ON todo_import;
+CREATE POLICY todo_import_tenant_isolation ON todo_import
+    USING (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid)
+    WITH CHECK (tenant_id = NULLIF(current_setting('app.tenant_id', true), '')::uuid);
@@ -0,0 +1,15 @@
+-- metrics count the todos of every tenant, through a function owned by
+-- `todo_api_system` like the other work across tenants, so no setting of a
+-- connection can lift the isolation of todos
+DROP POLICY IF EXISTS todo_metrics_read ON todo;
+
+GRANT SELECT ON todo TO todo_api_system;
+
+CREATE OR REPLACE FUNCTION count_todos_by_status()
+RETURNS TABLE (status todo_status, count bigint)
+LANGUAGE sql STABLE SECURITY DEFINER SET search_path = public, pg_temp
+AS $$
+    SELECT status, count(*) FROM todo GROUP BY status
+$$;
+
+ALTER FUNCTION count_todos_by_status() OWNER TO todo_api_system;
@@ -0,0 +1,13 @@
+-- token buckets of the rate limiter, shared by every instance of the server.
+-- Keys are a client, its verified tenant or else its address, and a route
+CREATE TABLE IF NOT EXISTS rate_limit_bucket (
+    key text NOT NULL,
+    tokens double precision NOT NULL,
+    -- whether the last request took a token, returned by the upsert that
+    -- takes one
+    allowed boolean NOT NULL,
+    updated_at timestamptz NOT NULL,
+    CONSTRAINT rate_limit_bucket_pk PRIMARY KEY (key)
+);
+
+CREATE INDEX IF NOT EXISTS rate_limit_bucket_updated_at_idx ON rate_limit_bucket(updated_at);
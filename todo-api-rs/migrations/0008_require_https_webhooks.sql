@@ -0,0 +1,34 @@
+-- deliveries are only sent over https, so webhooks with other urls are
+-- disabled rather than removed, keeping them and their deliveries until their
+-- tenant changes the url. Migrations are run by a superuser, which sees the
+-- webhooks of every tenant
+ALTER TABLE webhook ADD COLUMN IF NOT EXISTS disabled_reason text;
+
+UPDATE webhook
+SET disabled_reason = 'Webhook url must be an absolute https url'
+WHERE url NOT LIKE 'https://%' AND disabled_reason IS NULL;
+
+-- deliveries of disabled webhooks stay pending without being claimed, so they
+-- are delivered once the webhook is changed
+CREATE OR REPLACE FUNCTION claim_webhook_deliveries(max_deliveries bigint, lease_secs double precision)
+RETURNS TABLE (
+    id uuid, tenant_id uuid, event varchar, payload text, attempt_count integer,
+    url varchar, secret varchar
+)
+LANGUAGE sql SECURITY DEFINER SET search_path = public, pg_temp
+AS $$
+    WITH due AS (
+        SELECT d.id FROM webhook_delivery AS d
+        JOIN webhook AS w ON w.id = d.webhook_id
+        WHERE d.status = 'pending' AND d.next_attempt_at <= now()
+            AND w.disabled_reason IS NULL
+        ORDER BY d.next_attempt_at
+        LIMIT max_deliveries
+        FOR UPDATE OF d SKIP LOCKED
+    )
+    UPDATE webhook_delivery AS d
+    SET next_attempt_at = now() + make_interval(secs => lease_secs)
+    FROM due, webhook AS w
+    WHERE d.id = due.id AND w.id = d.webhook_id
+    RETURNING d.id, d.tenant_id, d.event, d.payload::text, d.attempt_count, w.url, w.secret
+$$;
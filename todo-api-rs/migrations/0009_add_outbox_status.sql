@@ -0,0 +1,37 @@
+-- events that failed their last attempt, or that cannot be decoded, are kept
+-- as dead instead of being relayed again forever
+DO $$ BEGIN
+    CREATE TYPE outbox_status AS ENUM ('pending', 'dead');
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+ALTER TABLE outbox ADD COLUMN IF NOT EXISTS status outbox_status NOT NULL DEFAULT 'pending';
+
+DROP INDEX IF EXISTS outbox_due_idx;
+CREATE INDEX IF NOT EXISTS outbox_due_idx ON outbox(next_attempt_at, seq) WHERE status = 'pending';
+
+-- dead events are no longer claimed
+CREATE OR REPLACE FUNCTION claim_outbox_events(max_events bigint, lease_secs double precision)
+RETURNS TABLE (
+    id uuid, seq bigint, tenant_id uuid, event varchar, payload text,
+    attempt_count integer, created_at timestamptz
+)
+LANGUAGE sql SECURITY DEFINER SET search_path = public, pg_temp
+AS $$
+    WITH due AS (
+        SELECT id FROM outbox
+        WHERE status = 'pending' AND next_attempt_at <= now()
+        ORDER BY seq
+        LIMIT max_events
+        FOR UPDATE SKIP LOCKED
+    ), claimed AS (
+        UPDATE outbox AS o
+        SET next_attempt_at = now() + make_interval(secs => lease_secs)
+        FROM due
+        WHERE o.id = due.id
+        RETURNING o.id, o.seq, o.tenant_id, o.event, o.payload::text, o.attempt_count,
+            o.created_at
+    )
+    SELECT * FROM claimed ORDER BY seq
+$$;
@@ -0,0 +1,28 @@
+use crate::adapters::dtos::backup::export::ExportPresenter;
+use crate::application::dtos::backup::export::ExportBackupOutput;
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ExportBackupController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> ExportBackupController<T, P>
+where
+    T: UseCase<(), ExportBackupOutput>,
+    P: ExportPresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "ExportBackupController::run", level = "debug", skip_all)]
+    pub async fn run(self) -> <P as ExportPresenter>::View {
+        let backup = self.interactor.exec(()).await;
+        self.presenter.present(backup)
+    }
+}
@@ -0,0 +1,48 @@
+use crate::adapters::dtos::backup::restore::{
+    RestorePresenter, RestoreRequest, RestoreResponseError,
+};
+use crate::application::dtos::backup::restore::{
+    RestoreBackupError, RestoreBackupInput, RestoreBackupOutput,
+};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct RestoreBackupController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> RestoreBackupController<T, P>
+where
+    T: UseCase<RestoreBackupInput, RestoreBackupOutput>,
+    P: RestorePresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "RestoreBackupController::run", level = "debug", skip_all)]
+    pub async fn run(self, req: RestoreRequest) -> <P as RestorePresenter>::View {
+        let input = match req.parse().map_err(RestoreResponseError::Input) {
+            Ok(input) => input,
+            Err(err) => return self.presenter.present(Err(err)),
+        };
+
+        let mode = input.mode;
+        let result = self
+            .interactor
+            .exec(input)
+            .await
+            .map(|summary| (mode, summary))
+            .map_err(|err| match err {
+                RestoreBackupError::DuplicatedTitle => RestoreResponseError::DuplicatedTitle,
+                RestoreBackupError::IdTaken => RestoreResponseError::IdTaken,
+                RestoreBackupError::Internal(src) => RestoreResponseError::Internal(src),
+            });
+
+        self.presenter.present(result)
+    }
+}
@@ -1 +1,3 @@
-pub mod todo;
\ No newline at end of file
+pub mod backup;
+pub mod todo;
+pub mod webhook;
\ No newline at end of file
@@ -22,6 +22,7 @@ where
         }
     }
 
+    #[tracing::instrument(name = "CreateTodoController::run", level = "debug", skip_all)]
     pub async fn run(self, req: CreateRequest) -> <P as CreatePresenter>::View {
         let input = match req.parse().map_err(CreateResponseError::Input) {
             Ok(input) => input,
@@ -22,6 +22,7 @@ where
         }
     }
 
+    #[tracing::instrument(name = "DeleteTodoController::run", level = "debug", skip_all)]
     pub async fn run(self, req: DeleteRequest) -> <P as DeletePresenter>::View {
         let todo_id = match req.parse().map_err(DeleteResponseError::Input) {
             Ok(todo_id) => todo_id,
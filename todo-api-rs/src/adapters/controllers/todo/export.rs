@@ -0,0 +1,33 @@
+use crate::adapters::dtos::todo::export::{ExportPresenter, ExportRequest, ExportResponseError};
+use crate::application::dtos::todo::export::{ExportTodosInput, ExportTodosOutput};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ExportTodosController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> ExportTodosController<T, P>
+where
+    T: UseCase<ExportTodosInput, ExportTodosOutput>,
+    P: ExportPresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "ExportTodosController::run", level = "debug", skip_all)]
+    pub async fn run(self, req: ExportRequest) -> <P as ExportPresenter>::View {
+        let input = match req.parse().map_err(ExportResponseError::Input) {
+            Ok(input) => input,
+            Err(err) => return self.presenter.present(Err(err)),
+        };
+
+        let todos = self.interactor.exec(input).await;
+        self.presenter.present(Ok(todos))
+    }
+}
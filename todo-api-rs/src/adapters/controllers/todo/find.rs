@@ -20,6 +20,7 @@ where
         }
     }
 
+    #[tracing::instrument(name = "FindTodoController::run", level = "debug", skip_all)]
     pub async fn run(self, req: FindRequest) -> <P as FindPresenter>::View {
         let todo_id = match req.parse().map_err(FindResponseError::Input) {
             Ok(todo_id) => todo_id,
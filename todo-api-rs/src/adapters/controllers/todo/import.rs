@@ -0,0 +1,61 @@
+use crate::adapters::dtos::todo::import::{
+    ImportPresenter, ImportReport, ImportRequest, ImportResponseError,
+};
+use crate::application::dtos::todo::import::{
+    ImportOutcome, ImportTodosError, ImportTodosInput, ImportTodosOutput,
+};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ImportTodosController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> ImportTodosController<T, P>
+where
+    T: UseCase<ImportTodosInput, ImportTodosOutput>,
+    P: ImportPresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "ImportTodosController::run", level = "debug", skip_all)]
+    pub async fn run(self, req: ImportRequest) -> <P as ImportPresenter>::View {
+        let parsed = match req.parse().map_err(ImportResponseError::Input) {
+            Ok(parsed) => parsed,
+            Err(err) => return self.presenter.present(Err(err)),
+        };
+
+        let dry_run = parsed.input.dry_run;
+        let result = self
+            .interactor
+            .exec(parsed.input)
+            .await
+            .map(|imported| {
+                let mut report = ImportReport {
+                    dry_run,
+                    created: Vec::new(),
+                    skipped: Vec::new(),
+                    invalid: parsed.invalid,
+                };
+                for (source, outcome) in parsed.sources.into_iter().zip(imported.outcomes) {
+                    match outcome {
+                        ImportOutcome::Created(todo) => report.created.push((source, todo)),
+                        ImportOutcome::Skipped(reason) => report.skipped.push((source, reason)),
+                    }
+                }
+
+                report
+            })
+            .map_err(|err| match err {
+                ImportTodosError::Internal(src) => ImportResponseError::Internal(src),
+            });
+
+        self.presenter.present(result)
+    }
+}
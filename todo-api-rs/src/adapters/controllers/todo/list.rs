@@ -20,6 +20,7 @@ where
         }
     }
 
+    #[tracing::instrument(name = "ListTodosController::run", level = "debug", skip_all)]
     pub async fn run(self, req: ListRequest) -> <P as ListPresenter>::View {
         let input = match req.parse().map_err(ListResponseError::Input) {
             Ok(input) => input,
@@ -20,6 +20,7 @@ where
         }
     }
 
+    #[tracing::instrument(name = "UpdateTodoController::run", level = "debug", skip_all)]
     pub async fn run(self, req: UpdateRequest) -> <P as UpdatePresenter>::View {
         let input = match req.parse().map_err(UpdateResponseError::Input) {
             Ok(input) => input,
@@ -29,6 +30,7 @@ where
         let todo_id = input.id;
         let result = self.interactor.exec(input).await.map_err(|err| match err {
             UpdateTodoError::NotFound => UpdateResponseError::NotFound(todo_id),
+            UpdateTodoError::Conflict => UpdateResponseError::Conflict(todo_id),
             UpdateTodoError::DuplicatedTitle(title) => UpdateResponseError::DuplicatedTitle(title),
             UpdateTodoError::Internal(src) => UpdateResponseError::Internal(src),
         });
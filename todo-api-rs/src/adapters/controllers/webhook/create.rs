@@ -0,0 +1,38 @@
+use crate::adapters::dtos::webhook::create::{CreatePresenter, CreateRequest, CreateResponseError};
+use crate::application::dtos::webhook::create::{
+    CreateWebhookError, CreateWebhookInput, CreateWebhookOutput,
+};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct CreateWebhookController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> CreateWebhookController<T, P>
+where
+    T: UseCase<CreateWebhookInput, CreateWebhookOutput>,
+    P: CreatePresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "CreateWebhookController::run", level = "debug", skip_all)]
+    pub async fn run(self, req: CreateRequest) -> <P as CreatePresenter>::View {
+        let input = match req.parse().map_err(CreateResponseError::Input) {
+            Ok(input) => input,
+            Err(err) => return self.presenter.present(Err(err)),
+        };
+
+        let result = self.interactor.exec(input).await.map_err(|err| match err {
+            CreateWebhookError::Internal(src) => CreateResponseError::Internal(src),
+        });
+
+        self.presenter.present(result)
+    }
+}
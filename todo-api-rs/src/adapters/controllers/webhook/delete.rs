@@ -0,0 +1,43 @@
+use crate::adapters::dtos::webhook::delete::{DeletePresenter, DeleteRequest, DeleteResponseError};
+use crate::application::dtos::webhook::delete::{
+    DeleteWebhookError, DeleteWebhookInput, DeleteWebhookOutput,
+};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct DeleteWebhookController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> DeleteWebhookController<T, P>
+where
+    T: UseCase<DeleteWebhookInput, DeleteWebhookOutput>,
+    P: DeletePresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "DeleteWebhookController::run", level = "debug", skip_all)]
+    pub async fn run(self, req: DeleteRequest) -> <P as DeletePresenter>::View {
+        let webhook_id = match req.parse().map_err(DeleteResponseError::Input) {
+            Ok(webhook_id) => webhook_id,
+            Err(err) => return self.presenter.present(Err(err)),
+        };
+
+        let result = self
+            .interactor
+            .exec(webhook_id)
+            .await
+            .map_err(|err| match err {
+                DeleteWebhookError::NotFound => DeleteResponseError::NotFound(webhook_id),
+                DeleteWebhookError::Internal(src) => DeleteResponseError::Internal(src),
+            });
+
+        self.presenter.present(result)
+    }
+}
@@ -0,0 +1,42 @@
+use crate::adapters::dtos::webhook::deliveries::{
+    DeliveriesPresenter, DeliveriesRequest, DeliveriesResponseError,
+};
+use crate::application::dtos::webhook::deliveries::{
+    ListDeliveriesError, ListDeliveriesInput, ListDeliveriesOutput,
+};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ListDeliveriesController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> ListDeliveriesController<T, P>
+where
+    T: UseCase<ListDeliveriesInput, ListDeliveriesOutput>,
+    P: DeliveriesPresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "ListDeliveriesController::run", level = "debug", skip_all)]
+    pub async fn run(self, req: DeliveriesRequest) -> <P as DeliveriesPresenter>::View {
+        let input = match req.parse().map_err(DeliveriesResponseError::Input) {
+            Ok(input) => input,
+            Err(err) => return self.presenter.present(Err(err)),
+        };
+
+        let webhook_id = input.webhook_id;
+        let result = self.interactor.exec(input).await.map_err(|err| match err {
+            ListDeliveriesError::NotFound => DeliveriesResponseError::NotFound(webhook_id),
+            ListDeliveriesError::Internal(src) => DeliveriesResponseError::Internal(src),
+        });
+
+        self.presenter.present(result)
+    }
+}
@@ -0,0 +1,31 @@
+use crate::adapters::dtos::webhook::list::{ListPresenter, ListResponseError};
+use crate::application::dtos::webhook::list::{ListWebhooksError, ListWebhooksOutput};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ListWebhooksController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> ListWebhooksController<T, P>
+where
+    T: UseCase<(), ListWebhooksOutput>,
+    P: ListPresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "ListWebhooksController::run", level = "debug", skip_all)]
+    pub async fn run(self) -> <P as ListPresenter>::View {
+        let result = self.interactor.exec(()).await.map_err(|err| match err {
+            ListWebhooksError::Internal(src) => ListResponseError::Internal(src),
+        });
+
+        self.presenter.present(result)
+    }
+}
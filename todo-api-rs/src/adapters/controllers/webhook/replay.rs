@@ -0,0 +1,43 @@
+use crate::adapters::dtos::webhook::replay::{ReplayPresenter, ReplayRequest, ReplayResponseError};
+use crate::application::dtos::webhook::replay::{
+    ReplayDeliveryError, ReplayDeliveryInput, ReplayDeliveryOutput,
+};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ReplayDeliveryController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> ReplayDeliveryController<T, P>
+where
+    T: UseCase<ReplayDeliveryInput, ReplayDeliveryOutput>,
+    P: ReplayPresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "ReplayDeliveryController::run", level = "debug", skip_all)]
+    pub async fn run(self, req: ReplayRequest) -> <P as ReplayPresenter>::View {
+        let delivery_id = match req.parse().map_err(ReplayResponseError::Input) {
+            Ok(delivery_id) => delivery_id,
+            Err(err) => return self.presenter.present(Err(err)),
+        };
+
+        let result = self
+            .interactor
+            .exec(delivery_id)
+            .await
+            .map_err(|err| match err {
+                ReplayDeliveryError::NotFound => ReplayResponseError::NotFound(delivery_id),
+                ReplayDeliveryError::Internal(src) => ReplayResponseError::Internal(src),
+            });
+
+        self.presenter.present(result)
+    }
+}
@@ -0,0 +1,40 @@
+use crate::adapters::dtos::webhook::update::{UpdatePresenter, UpdateRequest, UpdateResponseError};
+use crate::application::dtos::webhook::update::{
+    UpdateWebhookError, UpdateWebhookInput, UpdateWebhookOutput,
+};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct UpdateWebhookController<T, P> {
+    interactor: T,
+    presenter: P,
+}
+
+impl<T, P> UpdateWebhookController<T, P>
+where
+    T: UseCase<UpdateWebhookInput, UpdateWebhookOutput>,
+    P: UpdatePresenter,
+{
+    pub const fn new(interactor: T, presenter: P) -> Self {
+        Self {
+            interactor,
+            presenter,
+        }
+    }
+
+    #[tracing::instrument(name = "UpdateWebhookController::run", level = "debug", skip_all)]
+    pub async fn run(self, req: UpdateRequest) -> <P as UpdatePresenter>::View {
+        let input = match req.parse().map_err(UpdateResponseError::Input) {
+            Ok(input) => input,
+            Err(err) => return self.presenter.present(Err(err)),
+        };
+
+        let webhook_id = input.id;
+        let result = self.interactor.exec(input).await.map_err(|err| match err {
+            UpdateWebhookError::NotFound => UpdateResponseError::NotFound(webhook_id),
+            UpdateWebhookError::Internal(src) => UpdateResponseError::Internal(src),
+        });
+
+        self.presenter.present(result)
+    }
+}
@@ -0,0 +1,8 @@
+use crate::application::dtos::backup::export::Backup;
+
+pub trait ExportPresenter {
+    type View;
+    fn present(&self, response: ExportResponse) -> Self::View;
+}
+
+pub type ExportResponse = Backup;
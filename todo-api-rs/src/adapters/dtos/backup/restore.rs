@@ -0,0 +1,387 @@
+use std::collections::HashSet;
+use std::error;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::adapters::dtos::todo::import::ImportFormat;
+use crate::adapters::dtos::validation::{FieldLocation, InvalidField, ValidationReport};
+use crate::application::dtos::backup::restore::RestoreBackupInput;
+use crate::application::repositories::backup::{
+    ImportRecord, RestoreMode, RestoreSummary, Snapshot,
+};
+use crate::application::repositories::todo::ImportOrigin;
+use crate::domain::entities::todo::{Description, InitProps, Status, Title, TodoEntity};
+use crate::domain::types::{Date, DateTime, Id};
+
+/// Version of the archives written by backups
+pub const ARCHIVE_VERSION: u32 = 2;
+
+/// Version of the archives written before imports were backed up, which are
+/// still restored, leaving imports as they are
+const TODOS_ONLY_VERSION: u32 = 1;
+
+pub trait RestorePresenter {
+    type View;
+    fn present(&self, response: RestoreResponse) -> Self::View;
+}
+
+#[derive(Clone, Debug)]
+pub struct RestoreRequest {
+    pub mode: Option<String>,
+    pub archive: Vec<u8>,
+}
+
+/// Fields every version of the archive has, read before the rest of it
+#[derive(Deserialize)]
+struct ArchiveHeader {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct Archive {
+    todos: Vec<ArchivedTodo>,
+    /// Missing from archives of the version without imports
+    imports: Option<Vec<ArchivedImport>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchivedTodo {
+    id: String,
+    title: String,
+    description: Option<String>,
+    status: String,
+    todo_at: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchivedImport {
+    format: String,
+    uid: String,
+    todo_id: String,
+    created_at: String,
+}
+
+impl RestoreRequest {
+    pub fn parse(self) -> Result<RestoreBackupInput, ValidationReport<ParseError>> {
+        let mut report = ValidationReport::new();
+
+        let mode = report.check(match self.mode.as_deref() {
+            None | Some("") | Some("merge") => Ok(RestoreMode::Merge),
+            Some("replace") => Ok(RestoreMode::Replace),
+            Some(_) => Err(ParseError::Mode),
+        });
+
+        let archive = serde_json::from_slice::<ArchiveHeader>(&self.archive)
+            .map_err(|err| ParseError::Archive(err.to_string()))
+            .and_then(|header| match header.version {
+                TODOS_ONLY_VERSION | ARCHIVE_VERSION => {
+                    serde_json::from_slice::<Archive>(&self.archive)
+                        .map_err(|err| ParseError::Archive(err.to_string()))
+                        .and_then(|archive| match archive.imports {
+                            None if header.version == ARCHIVE_VERSION => {
+                                Err(ParseError::Archive(String::from("missing field `imports`")))
+                            }
+                            _ => Ok(archive),
+                        })
+                }
+                version => Err(ParseError::Version(version)),
+            });
+        let Some(archive) = report.check(archive) else {
+            return Err(report);
+        };
+
+        let mut ids = HashSet::new();
+        let mut todos = Vec::with_capacity(archive.todos.len());
+        for (index, todo) in archive.todos.into_iter().enumerate() {
+            let todo = todo.parse().and_then(|todo| match ids.insert(todo.id()) {
+                true => Ok(todo),
+                false => Err(String::from("id is used by another todo")),
+            });
+
+            if let Some(todo) =
+                report.check(todo.map_err(|reason| ParseError::Todo { index, reason }))
+            {
+                todos.push(todo);
+            }
+        }
+
+        let imports = archive.imports.map(|archived| {
+            let mut entries = HashSet::new();
+            let mut imports = Vec::with_capacity(archived.len());
+            for (index, import) in archived.into_iter().enumerate() {
+                let import = import.parse().and_then(|import| {
+                    if !ids.contains(&import.todo_id) {
+                        Err(String::from(
+                            "todo id is not the id of a todo of the archive",
+                        ))
+                    } else if !entries.insert(import.origin.clone()) {
+                        Err(String::from("entry is used by another import"))
+                    } else {
+                        Ok(import)
+                    }
+                });
+
+                if let Some(import) =
+                    report.check(import.map_err(|reason| ParseError::Import { index, reason }))
+                {
+                    imports.push(import);
+                }
+            }
+
+            imports
+        });
+
+        match mode {
+            Some(mode) if report.errors().is_empty() => Ok(RestoreBackupInput {
+                snapshot: Snapshot { todos, imports },
+                mode,
+            }),
+            _ => Err(report),
+        }
+    }
+}
+
+impl ArchivedTodo {
+    /// Restore the todo as it was, keeping its id and timestamps
+    fn parse(self) -> Result<TodoEntity, String> {
+        Ok(TodoEntity::init(InitProps {
+            id: Id::parse_str(&self.id).or(Err(String::from("id must be a UUID")))?,
+            title: Title::new(self.title).map_err(|err| err.to_string())?,
+            description: self
+                .description
+                .map(Description::new)
+                .transpose()
+                .map_err(|err| err.to_string())?,
+            status: Status::parse_str(&self.status).map_err(|err| err.to_string())?,
+            todo_at: self
+                .todo_at
+                .map(|at| Date::parse_str(&at))
+                .transpose()
+                .map_err(|err| err.to_string())?,
+            created_at: DateTime::parse_str(&self.created_at).map_err(|err| err.to_string())?,
+            updated_at: DateTime::parse_str(&self.updated_at).map_err(|err| err.to_string())?,
+        }))
+    }
+}
+
+impl ArchivedImport {
+    fn parse(self) -> Result<ImportRecord, String> {
+        let format = [ImportFormat::Csv, ImportFormat::Ics, ImportFormat::Txt]
+            .into_iter()
+            .find(|format| format.name() == self.format)
+            .ok_or(String::from("format must be one of csv, ics or txt"))?;
+        if self.uid.is_empty() {
+            return Err(String::from("uid must not be empty"));
+        }
+
+        Ok(ImportRecord {
+            origin: ImportOrigin {
+                format: format.name(),
+                uid: self.uid,
+            },
+            todo_id: Id::parse_str(&self.todo_id)
+                .or(Err(String::from("todo id must be a UUID")))?,
+            created_at: DateTime::parse_str(&self.created_at).map_err(|err| err.to_string())?,
+        })
+    }
+}
+
+pub type RestoreResponse = Result<(RestoreMode, RestoreSummary), RestoreResponseError>;
+
+#[derive(Debug, Error)]
+pub enum RestoreResponseError {
+    #[error(transparent)]
+    Input(ValidationReport<ParseError>),
+    #[error("Todo title already exists")]
+    DuplicatedTitle,
+    #[error("Todo id is already taken")]
+    IdTaken,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("Mode must be one of merge or replace")]
+    Mode,
+    #[error("Archive is not valid: {0}")]
+    Archive(String),
+    #[error("Archive version {0} is not supported, expected {ARCHIVE_VERSION}")]
+    Version(u32),
+    #[error("Todo at index {index} is invalid: {reason}")]
+    Todo { index: usize, reason: String },
+    #[error("Import at index {index} is invalid: {reason}")]
+    Import { index: usize, reason: String },
+}
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Mode => "mode",
+            Self::Archive(..) => "archive",
+            Self::Version(..) => "version",
+            Self::Todo { .. } => "todos",
+            Self::Import { .. } => "imports",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Mode => "invalid",
+            Self::Archive(..) => "invalid_format",
+            Self::Version(..) => "unsupported",
+            Self::Todo { .. } => "invalid",
+            Self::Import { .. } => "invalid",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+
+    fn location(&self) -> FieldLocation {
+        match self {
+            Self::Mode => FieldLocation::Query,
+            _ => FieldLocation::Body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(mode: &str, archive: serde_json::Value) -> RestoreRequest {
+        RestoreRequest {
+            mode: Some(mode.to_owned()),
+            archive: archive.to_string().into_bytes(),
+        }
+    }
+
+    fn todo(id: &str, title: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "title": title,
+            "description": null,
+            "status": "done",
+            "todoAt": "2024-02-01",
+            "createdAt": "2024-01-31T08:30:00.123456Z",
+            "updatedAt": "2024-02-02T08:30:00Z",
+        })
+    }
+
+    #[test]
+    fn todos_keep_their_ids_and_timestamps() {
+        let id = Id::new().to_string();
+        let archive = serde_json::json!({
+            "version": 1,
+            "exportedAt": "2024-02-03T00:00:00Z",
+            "todos": [todo(&id, "Buy milk")],
+        });
+
+        let input = request("replace", archive).parse().unwrap();
+        assert_eq!(input.mode, RestoreMode::Replace);
+
+        let todo = &input.snapshot.todos[0];
+        assert_eq!(todo.id().to_string(), id);
+        assert_eq!(*todo.status(), Status::Done);
+        assert_eq!(
+            todo.created_at().to_rfc3339(),
+            "2024-01-31T08:30:00.123456Z"
+        );
+        assert_eq!(todo.updated_at().to_rfc3339(), "2024-02-02T08:30:00Z");
+        // archives without imports leave them as they are
+        assert!(input.snapshot.imports.is_none());
+    }
+
+    #[test]
+    fn imports_refer_to_todos_of_the_archive() {
+        let id = Id::new().to_string();
+        let import = |uid: &str, todo_id: &str| {
+            serde_json::json!({
+                "format": "ics",
+                "uid": uid,
+                "todoId": todo_id,
+                "createdAt": "2024-01-31T08:30:00Z",
+            })
+        };
+        let archive = serde_json::json!({
+            "version": 2,
+            "todos": [todo(&id, "Buy milk")],
+            "imports": [import("milk@example.com", &id)],
+        });
+
+        let input = request("merge", archive).parse().unwrap();
+        let imports = input.snapshot.imports.unwrap();
+        assert_eq!(imports[0].origin.format, "ics");
+        assert_eq!(imports[0].origin.uid, "milk@example.com");
+        assert_eq!(imports[0].todo_id.to_string(), id);
+
+        let archive = serde_json::json!({
+            "version": 2,
+            "todos": [todo(&id, "Buy milk")],
+            "imports": [
+                import("milk@example.com", &id),
+                import("milk@example.com", &id),
+                import("eggs@example.com", &Id::new().to_string()),
+            ],
+        });
+        let report = request("merge", archive).parse().unwrap_err();
+        let indexes = report
+            .errors()
+            .iter()
+            .map(|err| match err {
+                ParseError::Import { index, .. } => *index,
+                _ => panic!("unexpected error {err}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(indexes, [1, 2]);
+    }
+
+    #[test]
+    fn unsupported_versions_fail() {
+        let archive = serde_json::json!({ "version": 3, "todos": [], "tags": [] });
+        let report = request("merge", archive).parse().unwrap_err();
+        assert_eq!(report.errors(), [ParseError::Version(3)]);
+
+        let archive = serde_json::json!({ "version": 2, "todos": [] });
+        let report = request("merge", archive).parse().unwrap_err();
+        assert_eq!(report.errors()[0].code(), "archive.invalid_format");
+    }
+
+    #[test]
+    fn every_invalid_field_is_reported() {
+        let id = Id::new().to_string();
+        let archive = serde_json::json!({
+            "version": 1,
+            "todos": [todo(&id, "First"), todo(&id, "Second"), todo("1", "")],
+        });
+
+        let report = request("overwrite", archive).parse().unwrap_err();
+        let codes = report
+            .errors()
+            .iter()
+            .map(|err| (err.code(), err.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            [
+                (
+                    String::from("mode.invalid"),
+                    String::from("Mode must be one of merge or replace")
+                ),
+                (
+                    String::from("todos.invalid"),
+                    String::from("Todo at index 1 is invalid: id is used by another todo")
+                ),
+                (
+                    String::from("todos.invalid"),
+                    String::from("Todo at index 2 is invalid: id must be a UUID")
+                ),
+            ]
+        );
+    }
+}
@@ -1 +1,4 @@
+pub mod backup;
 pub mod todo;
+pub mod validation;
+pub mod webhook;
\ No newline at end of file
@@ -2,9 +2,10 @@ use std::error;
 
 use thiserror::Error;
 
+use crate::adapters::dtos::validation::{InvalidField, ValidationReport};
 use crate::application::dtos::todo::create::CreateTodoInput;
 use crate::domain::entities::todo::{
-    Description, DescriptionError, StatusError, Status, Title, TitleError, TodoEntity,
+    Description, DescriptionError, Status, StatusError, Title, TitleError, TodoEntity,
 };
 use crate::domain::types::{Date, ParseDateError};
 
@@ -22,35 +23,44 @@ pub struct CreateRequest {
 }
 
 impl CreateRequest {
-    pub fn parse(self) -> Result<CreateTodoInput, ParseError> {
-        let title = self
-            .title
-            .ok_or(ParseError::Title(TitleError::Empty))
-            .and_then(|title| Title::new(title).map_err(ParseError::Title))?;
-
-        let description = self
-            .description
-            .map(Description::new)
-            .transpose()
-            .map_err(ParseError::Description)?;
-
-        let todo_at = self
-            .todo_at
-            .map(|at| Date::parse_str(&at))
-            .transpose()
-            .map_err(ParseError::TodoAt)?;
-
-        let status = self
-            .status
-            .ok_or(ParseError::Status(StatusError))
-            .and_then(|status| Status::parse_str(status.as_str()).map_err(ParseError::Status))?;
-
-        Ok(CreateTodoInput {
-            title,
-            description,
-            todo_at,
-            status,
-        })
+    pub fn parse(self) -> Result<CreateTodoInput, ValidationReport<ParseError>> {
+        let mut report = ValidationReport::new();
+
+        let title = report.check(
+            self.title
+                .ok_or(ParseError::Title(TitleError::Empty))
+                .and_then(|title| Title::new(title).map_err(ParseError::Title)),
+        );
+
+        let description = report.check(
+            self.description
+                .map(Description::new)
+                .transpose()
+                .map_err(ParseError::Description),
+        );
+
+        let todo_at = report.check(
+            self.todo_at
+                .map(|at| Date::parse_str(&at))
+                .transpose()
+                .map_err(ParseError::TodoAt),
+        );
+
+        let status = report.check(
+            self.status
+                .ok_or(ParseError::Status(StatusError))
+                .and_then(|status| Status::parse_str(status.as_str()).map_err(ParseError::Status)),
+        );
+
+        match (title, description, todo_at, status) {
+            (Some(title), Some(description), Some(todo_at), Some(status)) => Ok(CreateTodoInput {
+                title,
+                description,
+                todo_at,
+                status,
+            }),
+            _ => Err(report),
+        }
     }
 }
 
@@ -59,14 +69,14 @@ pub type CreateResponse = Result<TodoEntity, CreateResponseError>;
 #[derive(Debug, Error)]
 pub enum CreateResponseError {
     #[error(transparent)]
-    Input(ParseError),
+    Input(ValidationReport<ParseError>),
     #[error("Todo with title {0} already exists")]
     DuplicatedTitle(Title),
     #[error(transparent)]
     Internal(Box<dyn error::Error>),
 }
 
-#[derive(Debug, PartialEq, Eq, Error)]
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
 pub enum ParseError {
     #[error(transparent)]
     Title(TitleError),
@@ -77,3 +87,70 @@ pub enum ParseError {
     #[error(transparent)]
     Status(StatusError),
 }
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Title(..) => "title",
+            Self::Description(..) => "description",
+            Self::TodoAt(..) => "todoAt",
+            Self::Status(..) => "status",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Title(TitleError::Empty) => "empty",
+            Self::Title(TitleError::Length) => "too_long",
+            Self::Description(DescriptionError::Length) => "too_long",
+            Self::TodoAt(ParseDateError::Invalid) => "invalid_format",
+            Self::Status(StatusError) => "invalid",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reports_every_invalid_field() {
+        let req = CreateRequest {
+            title: Some(String::new()),
+            description: Some("d".repeat(Description::MAX_LENGTH + 1)),
+            todo_at: Some(String::from("2024-2-17")),
+            status: Some(String::from("todo")),
+        };
+
+        let report = req.parse().unwrap_err();
+        let codes = report
+            .errors()
+            .iter()
+            .map(ParseError::code)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            [
+                "title.empty",
+                "description.too_long",
+                "todoAt.invalid_format"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_works() {
+        let req = CreateRequest {
+            title: Some(String::from("Title")),
+            description: None,
+            todo_at: Some(String::from("2024-02-17")),
+            status: Some(String::from("done")),
+        };
+
+        let input = req.parse().unwrap();
+        assert_eq!(input.title.as_str(), "Title");
+        assert_eq!(input.status, Status::Done);
+    }
+}
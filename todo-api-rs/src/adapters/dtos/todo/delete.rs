@@ -2,6 +2,7 @@ use std::error;
 
 use thiserror::Error;
 
+use crate::adapters::dtos::validation::{FieldLocation, InvalidField};
 use crate::domain::types::Id;
 
 pub trait DeletePresenter {
@@ -40,3 +41,23 @@ pub enum ParseError {
     #[error("Invalid todo id format")]
     Id,
 }
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Id => "invalid_format",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+
+    fn location(&self) -> FieldLocation {
+        FieldLocation::Path
+    }
+}
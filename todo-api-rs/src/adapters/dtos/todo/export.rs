@@ -0,0 +1,45 @@
+use std::error;
+
+use futures_util::stream::BoxStream;
+use thiserror::Error;
+
+use crate::adapters::dtos::todo::list::ParseError;
+use crate::application::dtos::todo::export::{ExportTodosInput, ExportTodosOutput};
+use crate::domain::entities::todo::Title;
+
+pub trait ExportPresenter {
+    type View;
+    fn present(&self, response: ExportResponse) -> Self::View;
+}
+
+/// Filters of [`ListRequest`](super::list::ListRequest), without pagination
+/// since every matching todo is exported
+#[derive(Clone, Debug)]
+pub struct ExportRequest {
+    pub title: Option<String>,
+}
+
+impl ExportRequest {
+    pub fn parse(self) -> Result<ExportTodosInput, ParseError> {
+        let title = self
+            .title
+            .filter(|t| !t.is_empty())
+            .map(Title::new)
+            .transpose()
+            .map_err(ParseError::Title)?;
+
+        Ok(ExportTodosInput { title })
+    }
+}
+
+/// Exported file, produced piece by piece as the todos are fetched. An error
+/// ends the stream, as the response is already underway
+pub type ExportStream = BoxStream<'static, Result<String, Box<dyn error::Error>>>;
+
+pub type ExportResponse = Result<ExportTodosOutput, ExportResponseError>;
+
+#[derive(Debug, Error)]
+pub enum ExportResponseError {
+    #[error(transparent)]
+    Input(ParseError),
+}
@@ -0,0 +1,301 @@
+use super::{EntryError, EntrySource, ImportEntry, InvalidEntry, ParseError};
+use crate::adapters::dtos::todo::create::CreateRequest;
+use crate::adapters::dtos::validation::ValidationReport;
+
+/// First characters of text that exports prefix with a single quote, so
+/// spreadsheets do not evaluate it as a formula
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Field of a todo a column is mapped to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Uid,
+    Title,
+    Description,
+    Status,
+    TodoAt,
+}
+
+impl Column {
+    /// Map a column by its name in the header, ignoring case, spaces,
+    /// underscores and dashes so `todoAt`, `todo_at` and `Todo At` all match
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '_' | '-'))
+            .collect::<String>()
+            .to_lowercase();
+
+        match name.as_str() {
+            "id" | "uid" => Some(Self::Uid),
+            "title" => Some(Self::Title),
+            "description" => Some(Self::Description),
+            "status" => Some(Self::Status),
+            "todoat" => Some(Self::TodoAt),
+            _ => None,
+        }
+    }
+}
+
+/// Record of the file with the number of the line it starts at
+struct Record {
+    line: usize,
+    fields: Vec<String>,
+}
+
+/// Read the records of a CSV (RFC 4180) file whose first record is a header
+/// naming the columns. Columns that are not fields of a todo, such as
+/// `createdAt`, are ignored, and records without as many fields as the header
+/// are invalid entries
+pub(super) fn entries(content: &str) -> Result<Vec<Result<ImportEntry, InvalidEntry>>, ParseError> {
+    // spreadsheets on Windows may start the file with a byte order mark
+    let content = content.trim_start_matches('\u{feff}');
+    let mut records = records(content)?.into_iter();
+
+    let Some(header) = records.next() else {
+        return Err(ParseError::File {
+            line: 1,
+            reason: "file must start with a header",
+        });
+    };
+
+    let mut columns: Vec<Option<Column>> = Vec::with_capacity(header.fields.len());
+    for name in &header.fields {
+        let column = Column::from_name(name);
+        if column.is_some() && columns.contains(&column) {
+            return Err(ParseError::File {
+                line: header.line,
+                reason: "header must not name a column more than once",
+            });
+        }
+
+        columns.push(column);
+    }
+
+    if !columns.contains(&Some(Column::Title)) {
+        return Err(ParseError::File {
+            line: header.line,
+            reason: "header must have a title column",
+        });
+    }
+
+    let entries = records
+        .map(|record| {
+            if record.fields.len() != columns.len() {
+                let mut errors = ValidationReport::new();
+                errors.push(EntryError::FieldCount);
+                return Err(InvalidEntry {
+                    source: EntrySource {
+                        line: record.line,
+                        uid: None,
+                    },
+                    errors,
+                });
+            }
+
+            Ok(entry(&columns, record))
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+fn entry(columns: &[Option<Column>], record: Record) -> ImportEntry {
+    let mut uid = None;
+    let mut todo = CreateRequest {
+        title: None,
+        description: None,
+        todo_at: None,
+        status: Some(String::from("todo")),
+    };
+
+    for (column, value) in columns.iter().zip(record.fields) {
+        // empty cells are missing values, except for titles which are required
+        let value = Some(value).filter(|value| !value.is_empty());
+        match column {
+            Some(Column::Uid) => uid = value,
+            Some(Column::Title) => todo.title = Some(value.map(text).unwrap_or_default()),
+            Some(Column::Description) => todo.description = value.map(text),
+            Some(Column::Status) => todo.status = value.or(todo.status),
+            Some(Column::TodoAt) => todo.todo_at = value,
+            None => {}
+        }
+    }
+
+    ImportEntry {
+        source: EntrySource {
+            line: record.line,
+            uid,
+        },
+        todo,
+    }
+}
+
+/// Remove the single quote an export prefixed text with, so exported todos
+/// are imported with the text they had
+fn text(value: String) -> String {
+    match value.strip_prefix('\'') {
+        Some(text) if text.starts_with(FORMULA_PREFIXES) => text.to_owned(),
+        _ => value,
+    }
+}
+
+/// Split the content into records of fields, undoing the quoting of fields.
+/// Empty lines are skipped
+fn records(content: &str) -> Result<Vec<Record>, ParseError> {
+    let mut records = Vec::new();
+    let mut record = Record {
+        line: 1,
+        fields: Vec::new(),
+    };
+    let mut field = String::new();
+    let mut line = 1;
+    let mut chars = content.chars().peekable();
+
+    loop {
+        // start of a field
+        if chars.peek() == Some(&'"') {
+            let quote_line = line;
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    Some('"') => break,
+                    Some(c) => {
+                        line += usize::from(c == '\n');
+                        field.push(c);
+                    }
+                    None => {
+                        return Err(ParseError::File {
+                            line: quote_line,
+                            reason: "quoted field must end with a double quote",
+                        })
+                    }
+                }
+            }
+
+            if !matches!(chars.peek(), None | Some(',' | '\r' | '\n')) {
+                return Err(ParseError::File {
+                    line,
+                    reason: "quoted field must be followed by a comma or a line break",
+                });
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !matches!(c, ',' | '\r' | '\n')) {
+                field.push(c);
+            }
+        }
+
+        record.fields.push(std::mem::take(&mut field));
+
+        // end of a field
+        match chars.next() {
+            Some(',') => continue,
+            Some('\r') if chars.peek() != Some(&'\n') => {
+                return Err(ParseError::File {
+                    line,
+                    reason: "line breaks must be CRLF or LF",
+                })
+            }
+            Some('\r' | '\n') => {
+                chars.next_if_eq(&'\n');
+                line += 1;
+            }
+            _ => {}
+        }
+
+        let next = Record {
+            line,
+            fields: Vec::new(),
+        };
+        let done = std::mem::replace(&mut record, next);
+        if done.fields != [""] {
+            records.push(done);
+        }
+
+        if chars.peek().is_none() {
+            break;
+        }
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_are_mapped_by_header() {
+        let content = concat!(
+            "\u{feff}Title,Notes,todo_at,STATUS,id\r\n",
+            "\"Buy milk, eggs\",ignored,2024-02-01,in_progress,1\r\n",
+            "\r\n",
+            "\"Two\n\"\"lines\"\"\",,,,\n",
+            "Last,,,done,3",
+        );
+
+        let entries = entries(content).unwrap();
+        let fields = entries
+            .iter()
+            .map(|entry| {
+                let entry = entry.as_ref().unwrap();
+                (
+                    entry.source.line,
+                    entry.source.uid.as_deref(),
+                    entry.todo.title.as_deref(),
+                    entry.todo.description.as_deref(),
+                    entry.todo.todo_at.as_deref(),
+                    entry.todo.status.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                (
+                    2,
+                    Some("1"),
+                    Some("Buy milk, eggs"),
+                    None,
+                    Some("2024-02-01"),
+                    Some("in_progress")
+                ),
+                (4, None, Some("Two\n\"lines\""), None, None, Some("todo")),
+                (6, Some("3"), Some("Last"), None, None, Some("done")),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_files_fail() {
+        let no_title = entries("description,status\nA todo,done\n");
+        assert!(matches!(no_title, Err(ParseError::File { line: 1, .. })));
+
+        let unterminated = entries("title\nFirst\n\"Second\nThird\n");
+        assert!(matches!(
+            unterminated,
+            Err(ParseError::File { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn records_with_missing_fields_are_invalid() {
+        let entries = entries("title,status\nFirst,todo\nSecond\nThird,done,extra\n").unwrap();
+        assert!(entries[0].is_ok());
+
+        for (entry, line) in entries[1..].iter().zip([3, 4]) {
+            let invalid = entry.as_ref().unwrap_err();
+            assert_eq!(invalid.source.line, line);
+            assert_eq!(invalid.errors.errors(), [EntryError::FieldCount]);
+        }
+    }
+
+    #[test]
+    fn quotes_of_exported_formulas_are_removed() {
+        let entries = entries("title,description\n'=1+1,'note\n").unwrap();
+        let todo = &entries[0].as_ref().unwrap().todo;
+        assert_eq!(todo.title.as_deref(), Some("=1+1"));
+        assert_eq!(todo.description.as_deref(), Some("'note"));
+    }
+}
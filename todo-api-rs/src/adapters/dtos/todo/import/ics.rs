@@ -0,0 +1,235 @@
+use super::{EntrySource, ImportEntry, ParseError};
+use crate::adapters::dtos::todo::create::CreateRequest;
+
+/// Property of a component, such as `SUMMARY:Buy milk`
+struct Property {
+    name: String,
+    value: String,
+}
+
+/// Read the `VTODO` components of an iCalendar (RFC 5545) file. Properties of
+/// components nested in them, such as alarms, are ignored
+pub(super) fn entries(content: &str) -> Result<Vec<ImportEntry>, ParseError> {
+    let mut components: Vec<String> = Vec::new();
+    let mut todo: Vec<Property> = Vec::new();
+    let mut todo_line = 0;
+    let mut entries = Vec::new();
+    let mut has_calendar = false;
+
+    // editors on Windows may start the file with a byte order mark
+    let content = content.trim_start_matches('\u{feff}');
+    for (line, text) in unfold(content) {
+        let property = parse_line(&text).ok_or(ParseError::File {
+            line,
+            reason: "content lines must have a name and a value separated by a colon",
+        })?;
+
+        match property.name.as_str() {
+            "BEGIN" => {
+                let component = property.value.to_ascii_uppercase();
+                has_calendar |= component == "VCALENDAR";
+                if component == "VTODO" {
+                    todo_line = line;
+                }
+                components.push(component);
+            }
+            "END" => {
+                let component = property.value.to_ascii_uppercase();
+                if components.pop().as_ref() != Some(&component) {
+                    return Err(ParseError::File {
+                        line,
+                        reason: "component ends without having begun",
+                    });
+                }
+
+                if component == "VTODO" {
+                    entries.push(entry(todo_line, std::mem::take(&mut todo)));
+                }
+            }
+            _ if components.last().is_some_and(|c| c == "VTODO") => todo.push(property),
+            _ => {}
+        }
+    }
+
+    if !has_calendar {
+        return Err(ParseError::File {
+            line: 1,
+            reason: "file must contain a VCALENDAR component",
+        });
+    }
+
+    if !components.is_empty() {
+        return Err(ParseError::File {
+            line: content.lines().count(),
+            reason: "component begins without ending",
+        });
+    }
+
+    Ok(entries)
+}
+
+fn entry(line: usize, properties: Vec<Property>) -> ImportEntry {
+    let mut uid = None;
+    let mut todo = CreateRequest {
+        title: None,
+        description: None,
+        todo_at: None,
+        status: Some(String::from("todo")),
+    };
+
+    for Property { name, value } in properties {
+        match name.as_str() {
+            "UID" => uid = Some(value),
+            "SUMMARY" => todo.title = Some(unescape(&value)),
+            "DESCRIPTION" => todo.description = Some(unescape(&value)),
+            "DUE" => todo.todo_at = Some(due_date(value)),
+            "STATUS" => todo.status = Some(status(value)),
+            _ => {}
+        }
+    }
+
+    ImportEntry {
+        source: EntrySource { line, uid },
+        todo,
+    }
+}
+
+/// Turn a `DATE` or `DATE-TIME` value into a `YYYY-MM-DD` date, leaving values
+/// in other formats as they are so they fail validation
+fn due_date(value: String) -> String {
+    match value.get(..8) {
+        Some(ymd) if ymd.bytes().all(|b| b.is_ascii_digit()) => {
+            format!("{}-{}-{}", &ymd[..4], &ymd[4..6], &ymd[6..])
+        }
+        _ => value,
+    }
+}
+
+/// Turn a `STATUS` value into the one of a todo, leaving unsupported values
+/// such as `CANCELLED` as they are so they fail validation
+fn status(value: String) -> String {
+    match value.to_ascii_uppercase().as_str() {
+        "NEEDS-ACTION" => String::from("todo"),
+        "IN-PROCESS" => String::from("in_progress"),
+        "COMPLETED" => String::from("done"),
+        _ => value,
+    }
+}
+
+/// Join folded lines, returning each content line with the number of the
+/// line it starts at. Empty lines are skipped
+fn unfold(content: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some((_, previous))) => previous.push_str(continuation),
+            _ if line.is_empty() => {}
+            _ => lines.push((idx + 1, line.to_owned())),
+        }
+    }
+
+    lines
+}
+
+/// Split a content line into its upper cased name and its value, ignoring
+/// parameters, which may contain colons inside double quotes
+fn parse_line(line: &str) -> Option<Property> {
+    let mut quoted = false;
+    let mut name_end = None;
+    for (idx, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted && name_end.is_none() => name_end = Some(idx),
+            ':' if !quoted => {
+                let name = &line[..name_end.unwrap_or(idx)];
+                return Some(Property {
+                    name: name.trim().to_ascii_uppercase(),
+                    value: line[idx + 1..].to_owned(),
+                })
+                .filter(|property| !property.name.is_empty());
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Undo the escaping of a `TEXT` value
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n' | 'N')) => {
+                chars.next();
+                unescaped.push('\n');
+            }
+            ('\\', Some(escaped @ ('\\' | ';' | ','))) => {
+                chars.next();
+                unescaped.push(escaped);
+            }
+            _ => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn todos_are_read() {
+        let content = concat!(
+            "BEGIN:VCALENDAR\r\n",
+            "VERSION:2.0\r\n",
+            "BEGIN:VEVENT\r\n",
+            "UID:event@example.com\r\n",
+            "SUMMARY:Not a todo\r\n",
+            "END:VEVENT\r\n",
+            "BEGIN:VTODO\r\n",
+            "UID:todo@example.com\r\n",
+            "SUMMARY;LANGUAGE=\"en:us\":Buy milk\\, eggs\r\n",
+            "DESCRIPTION:A description that was folded because it was longer than sev\r\n",
+            " enty five octets\\nwith two lines\r\n",
+            "DUE;TZID=Europe/Paris:20240201T100000\r\n",
+            "STATUS:IN-PROCESS\r\n",
+            "BEGIN:VALARM\r\n",
+            "DESCRIPTION:Alarm\r\n",
+            "END:VALARM\r\n",
+            "END:VTODO\r\n",
+            "END:VCALENDAR\r\n",
+        );
+
+        let entries = entries(content).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        let ImportEntry { source, todo } = &entries[0];
+        assert_eq!(source.line, 7);
+        assert_eq!(source.uid.as_deref(), Some("todo@example.com"));
+        assert_eq!(todo.title.as_deref(), Some("Buy milk, eggs"));
+        assert_eq!(
+            todo.description.as_deref(),
+            Some("A description that was folded because it was longer than seventy five octets\nwith two lines")
+        );
+        assert_eq!(todo.todo_at.as_deref(), Some("2024-02-01"));
+        assert_eq!(todo.status.as_deref(), Some("in_progress"));
+    }
+
+    #[test]
+    fn malformed_files_fail() {
+        let not_calendar = entries("BEGIN:VTODO\nEND:VTODO\n");
+        assert!(matches!(
+            not_calendar,
+            Err(ParseError::File { line: 1, .. })
+        ));
+
+        let unbalanced = entries("BEGIN:VCALENDAR\nBEGIN:VTODO\nEND:VCALENDAR\n");
+        assert!(matches!(unbalanced, Err(ParseError::File { line: 3, .. })));
+
+        let no_value = entries("BEGIN:VCALENDAR\nSUMMARY\nEND:VCALENDAR\n");
+        assert!(matches!(no_value, Err(ParseError::File { line: 2, .. })));
+    }
+}
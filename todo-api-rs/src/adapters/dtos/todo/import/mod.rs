@@ -0,0 +1,339 @@
+mod csv;
+mod ics;
+mod txt;
+
+use std::error;
+
+use thiserror::Error;
+
+use crate::adapters::dtos::todo::create::{CreateRequest, ParseError as TodoParseError};
+use crate::adapters::dtos::validation::{FieldLocation, InvalidField, ValidationReport};
+use crate::application::dtos::todo::import::{ImportTodoInput, ImportTodosInput, SkipReason};
+use crate::application::repositories::todo::ImportOrigin;
+use crate::domain::entities::todo::{Description, Title, TodoEntity};
+
+pub trait ImportPresenter {
+    type View;
+    fn present(&self, response: ImportResponse) -> Self::View;
+}
+
+/// Format of an imported file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// CSV (RFC 4180) file, whose header names the columns of its records
+    Csv,
+    /// iCalendar (RFC 5545) file, whose `VTODO` components are imported
+    Ics,
+    /// todo.txt file, whose lines are imported
+    Txt,
+}
+
+impl ImportFormat {
+    /// Name of the format, also remembered with the entries it imported
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ics => "ics",
+            Self::Txt => "txt",
+        }
+    }
+
+    /// Whether every entry must have a unique identifier, which is optional
+    /// in CSV and todo.txt files
+    fn requires_uid(self) -> bool {
+        match self {
+            Self::Csv | Self::Txt => false,
+            Self::Ics => true,
+        }
+    }
+}
+
+/// What to do with titles and descriptions longer than allowed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LengthPolicy {
+    /// Report the entry as invalid
+    #[default]
+    Reject,
+    /// Cut the text to the maximum length
+    Truncate,
+}
+
+impl LengthPolicy {
+    pub fn parse_str(value: &str) -> Result<Self, ParseError> {
+        match value {
+            "reject" => Ok(Self::Reject),
+            "truncate" => Ok(Self::Truncate),
+            _ => Err(ParseError::Policy),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ImportRequest {
+    pub format: ImportFormat,
+    pub content: String,
+    pub policy: Option<String>,
+    pub dry_run: bool,
+}
+
+/// Entry of an imported file, with the fields of its todo as they were found
+#[derive(Clone, Debug)]
+struct ImportEntry {
+    source: EntrySource,
+    todo: CreateRequest,
+}
+
+/// Where an entry is in the imported file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntrySource {
+    /// Line the entry starts at
+    pub line: usize,
+    /// Unique identifier of the entry, such as the UID of a `VTODO`
+    pub uid: Option<String>,
+}
+
+/// Entries that can be imported, with the source of each todo of the input in
+/// the same order, and the entries that failed validation
+#[derive(Clone, Debug)]
+pub struct ParsedImport {
+    pub input: ImportTodosInput,
+    pub sources: Vec<EntrySource>,
+    pub invalid: Vec<InvalidEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidEntry {
+    pub source: EntrySource,
+    pub errors: ValidationReport<EntryError>,
+}
+
+impl ImportRequest {
+    pub fn parse(self) -> Result<ParsedImport, ParseError> {
+        let policy = self
+            .policy
+            .filter(|policy| !policy.is_empty())
+            .map(|policy| LengthPolicy::parse_str(&policy))
+            .transpose()?
+            .unwrap_or_default();
+
+        let entries = match self.format {
+            ImportFormat::Csv => csv::entries(&self.content)?,
+            ImportFormat::Ics => ics::entries(&self.content)?.into_iter().map(Ok).collect(),
+            ImportFormat::Txt => txt::entries(&self.content).into_iter().map(Ok).collect(),
+        };
+
+        let mut parsed = ParsedImport {
+            input: ImportTodosInput {
+                todos: Vec::new(),
+                dry_run: self.dry_run,
+            },
+            sources: Vec::new(),
+            invalid: Vec::new(),
+        };
+        for entry in entries {
+            match entry.and_then(|entry| entry.parse(self.format, policy)) {
+                Ok((source, todo)) => {
+                    parsed.sources.push(source);
+                    parsed.input.todos.push(todo);
+                }
+                Err(invalid) => parsed.invalid.push(invalid),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+impl ImportEntry {
+    fn parse(
+        self,
+        format: ImportFormat,
+        policy: LengthPolicy,
+    ) -> Result<(EntrySource, ImportTodoInput), InvalidEntry> {
+        let mut todo = self.todo;
+        if policy == LengthPolicy::Truncate {
+            todo.title = todo.title.map(|title| truncate(title, Title::MAX_LENGTH));
+            todo.description = todo
+                .description
+                .map(|description| truncate(description, Description::MAX_LENGTH));
+        }
+
+        let mut errors = ValidationReport::new();
+        let uid = self.source.uid.clone().filter(|uid| !uid.is_empty());
+        let missing_uid = uid.is_none() && format.requires_uid();
+        if missing_uid {
+            errors.push(EntryError::Uid);
+        }
+
+        let todo = todo.parse().map_err(|report| {
+            for err in report.into_errors() {
+                errors.push(EntryError::Todo(err));
+            }
+        });
+
+        match todo {
+            Ok(todo) if !missing_uid => {
+                let origin = uid.map(|uid| ImportOrigin {
+                    format: format.name(),
+                    uid,
+                });
+                Ok((self.source, ImportTodoInput { origin, todo }))
+            }
+            _ => Err(InvalidEntry {
+                source: self.source,
+                errors,
+            }),
+        }
+    }
+}
+
+/// Cut `text` to at most `max_len` bytes, without splitting a character
+fn truncate(mut text: String, max_len: usize) -> String {
+    if text.len() > max_len {
+        let end = (0..=max_len)
+            .rev()
+            .find(|&idx| text.is_char_boundary(idx))
+            .unwrap_or(0);
+        text.truncate(end);
+    }
+
+    text
+}
+
+/// Outcome of every entry of an imported file
+#[derive(Clone, Debug)]
+pub struct ImportReport {
+    /// Whether nothing was created, the report telling what importing would do
+    pub dry_run: bool,
+    pub created: Vec<(EntrySource, TodoEntity)>,
+    pub skipped: Vec<(EntrySource, SkipReason)>,
+    pub invalid: Vec<InvalidEntry>,
+}
+
+pub type ImportResponse = Result<ImportReport, ImportResponseError>;
+
+#[derive(Debug, Error)]
+pub enum ImportResponseError {
+    #[error(transparent)]
+    Input(ParseError),
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("Policy must be one of reject or truncate")]
+    Policy,
+    #[error("Invalid file at line {line}: {reason}")]
+    File { line: usize, reason: &'static str },
+}
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Policy => "policy",
+            Self::File { .. } => "file",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Policy => "invalid",
+            Self::File { .. } => "invalid_format",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+
+    fn location(&self) -> FieldLocation {
+        match self {
+            Self::Policy => FieldLocation::Query,
+            Self::File { .. } => FieldLocation::Body,
+        }
+    }
+}
+
+/// Error of a single entry of an imported file
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum EntryError {
+    #[error("Entry must have a unique identifier")]
+    Uid,
+    #[error("Record must have as many fields as the header")]
+    FieldCount,
+    #[error(transparent)]
+    Todo(TodoParseError),
+}
+
+impl InvalidField for EntryError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Uid => "uid",
+            Self::FieldCount => "fields",
+            Self::Todo(err) => err.field(),
+        }
+    }
+
+    fn code(&self) -> String {
+        match self {
+            Self::Uid => format!("{}.missing", self.field()),
+            Self::FieldCount => format!("{}.invalid_count", self.field()),
+            Self::Todo(err) => err.code(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(content: &str, policy: Option<&str>) -> ImportRequest {
+        ImportRequest {
+            format: ImportFormat::Ics,
+            content: content.to_owned(),
+            policy: policy.map(str::to_owned),
+            dry_run: false,
+        }
+    }
+
+    fn calendar(summary: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:1@example.com\r\nSUMMARY:{summary}\r\nEND:VTODO\r\nEND:VCALENDAR\r\n"
+        )
+    }
+
+    #[test]
+    fn long_titles_are_rejected_by_default() {
+        let parsed = request(&calendar(&"é".repeat(40)), None).parse().unwrap();
+        assert!(parsed.input.todos.is_empty());
+
+        let codes = parsed.invalid[0]
+            .errors
+            .errors()
+            .iter()
+            .map(EntryError::code)
+            .collect::<Vec<_>>();
+        let source = &parsed.invalid[0].source;
+        assert_eq!(source.uid.as_deref(), Some("1@example.com"));
+        assert_eq!(source.line, 2);
+        assert_eq!(codes, ["title.too_long"]);
+    }
+
+    #[test]
+    fn long_titles_are_truncated_on_request() {
+        let parsed = request(&calendar(&"é".repeat(40)), Some("truncate"))
+            .parse()
+            .unwrap();
+        assert!(parsed.invalid.is_empty());
+
+        let title = parsed.input.todos[0].todo.title.as_str();
+        assert_eq!(title, "é".repeat(Title::MAX_LENGTH / 2));
+    }
+
+    #[test]
+    fn unknown_policy_fails() {
+        let err = request(&calendar("Title"), Some("ignore"))
+            .parse()
+            .unwrap_err();
+        assert_eq!(err, ParseError::Policy);
+    }
+}
@@ -0,0 +1,184 @@
+use super::{EntrySource, ImportEntry};
+use crate::adapters::dtos::todo::create::CreateRequest;
+use crate::domain::types::Date;
+
+/// Priority of the todos in progress, the only status other than done that
+/// todo.txt can tell apart
+const IN_PROGRESS_PRIORITY: &str = "(A)";
+const DUE_KEY: &str = "due:";
+const ID_KEY: &str = "id:";
+
+/// Read the lines of a todo.txt file, each one being a todo. Empty lines are
+/// skipped
+pub(super) fn entries(content: &str) -> Vec<ImportEntry> {
+    // editors on Windows may start the file with a byte order mark
+    let content = content.trim_start_matches('\u{feff}');
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(idx, text)| entry(idx + 1, text))
+        .collect()
+}
+
+/// Read a line such as `x 2024-02-03 2024-01-31 Buy milk due:2024-02-01`.
+///
+/// Completed lines are done todos, lines with the `(A)` priority are todos in
+/// progress, and other ones are todos, ignoring the other priorities as well as
+/// the completion and creation dates. Only the last `due:` and `id:` keys are
+/// read, an empty one meaning there is none, so titles with such words are
+/// read back as they were written. Projects, contexts, unknown keys and the
+/// other `due:` and `id:` words are kept in the title
+fn entry(line: usize, text: &str) -> ImportEntry {
+    let mut words = text.split_whitespace().peekable();
+    let completed = words.next_if_eq(&"x").is_some();
+    let status = if completed {
+        words.next_if(|word| is_date(word));
+        "done"
+    } else {
+        match words.next_if(|word| is_priority(word)) {
+            Some(IN_PROGRESS_PRIORITY) => "in_progress",
+            _ => "todo",
+        }
+    };
+    words.next_if(|word| is_date(word));
+
+    let words = words.collect::<Vec<_>>();
+    let last_key = |key: &str| words.iter().rposition(|word| word.starts_with(key));
+    let due = last_key(DUE_KEY);
+    let id = last_key(ID_KEY);
+    let value = |position: Option<usize>, key: &str| {
+        position
+            .and_then(|position| words[position].strip_prefix(key))
+            .filter(|value| !value.is_empty())
+            .map(str::to_owned)
+    };
+
+    let uid = value(id, ID_KEY);
+    let todo = CreateRequest {
+        title: Some(
+            words
+                .iter()
+                .enumerate()
+                .filter(|(position, _)| Some(*position) != due && Some(*position) != id)
+                .map(|(_, word)| *word)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        description: None,
+        todo_at: value(due, DUE_KEY),
+        status: Some(String::from(status)),
+    };
+
+    ImportEntry {
+        source: EntrySource { line, uid },
+        todo,
+    }
+}
+
+fn is_date(word: &str) -> bool {
+    Date::parse_str(word).is_ok()
+}
+
+/// Whether the word is a priority such as `(A)`
+fn is_priority(word: &str) -> bool {
+    match word.as_bytes() {
+        [b'(', priority, b')'] => priority.is_ascii_uppercase(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::presenters::txt::todo_line;
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::entities::todo::Status;
+
+    #[test]
+    fn todos_round_trip() {
+        let due = Date::from(time::macros::date!(2024 - 02 - 01));
+        let mut todos = [
+            todo("Call mom +family @phone", Status::Todo),
+            todo("Buy milk", Status::InProgress),
+            todo("2024 taxes", Status::Done),
+        ];
+        for todo in &mut todos[1..] {
+            todo.reschedule(Some(due));
+        }
+
+        let content = todos.iter().map(todo_line).collect::<String>();
+        let entries = entries(&content);
+        assert_eq!(entries.len(), todos.len());
+
+        for (todo, entry) in todos.iter().zip(entries) {
+            let id = todo.id().to_string();
+            assert_eq!(entry.source.uid.as_deref(), Some(id.as_str()));
+
+            let input = entry.todo.parse().unwrap();
+            assert_eq!(&input.title, todo.title());
+            assert_eq!(&input.status, todo.status());
+            assert_eq!(input.todo_at, todo.todo_at());
+        }
+    }
+
+    #[test]
+    fn titles_with_keys_round_trip() {
+        let due = Date::from(time::macros::date!(2024 - 02 - 01));
+        let mut todos = [
+            todo("Renew id:card due:friday", Status::Todo),
+            todo("Ask status:pending due:2024-03-01", Status::InProgress),
+            todo("Check due:", Status::Done),
+        ];
+        todos[0].reschedule(Some(due));
+
+        let content = todos.iter().map(todo_line).collect::<String>();
+        for (todo, entry) in todos.iter().zip(entries(&content)) {
+            let id = todo.id().to_string();
+            assert_eq!(entry.source.uid.as_deref(), Some(id.as_str()));
+
+            let input = entry.todo.parse().unwrap();
+            assert_eq!(&input.title, todo.title());
+            assert_eq!(&input.status, todo.status());
+            assert_eq!(input.todo_at, todo.todo_at());
+        }
+    }
+
+    #[test]
+    fn lines_are_read() {
+        let content = concat!(
+            "(A) 2024-01-31 Call mom +family @phone due:2024-02-01 owner:ann\n",
+            "\n",
+            "x 2024-02-03 (B) Pay rent status:todo\n",
+            "(B) 2024-01-31 Water plants due:2024-02-01 due:2024-02-02\n",
+            "(a) lowercase is not a priority\n",
+        );
+
+        let entries = entries(content);
+        let fields = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.source.line,
+                    entry.todo.title.as_deref().unwrap(),
+                    entry.todo.todo_at.as_deref(),
+                    entry.todo.status.as_deref().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                (
+                    1,
+                    "Call mom +family @phone owner:ann",
+                    Some("2024-02-01"),
+                    "in_progress"
+                ),
+                (3, "(B) Pay rent status:todo", None, "done"),
+                (4, "Water plants due:2024-02-01", Some("2024-02-02"), "todo"),
+                (5, "(a) lowercase is not a priority", None, "todo"),
+            ]
+        );
+    }
+}
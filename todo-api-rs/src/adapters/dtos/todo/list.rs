@@ -3,6 +3,7 @@ use std::num::NonZeroU32;
 
 use thiserror::Error;
 
+use crate::adapters::dtos::validation::{FieldLocation, InvalidField};
 use crate::application::dtos::todo::list::{ListTodosInput, TodosList};
 use crate::domain::entities::todo::{Title, TitleError};
 
@@ -57,3 +58,28 @@ pub enum ParseError {
     #[error(transparent)]
     Title(TitleError),
 }
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Page => "page",
+            Self::PerPage => "perPage",
+            Self::Title(..) => "title",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Page => "invalid",
+            Self::PerPage => "invalid",
+            Self::Title(TitleError::Empty) => "empty",
+            Self::Title(TitleError::Length) => "too_long",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+
+    fn location(&self) -> FieldLocation {
+        FieldLocation::Query
+    }
+}
@@ -2,6 +2,7 @@ use std::error;
 
 use thiserror::Error;
 
+use crate::adapters::dtos::validation::{FieldLocation, InvalidField, ValidationReport};
 use crate::application::dtos::todo::update::UpdateTodoInput;
 use crate::domain::entities::todo::{
     Description, DescriptionError, Status, StatusError, Title, TitleError,
@@ -23,43 +24,54 @@ pub struct UpdateRequest {
 }
 
 impl UpdateRequest {
-    pub fn parse(self) -> Result<UpdateTodoInput, ParseError> {
-        let id = self
-            .id
-            .filter(|id| !id.is_empty())
-            .ok_or(ParseError::Id)
-            .map(|id| Id::parse_str(&id))?
-            .or(Err(ParseError::Id))?;
-
-        let title = self
-            .title
-            .ok_or(ParseError::Title(TitleError::Empty))
-            .and_then(|t| Title::new(t).map_err(ParseError::Title))?;
-
-        let description = self
-            .description
-            .map(Description::new)
-            .transpose()
-            .map_err(ParseError::Description)?;
-
-        let status = self
-            .status
-            .ok_or(ParseError::Status(StatusError))
-            .and_then(|status| Status::parse_str(status.as_str()).map_err(ParseError::Status))?;
-
-        let todo_at = self
-            .todo_at
-            .map(|at| Date::parse_str(&at))
-            .transpose()
-            .map_err(ParseError::TodoAt)?;
-
-        Ok(UpdateTodoInput {
-            id,
-            title,
-            description,
-            todo_at,
-            status,
-        })
+    pub fn parse(self) -> Result<UpdateTodoInput, ValidationReport<ParseError>> {
+        let mut report = ValidationReport::new();
+
+        let id = report.check(
+            self.id
+                .filter(|id| !id.is_empty())
+                .ok_or(ParseError::Id)
+                .and_then(|id| Id::parse_str(&id).or(Err(ParseError::Id))),
+        );
+
+        let title = report.check(
+            self.title
+                .ok_or(ParseError::Title(TitleError::Empty))
+                .and_then(|t| Title::new(t).map_err(ParseError::Title)),
+        );
+
+        let description = report.check(
+            self.description
+                .map(Description::new)
+                .transpose()
+                .map_err(ParseError::Description),
+        );
+
+        let status = report.check(
+            self.status
+                .ok_or(ParseError::Status(StatusError))
+                .and_then(|status| Status::parse_str(status.as_str()).map_err(ParseError::Status)),
+        );
+
+        let todo_at = report.check(
+            self.todo_at
+                .map(|at| Date::parse_str(&at))
+                .transpose()
+                .map_err(ParseError::TodoAt),
+        );
+
+        match (id, title, description, status, todo_at) {
+            (Some(id), Some(title), Some(description), Some(status), Some(todo_at)) => {
+                Ok(UpdateTodoInput {
+                    id,
+                    title,
+                    description,
+                    todo_at,
+                    status,
+                })
+            }
+            _ => Err(report),
+        }
     }
 }
 
@@ -68,9 +80,11 @@ pub type UpdateResponse = Result<(), UpdateResponseError>;
 #[derive(Debug, Error)]
 pub enum UpdateResponseError {
     #[error(transparent)]
-    Input(ParseError),
+    Input(ValidationReport<ParseError>),
     #[error("Todo with id {0} not found")]
     NotFound(Id),
+    #[error("Todo with id {0} was updated concurrently, retry with its current version")]
+    Conflict(Id),
     #[error("Todo with title {0} already exists")]
     DuplicatedTitle(Title),
     #[error(transparent)]
@@ -90,3 +104,35 @@ pub enum ParseError {
     #[error(transparent)]
     Status(StatusError),
 }
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Title(..) => "title",
+            Self::Description(..) => "description",
+            Self::TodoAt(..) => "todoAt",
+            Self::Status(..) => "status",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Id => "invalid_format",
+            Self::Title(TitleError::Empty) => "empty",
+            Self::Title(TitleError::Length) => "too_long",
+            Self::Description(DescriptionError::Length) => "too_long",
+            Self::TodoAt(ParseDateError::Invalid) => "invalid_format",
+            Self::Status(StatusError) => "invalid",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+
+    fn location(&self) -> FieldLocation {
+        match self {
+            Self::Id => FieldLocation::Path,
+            _ => FieldLocation::Body,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::error;
+use std::fmt;
+
+/// Error of a single request field
+pub trait InvalidField: fmt::Display {
+    /// Name of the request field that failed parsing
+    fn field(&self) -> &'static str;
+
+    /// Machine readable code of the error, such as `title.empty`
+    fn code(&self) -> String;
+
+    /// Part of the request the field is read from, the body unless it's a
+    /// path or query parameter
+    fn location(&self) -> FieldLocation {
+        FieldLocation::Body
+    }
+}
+
+/// Part of the request a field is read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldLocation {
+    Body,
+    Path,
+    Query,
+}
+
+impl fmt::Display for FieldLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Body => f.write_str("body"),
+            Self::Path => f.write_str("path"),
+            Self::Query => f.write_str("query"),
+        }
+    }
+}
+
+/// Every error found while parsing the fields of a request, so clients can
+/// fix all of them at once instead of one per round trip
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationReport<E> {
+    errors: Vec<E>,
+}
+
+impl<E> ValidationReport<E> {
+    pub fn new() -> Self {
+        Self { errors: Vec::new() }
+    }
+
+    /// Record the error of `result`, if any, returning its successful value
+    pub fn check<T>(&mut self, result: Result<T, E>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.errors.push(err);
+                None
+            }
+        }
+    }
+
+    pub fn push(&mut self, err: E) {
+        self.errors.push(err);
+    }
+
+    pub fn errors(&self) -> &[E] {
+        &self.errors
+    }
+
+    pub fn into_errors(self) -> Vec<E> {
+        self.errors
+    }
+}
+
+impl<E> Default for ValidationReport<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ValidationReport<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, err) in self.errors.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("; ")?;
+            }
+            err.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> error::Error for ValidationReport<E> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_collects_errors() {
+        let mut report = ValidationReport::<&str>::new();
+        assert_eq!(report.check(Ok::<_, &str>(1)), Some(1));
+        assert!(report.errors().is_empty());
+
+        assert_eq!(report.check(Err::<u32, _>("first")), None);
+        assert_eq!(report.check(Err::<u32, _>("second")), None);
+        assert_eq!(report.errors(), ["first", "second"]);
+        assert_eq!(report.to_string(), "first; second");
+    }
+}
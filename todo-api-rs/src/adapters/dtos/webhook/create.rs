@@ -0,0 +1,140 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::adapters::dtos::validation::{InvalidField, ValidationReport};
+use crate::application::dtos::webhook::create::CreateWebhookInput;
+use crate::domain::entities::webhook::{
+    WebhookEntity, WebhookEvent, WebhookEventError, WebhookSecret, WebhookSecretError, WebhookUrl,
+    WebhookUrlError,
+};
+
+pub trait CreatePresenter {
+    type View;
+    fn present(&self, response: CreateResponse) -> Self::View;
+}
+
+#[derive(Clone, Debug)]
+pub struct CreateRequest {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub secret: Option<String>,
+}
+
+impl CreateRequest {
+    pub fn parse(self) -> Result<CreateWebhookInput, ValidationReport<ParseError>> {
+        let mut report = ValidationReport::new();
+
+        let url = report.check(
+            self.url
+                .ok_or(ParseError::Url(WebhookUrlError::Invalid))
+                .and_then(|url| WebhookUrl::new(url).map_err(ParseError::Url)),
+        );
+
+        let events = report.check(
+            self.events
+                .unwrap_or_default()
+                .iter()
+                .map(|event| WebhookEvent::parse_str(event).map_err(ParseError::Events))
+                .collect::<Result<Vec<_>, _>>(),
+        );
+
+        let secret = report.check(
+            self.secret
+                .ok_or(ParseError::Secret(WebhookSecretError::Length))
+                .and_then(|secret| WebhookSecret::new(secret).map_err(ParseError::Secret)),
+        );
+
+        match (url, events, secret) {
+            (Some(url), Some(events), Some(secret)) => Ok(CreateWebhookInput {
+                url,
+                events,
+                secret,
+            }),
+            _ => Err(report),
+        }
+    }
+}
+
+pub type CreateResponse = Result<WebhookEntity, CreateResponseError>;
+
+#[derive(Debug, Error)]
+pub enum CreateResponseError {
+    #[error(transparent)]
+    Input(ValidationReport<ParseError>),
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error(transparent)]
+    Url(WebhookUrlError),
+    #[error(transparent)]
+    Events(WebhookEventError),
+    #[error(transparent)]
+    Secret(WebhookSecretError),
+}
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Url(..) => "url",
+            Self::Events(..) => "events",
+            Self::Secret(..) => "secret",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Url(WebhookUrlError::Invalid) => "invalid_format",
+            Self::Url(WebhookUrlError::Length) => "too_long",
+            Self::Events(WebhookEventError) => "invalid",
+            Self::Secret(WebhookSecretError::Length) => "invalid_length",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reports_every_invalid_field() {
+        let req = CreateRequest {
+            url: Some(String::from("example.com")),
+            events: Some(vec![String::from("todo.created"), String::from("todo")]),
+            secret: None,
+        };
+
+        let report = req.parse().unwrap_err();
+        let codes = report
+            .errors()
+            .iter()
+            .map(ParseError::code)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            [
+                "url.invalid_format",
+                "events.invalid",
+                "secret.invalid_length"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_works() {
+        let req = CreateRequest {
+            url: Some(String::from("https://example.com/hooks")),
+            events: None,
+            secret: Some(String::from("0123456789abcdef")),
+        };
+
+        let input = req.parse().unwrap();
+        assert_eq!(input.url.as_str(), "https://example.com/hooks");
+        assert!(input.events.is_empty());
+    }
+}
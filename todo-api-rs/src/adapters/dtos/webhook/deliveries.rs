@@ -0,0 +1,83 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::adapters::dtos::validation::{FieldLocation, InvalidField};
+use crate::application::dtos::webhook::deliveries::ListDeliveriesInput;
+use crate::domain::entities::webhook::{DeliveryEntity, DeliveryStatus, DeliveryStatusError};
+use crate::domain::types::Id;
+
+pub trait DeliveriesPresenter {
+    type View;
+    fn present(&self, response: DeliveriesResponse) -> Self::View;
+}
+
+#[derive(Clone, Debug)]
+pub struct DeliveriesRequest {
+    pub webhook_id: Option<String>,
+    pub status: Option<String>,
+}
+
+impl DeliveriesRequest {
+    pub fn parse(self) -> Result<ListDeliveriesInput, ParseError> {
+        let webhook_id = self
+            .webhook_id
+            .filter(|id| !id.is_empty())
+            .ok_or(ParseError::Id)
+            .and_then(|id| Id::parse_str(&id).or(Err(ParseError::Id)))?;
+
+        let status = self
+            .status
+            .filter(|status| !status.is_empty())
+            .map(|status| DeliveryStatus::parse_str(&status))
+            .transpose()
+            .map_err(ParseError::Status)?;
+
+        Ok(ListDeliveriesInput { webhook_id, status })
+    }
+}
+
+pub type DeliveriesResponse = Result<Vec<DeliveryEntity>, DeliveriesResponseError>;
+
+#[derive(Debug, Error)]
+pub enum DeliveriesResponseError {
+    #[error(transparent)]
+    Input(ParseError),
+    #[error("Webhook with id {0} not found")]
+    NotFound(Id),
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("Invalid webhook id format")]
+    Id,
+    #[error(transparent)]
+    Status(DeliveryStatusError),
+}
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Status(..) => "status",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Id => "invalid_format",
+            Self::Status(DeliveryStatusError) => "invalid",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+
+    fn location(&self) -> FieldLocation {
+        match self {
+            Self::Id => FieldLocation::Path,
+            Self::Status(..) => FieldLocation::Query,
+        }
+    }
+}
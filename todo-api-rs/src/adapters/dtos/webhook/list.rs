@@ -0,0 +1,18 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::domain::entities::webhook::WebhookEntity;
+
+pub trait ListPresenter {
+    type View;
+    fn present(&self, response: ListResponse) -> Self::View;
+}
+
+pub type ListResponse = Result<Vec<WebhookEntity>, ListResponseError>;
+
+#[derive(Debug, Error)]
+pub enum ListResponseError {
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
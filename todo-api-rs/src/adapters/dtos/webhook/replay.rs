@@ -0,0 +1,63 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::adapters::dtos::validation::{FieldLocation, InvalidField};
+use crate::domain::types::Id;
+
+pub trait ReplayPresenter {
+    type View;
+    fn present(&self, response: ReplayResponse) -> Self::View;
+}
+
+#[derive(Clone, Debug)]
+pub struct ReplayRequest {
+    pub id: Option<String>,
+}
+
+impl ReplayRequest {
+    pub fn parse(self) -> Result<Id, ParseError> {
+        self.id
+            .filter(|id| !id.is_empty())
+            .ok_or(ParseError::Id)
+            .and_then(|id| Id::parse_str(&id).or(Err(ParseError::Id)))
+    }
+}
+
+pub type ReplayResponse = Result<(), ReplayResponseError>;
+
+#[derive(Debug, Error)]
+pub enum ReplayResponseError {
+    #[error(transparent)]
+    Input(ParseError),
+    #[error("Webhook delivery with id {0} not found")]
+    NotFound(Id),
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("Invalid webhook delivery id format")]
+    Id,
+}
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Id => "invalid_format",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+
+    fn location(&self) -> FieldLocation {
+        FieldLocation::Path
+    }
+}
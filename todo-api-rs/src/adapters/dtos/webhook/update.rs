@@ -0,0 +1,166 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::adapters::dtos::validation::{FieldLocation, InvalidField, ValidationReport};
+use crate::application::dtos::webhook::update::UpdateWebhookInput;
+use crate::domain::entities::webhook::{
+    WebhookEntity, WebhookEvent, WebhookEventError, WebhookSecret, WebhookSecretError, WebhookUrl,
+    WebhookUrlError,
+};
+use crate::domain::types::Id;
+
+pub trait UpdatePresenter {
+    type View;
+    fn present(&self, response: UpdateResponse) -> Self::View;
+}
+
+#[derive(Clone, Debug)]
+pub struct UpdateRequest {
+    pub id: Option<String>,
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub secret: Option<String>,
+}
+
+impl UpdateRequest {
+    pub fn parse(self) -> Result<UpdateWebhookInput, ValidationReport<ParseError>> {
+        let mut report = ValidationReport::new();
+
+        let id = report.check(
+            self.id
+                .filter(|id| !id.is_empty())
+                .ok_or(ParseError::Id)
+                .and_then(|id| Id::parse_str(&id).or(Err(ParseError::Id))),
+        );
+
+        let url = report.check(
+            self.url
+                .ok_or(ParseError::Url(WebhookUrlError::Invalid))
+                .and_then(|url| WebhookUrl::new(url).map_err(ParseError::Url)),
+        );
+
+        let events = report.check(
+            self.events
+                .unwrap_or_default()
+                .iter()
+                .map(|event| WebhookEvent::parse_str(event).map_err(ParseError::Events))
+                .collect::<Result<Vec<_>, _>>(),
+        );
+
+        let secret = report.check(
+            self.secret
+                .ok_or(ParseError::Secret(WebhookSecretError::Length))
+                .and_then(|secret| WebhookSecret::new(secret).map_err(ParseError::Secret)),
+        );
+
+        match (id, url, events, secret) {
+            (Some(id), Some(url), Some(events), Some(secret)) => Ok(UpdateWebhookInput {
+                id,
+                url,
+                events,
+                secret,
+            }),
+            _ => Err(report),
+        }
+    }
+}
+
+pub type UpdateResponse = Result<WebhookEntity, UpdateResponseError>;
+
+#[derive(Debug, Error)]
+pub enum UpdateResponseError {
+    #[error(transparent)]
+    Input(ValidationReport<ParseError>),
+    #[error("Webhook with id {0} not found")]
+    NotFound(Id),
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("Invalid webhook id format")]
+    Id,
+    #[error(transparent)]
+    Url(WebhookUrlError),
+    #[error(transparent)]
+    Events(WebhookEventError),
+    #[error(transparent)]
+    Secret(WebhookSecretError),
+}
+
+impl InvalidField for ParseError {
+    fn field(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Url(..) => "url",
+            Self::Events(..) => "events",
+            Self::Secret(..) => "secret",
+        }
+    }
+
+    fn code(&self) -> String {
+        let kind = match self {
+            Self::Id => "invalid_format",
+            Self::Url(WebhookUrlError::Invalid) => "invalid_format",
+            Self::Url(WebhookUrlError::Length) => "too_long",
+            Self::Events(WebhookEventError) => "invalid",
+            Self::Secret(WebhookSecretError::Length) => "invalid_length",
+        };
+
+        format!("{}.{kind}", self.field())
+    }
+
+    fn location(&self) -> FieldLocation {
+        match self {
+            Self::Id => FieldLocation::Path,
+            Self::Url(..) | Self::Events(..) | Self::Secret(..) => FieldLocation::Body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reports_every_invalid_field() {
+        let req = UpdateRequest {
+            id: Some(String::from("webhook")),
+            url: Some(String::from("http://example.com/hooks")),
+            events: None,
+            secret: Some(String::from("0123456789abcdef")),
+        };
+
+        let report = req.parse().unwrap_err();
+        let errors = report
+            .errors()
+            .iter()
+            .map(|err| (err.code(), err.location()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                (String::from("id.invalid_format"), FieldLocation::Path),
+                (String::from("url.invalid_format"), FieldLocation::Body),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_works() {
+        let id = Id::new();
+        let req = UpdateRequest {
+            id: Some(id.to_string()),
+            url: Some(String::from("https://example.com/hooks")),
+            events: Some(vec![String::from("todo.deleted")]),
+            secret: Some(String::from("0123456789abcdef")),
+        };
+
+        let input = req.parse().unwrap();
+        assert_eq!(input.id, id);
+        assert_eq!(input.url.as_str(), "https://example.com/hooks");
+        assert_eq!(input.events, [WebhookEvent::TodoDeleted]);
+    }
+}
@@ -0,0 +1,5 @@
+mod record;
+mod todo;
+
+pub use record::*;
+pub use todo::*;
\ No newline at end of file
@@ -0,0 +1,37 @@
+pub const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+
+/// Format a CSV (RFC 4180) record ending with CRLF, quoting the fields that
+/// contain a comma, a double quote or a line break
+pub fn record<'a>(fields: impl IntoIterator<Item = &'a str>) -> String {
+    let mut output = String::new();
+    for (idx, field) in fields.into_iter().enumerate() {
+        if idx > 0 {
+            output.push(',');
+        }
+
+        if field.contains([',', '"', '\r', '\n']) {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+
+    output.push_str("\r\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fields_are_quoted_when_needed() {
+        let record = record(["plain", "a, b", "say \"hi\"", "two\nlines", ""]);
+        assert_eq!(
+            record,
+            "plain,\"a, b\",\"say \"\"hi\"\"\",\"two\nlines\",\r\n"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::borrow::Cow;
+
+use futures_util::stream;
+use futures_util::{StreamExt, TryStreamExt};
+
+use super::record::record;
+use crate::adapters::dtos::todo::export::{
+    ExportPresenter, ExportResponse, ExportResponseError, ExportStream,
+};
+use crate::adapters::presenters::json::error::{ErrorFormat, JsonError};
+use crate::application::dtos::todo::export::ExportTodosError;
+use crate::domain::entities::todo::TodoEntity;
+
+/// Columns of exported todos, named like the fields of their JSON view
+const HEADER: [&str; 7] = [
+    "id",
+    "title",
+    "description",
+    "status",
+    "todoAt",
+    "createdAt",
+    "updatedAt",
+];
+
+/// Presents todos as CSV (RFC 4180) records, a header and then one per todo,
+/// while errors keep being presented as JSON
+#[derive(Clone, Debug)]
+pub struct CsvTodoPresenter {
+    errors: ErrorFormat,
+}
+
+impl CsvTodoPresenter {
+    pub const fn new() -> Self {
+        Self {
+            errors: ErrorFormat::Content,
+        }
+    }
+
+    /// Set the shape of error bodies, which defaults to [`ErrorFormat::Content`]
+    pub fn with_error_format(mut self, errors: ErrorFormat) -> Self {
+        self.errors = errors;
+        self
+    }
+}
+
+impl ExportPresenter for CsvTodoPresenter {
+    type View = Result<ExportStream, JsonError>;
+
+    fn present(&self, response: ExportResponse) -> Self::View {
+        response
+            .map(|todos| {
+                let rows = todos.map_ok(|todo| todo_record(&todo)).map_err(|err| {
+                    let ExportTodosError::Internal(src) = err;
+                    src
+                });
+
+                stream::once(async { Ok(record(HEADER)) })
+                    .chain(rows)
+                    .boxed()
+            })
+            .map_err(|err| match err {
+                ExportResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
+            })
+    }
+}
+
+/// First characters that make spreadsheets evaluate a cell as a formula
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+fn todo_record(todo: &TodoEntity) -> String {
+    let id = todo.id().to_string();
+    let status = todo.status().to_string();
+    let todo_at = todo.todo_at().map(|at| at.to_ymd()).unwrap_or_default();
+    let created_at = todo.created_at().to_rfc3339();
+    let updated_at = todo.updated_at().to_rfc3339();
+
+    let title = text_cell(todo.title().as_str());
+    let description = todo.description().map(|d| text_cell(d.as_str()));
+
+    record([
+        id.as_str(),
+        title.as_ref(),
+        description.as_deref().unwrap_or_default(),
+        status.as_str(),
+        todo_at.as_str(),
+        created_at.as_str(),
+        updated_at.as_str(),
+    ])
+}
+
+/// Prefix text that would be evaluated as a formula with a single quote, so
+/// opening an export in a spreadsheet cannot run formulas written by users
+fn text_cell(text: &str) -> Cow<'_, str> {
+    if text.starts_with(FORMULA_PREFIXES) {
+        Cow::Owned(format!("'{text}"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::dtos::todo::export::ExportedTodos;
+    use crate::domain::entities::todo::{Description, InitProps, Status, Title};
+    use crate::domain::types::{Date, Id};
+
+    #[tokio::test]
+    async fn todos_are_presented_as_records() {
+        let created_at = time::macros::datetime!(2024-01-31 08:30 UTC);
+        let todo = TodoEntity::init(InitProps {
+            id: Id::new(),
+            title: Title::new("Buy milk, eggs").unwrap(),
+            description: Some(Description::new("Two\nlines").unwrap()),
+            status: Status::InProgress,
+            todo_at: Some(Date::from(time::macros::date!(2024 - 02 - 01))),
+            created_at: created_at.into(),
+            updated_at: created_at.into(),
+        });
+
+        let todos = ExportedTodos::new(stream::iter([todo.clone()]).map(Ok).boxed());
+        let records = CsvTodoPresenter::new()
+            .present(Ok(todos))
+            .unwrap()
+            .try_collect::<String>()
+            .await
+            .unwrap();
+
+        let expected = format!(
+            "id,title,description,status,todoAt,createdAt,updatedAt\r\n\
+             {},\"Buy milk, eggs\",\"Two\nlines\",in_progress,2024-02-01,{},{}\r\n",
+            todo.id(),
+            todo.created_at().to_rfc3339(),
+            todo.updated_at().to_rfc3339(),
+        );
+        assert_eq!(records, expected);
+    }
+
+    #[test]
+    fn formulas_are_not_evaluated_by_spreadsheets() {
+        let todo = TodoEntity::init(InitProps {
+            id: Id::new(),
+            title: Title::new("=HYPERLINK(\"https://example.com\")").unwrap(),
+            description: Some(Description::new("@SUM(A1)").unwrap()),
+            status: Status::Todo,
+            todo_at: None,
+            created_at: time::macros::datetime!(2024-01-31 08:30 UTC).into(),
+            updated_at: time::macros::datetime!(2024-01-31 08:30 UTC).into(),
+        });
+
+        let record = todo_record(&todo);
+        assert!(record.contains(",\"'=HYPERLINK(\"\"https://example.com\"\")\",'@SUM(A1),"));
+
+        for text in ["+1", "-1", "\tcell", "\rcell"] {
+            assert_eq!(text_cell(text), format!("'{text}"));
+        }
+        assert_eq!(text_cell("Buy milk"), "Buy milk");
+    }
+}
@@ -0,0 +1,111 @@
+pub const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Content lines cannot be longer than 75 octets, excluding the line break
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Writer of iCalendar (RFC 5545) content lines, which folds lines longer
+/// than 75 octets and ends each one with CRLF
+#[derive(Clone, Debug, Default)]
+pub struct CalendarWriter {
+    output: String,
+}
+
+impl CalendarWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn begin(&mut self, component: &str) -> &mut Self {
+        self.property("BEGIN", component)
+    }
+
+    pub fn end(&mut self, component: &str) -> &mut Self {
+        self.property("END", component)
+    }
+
+    /// Write a property whose value is already formatted, such as a date
+    pub fn property(&mut self, name: &str, value: &str) -> &mut Self {
+        let mut len = 0;
+        for c in name.chars().chain([':']).chain(value.chars()) {
+            // folding never splits a multi-octet character
+            if len + c.len_utf8() > MAX_LINE_OCTETS {
+                self.output.push_str("\r\n ");
+                len = 1;
+            }
+
+            self.output.push(c);
+            len += c.len_utf8();
+        }
+
+        self.output.push_str("\r\n");
+        self
+    }
+
+    /// Write a property of `TEXT` type, escaping its value
+    pub fn text(&mut self, name: &str, value: &str) -> &mut Self {
+        self.property(name, &escape_text(value))
+    }
+
+    pub fn finish(self) -> String {
+        self.output
+    }
+}
+
+/// Escape backslashes, semicolons, commas and line breaks of a `TEXT` value
+pub fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                escaped.push_str("\\n");
+            }
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_escaped() {
+        let escaped = escape_text("a\\b;c,d\ne\r\nf\rg");
+        assert_eq!(escaped, "a\\\\b\\;c\\,d\\ne\\nf\\ng");
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let value = "x".repeat(100);
+        let mut writer = CalendarWriter::new();
+        writer.property("SUMMARY", &value);
+        let output = writer.finish();
+
+        let lines = output.split_terminator("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), MAX_LINE_OCTETS);
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(output.replace("\r\n ", ""), format!("SUMMARY:{value}\r\n"));
+    }
+
+    #[test]
+    fn folding_keeps_characters_whole() {
+        let value = "é".repeat(60);
+        let mut writer = CalendarWriter::new();
+        writer.property("SUMMARY", &value);
+        let output = writer.finish();
+
+        for line in output.split_terminator("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(output.replace("\r\n ", ""), format!("SUMMARY:{value}\r\n"));
+    }
+}
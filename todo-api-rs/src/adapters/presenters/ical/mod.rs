@@ -0,0 +1,5 @@
+mod calendar;
+mod todo;
+
+pub use calendar::*;
+pub use todo::*;
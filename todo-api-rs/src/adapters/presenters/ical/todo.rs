@@ -0,0 +1,220 @@
+use futures_util::stream;
+use futures_util::{StreamExt, TryStreamExt};
+use time::macros::format_description;
+use time::UtcOffset;
+
+use super::calendar::CalendarWriter;
+use crate::adapters::dtos::todo::export::{
+    ExportPresenter, ExportResponse, ExportResponseError, ExportStream,
+};
+use crate::adapters::dtos::todo::find::{FindPresenter, FindResponse, FindResponseError};
+use crate::adapters::presenters::json::error::{Content, ErrorFormat, JsonError};
+use crate::application::dtos::todo::export::ExportTodosError;
+use crate::domain::entities::todo::{Status, TodoEntity};
+use crate::domain::types::DateTime;
+
+const PRODUCT_ID: &str = "-//todo-api-rs//Todo API//EN";
+
+/// Presents todos as an iCalendar (RFC 5545) document of `VTODO` components,
+/// while errors keep being presented as JSON
+#[derive(Clone, Debug)]
+pub struct IcalTodoPresenter {
+    errors: ErrorFormat,
+}
+
+impl IcalTodoPresenter {
+    pub const fn new() -> Self {
+        Self {
+            errors: ErrorFormat::Content,
+        }
+    }
+
+    /// Set the shape of error bodies, which defaults to [`ErrorFormat::Content`]
+    pub fn with_error_format(mut self, errors: ErrorFormat) -> Self {
+        self.errors = errors;
+        self
+    }
+}
+
+impl FindPresenter for IcalTodoPresenter {
+    type View = Result<String, JsonError>;
+
+    fn present(&self, response: FindResponse) -> Self::View {
+        response
+            .map(|todo| calendar(&[todo]))
+            .map_err(|err| match err {
+                FindResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
+                FindResponseError::NotFound(..) => {
+                    let content = Content::new("NotFound", err.to_string());
+                    self.errors.error(404, content)
+                }
+                FindResponseError::Internal(src) => self.errors.internal().with_src(src),
+            })
+    }
+}
+
+impl ExportPresenter for IcalTodoPresenter {
+    type View = Result<ExportStream, JsonError>;
+
+    fn present(&self, response: ExportResponse) -> Self::View {
+        response
+            .map(|todos| {
+                let components = todos.map_ok(|todo| todo_component(&todo)).map_err(|err| {
+                    let ExportTodosError::Internal(src) = err;
+                    src
+                });
+
+                stream::once(async { Ok(calendar_start()) })
+                    .chain(components)
+                    .chain(stream::once(async { Ok(calendar_end()) }))
+                    .boxed()
+            })
+            .map_err(|err| match err {
+                ExportResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
+            })
+    }
+}
+
+fn calendar(todos: &[TodoEntity]) -> String {
+    let mut calendar = calendar_start();
+    calendar.extend(todos.iter().map(todo_component));
+    calendar.push_str(&calendar_end());
+    calendar
+}
+
+fn calendar_start() -> String {
+    let mut writer = CalendarWriter::new();
+    writer
+        .begin("VCALENDAR")
+        .property("VERSION", "2.0")
+        .property("PRODID", PRODUCT_ID)
+        .property("CALSCALE", "GREGORIAN");
+    writer.finish()
+}
+
+fn calendar_end() -> String {
+    let mut writer = CalendarWriter::new();
+    writer.end("VCALENDAR");
+    writer.finish()
+}
+
+fn todo_component(todo: &TodoEntity) -> String {
+    let mut writer = CalendarWriter::new();
+    write_todo(&mut writer, todo);
+    writer.finish()
+}
+
+fn write_todo(writer: &mut CalendarWriter, todo: &TodoEntity) {
+    let ymd = format_description!("[year][month][day]");
+    let status = match todo.status() {
+        Status::Todo => "NEEDS-ACTION",
+        Status::InProgress => "IN-PROCESS",
+        Status::Done => "COMPLETED",
+    };
+
+    writer
+        .begin("VTODO")
+        .property("UID", &todo.id().to_string())
+        // without a `METHOD`, the stamp is when the todo was last changed
+        .property("DTSTAMP", &utc_date_time(todo.updated_at()))
+        .property("CREATED", &utc_date_time(todo.created_at()))
+        .property("LAST-MODIFIED", &utc_date_time(todo.updated_at()))
+        .text("SUMMARY", todo.title().as_str());
+
+    if let Some(description) = todo.description() {
+        writer.text("DESCRIPTION", description.as_str());
+    }
+
+    if let Some(todo_at) = todo.todo_at() {
+        // probably safe to unwrap since it uses a well known/supported format
+        let due = todo_at.time().format(ymd).unwrap();
+        writer.property("DUE;VALUE=DATE", &due);
+    }
+
+    writer.property("STATUS", status).end("VTODO");
+}
+
+/// Format as a UTC `DATE-TIME` such as `20240131T083000Z`
+fn utc_date_time(date_time: DateTime) -> String {
+    let format = format_description!("[year][month][day]T[hour][minute][second]Z");
+    // probably safe to unwrap since it uses a well known/supported format
+    date_time
+        .time()
+        .to_offset(UtcOffset::UTC)
+        .format(format)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::dtos::todo::export::ExportedTodos;
+    use crate::domain::entities::todo::{Description, InitProps, NewProps, Title};
+    use crate::domain::types::{Date, Id};
+
+    #[test]
+    fn todo_is_presented_as_vtodo() {
+        let created_at = time::macros::datetime!(2024-01-31 08:30 +02:00);
+        let todo = TodoEntity::init(InitProps {
+            id: Id::new(),
+            title: Title::new("Buy milk, eggs").unwrap(),
+            description: Some(Description::new("Two\nlines").unwrap()),
+            status: Status::InProgress,
+            todo_at: Some(Date::from(time::macros::date!(2024 - 02 - 01))),
+            created_at: created_at.into(),
+            updated_at: created_at.into(),
+        });
+
+        let presenter = IcalTodoPresenter::new();
+        let presented = FindPresenter::present(&presenter, Ok(todo.clone())).unwrap();
+        let lines = presented.split_terminator("\r\n").collect::<Vec<_>>();
+        let uid = format!("UID:{}", todo.id());
+        assert_eq!(
+            lines,
+            [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//todo-api-rs//Todo API//EN",
+                "CALSCALE:GREGORIAN",
+                "BEGIN:VTODO",
+                &uid,
+                "DTSTAMP:20240131T063000Z",
+                "CREATED:20240131T063000Z",
+                "LAST-MODIFIED:20240131T063000Z",
+                "SUMMARY:Buy milk\\, eggs",
+                "DESCRIPTION:Two\\nlines",
+                "DUE;VALUE=DATE:20240201",
+                "STATUS:IN-PROCESS",
+                "END:VTODO",
+                "END:VCALENDAR",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn exported_todos_are_streamed_in_one_calendar() {
+        let todos = ["First", "Second"].map(|title| {
+            TodoEntity::new(NewProps {
+                title: Title::new(title).unwrap(),
+                description: None,
+                status: Status::Todo,
+                todo_at: None,
+            })
+        });
+
+        let todos = ExportedTodos::new(stream::iter(todos).map(Ok).boxed());
+        let presenter = IcalTodoPresenter::new();
+        let exported = ExportPresenter::present(&presenter, Ok(todos))
+            .unwrap()
+            .try_collect::<String>()
+            .await
+            .unwrap();
+        let lines = exported.split_terminator("\r\n").collect::<Vec<_>>();
+
+        assert_eq!(lines.first(), Some(&"BEGIN:VCALENDAR"));
+        assert_eq!(lines.last(), Some(&"END:VCALENDAR"));
+        assert_eq!(lines.iter().filter(|l| **l == "BEGIN:VTODO").count(), 2);
+        assert!(lines.contains(&"SUMMARY:First"));
+        assert!(lines.contains(&"SUMMARY:Second"));
+    }
+}
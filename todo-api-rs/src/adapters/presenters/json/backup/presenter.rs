@@ -0,0 +1,155 @@
+use futures_util::{stream, StreamExt};
+
+use super::error::{Content, ErrorFormat, JsonError};
+use super::{BackupImportView, RestoreReportView, TableSummaryView};
+
+use crate::adapters::dtos::backup::export::{ExportPresenter, ExportResponse};
+use crate::adapters::dtos::backup::restore::{
+    RestorePresenter, RestoreResponse, RestoreResponseError, ARCHIVE_VERSION,
+};
+use crate::adapters::dtos::todo::export::ExportStream;
+use crate::adapters::presenters::json::todo::TodoView;
+use crate::application::dtos::todo::export::ExportTodosError;
+use crate::application::repositories::backup::RestoreMode;
+
+#[derive(Clone, Debug)]
+pub struct JsonBackupPresenter {
+    errors: ErrorFormat,
+}
+
+impl JsonBackupPresenter {
+    pub const fn new() -> Self {
+        Self {
+            errors: ErrorFormat::Content,
+        }
+    }
+
+    /// Set the shape of error bodies, which defaults to [`ErrorFormat::Content`]
+    pub fn with_error_format(mut self, errors: ErrorFormat) -> Self {
+        self.errors = errors;
+        self
+    }
+}
+
+impl ExportPresenter for JsonBackupPresenter {
+    type View = ExportStream;
+
+    /// Write the archive piece by piece as rows are fetched, one row per
+    /// line, so it never has to be held in memory
+    fn present(&self, response: ExportResponse) -> Self::View {
+        let head = format!(
+            "{{\"version\":{ARCHIVE_VERSION},\"exportedAt\":\"{}\",\"todos\":[",
+            response.exported_at.to_rfc3339()
+        );
+
+        let todos = response.todos.enumerate().map(|(idx, todo)| {
+            let todo = todo.map_err(|err| {
+                let ExportTodosError::Internal(src) = err;
+                src
+            })?;
+
+            let separator = if idx == 0 { "\n" } else { ",\n" };
+            let todo = serde_json::to_string(&TodoView::from(todo))?;
+            Ok(format!("{separator}{todo}"))
+        });
+
+        let imports = response.imports.enumerate().map(|(idx, import)| {
+            let import = import.map_err(|err| {
+                let ExportTodosError::Internal(src) = err;
+                src
+            })?;
+
+            let separator = if idx == 0 { "\n" } else { ",\n" };
+            let import = serde_json::to_string(&BackupImportView::from(import))?;
+            Ok(format!("{separator}{import}"))
+        });
+
+        stream::iter([head])
+            .map(Ok)
+            .chain(todos)
+            .chain(stream::iter([String::from("\n],\"imports\":[")]).map(Ok))
+            .chain(imports)
+            .chain(stream::iter([String::from("\n]}\n")]).map(Ok))
+            .boxed()
+    }
+}
+
+impl RestorePresenter for JsonBackupPresenter {
+    type View = Result<RestoreReportView, JsonError>;
+
+    fn present(&self, response: RestoreResponse) -> Self::View {
+        response
+            .map(|(mode, summary)| RestoreReportView {
+                mode: String::from(match mode {
+                    RestoreMode::Merge => "merge",
+                    RestoreMode::Replace => "replace",
+                }),
+                todos: TableSummaryView::from(summary.todos),
+                imports: TableSummaryView::from(summary.imports),
+            })
+            .map_err(|err| match err {
+                RestoreResponseError::Input(report) => self.errors.parse_error(report.errors()),
+                RestoreResponseError::DuplicatedTitle => {
+                    let content = Content::new("DuplicatedTitle", err.to_string());
+                    self.errors.error(409, content)
+                }
+                RestoreResponseError::IdTaken => {
+                    let content = Content::new("IdTaken", err.to_string());
+                    self.errors.error(409, content)
+                }
+                RestoreResponseError::Internal(src) => self.errors.internal().with_src(src),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+
+    use super::*;
+    use crate::application::dtos::backup::export::Backup;
+    use crate::application::dtos::todo::export::ExportedTodos;
+    use crate::application::repositories::backup::ImportRecord;
+    use crate::application::repositories::todo::ImportOrigin;
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::entities::todo::Status;
+    use crate::domain::types::DateTime;
+
+    #[tokio::test]
+    async fn archives_are_valid_json() {
+        let todos = [todo("First", Status::Todo), todo("Second", Status::Todo)];
+        let import = ImportRecord {
+            origin: ImportOrigin {
+                format: "ics",
+                uid: String::from("first@example.com"),
+            },
+            todo_id: todos[0].id(),
+            created_at: DateTime::now(),
+        };
+        let backup = Backup {
+            exported_at: DateTime::now(),
+            todos: ExportedTodos::new(stream::iter(todos.clone()).map(Ok).boxed()),
+            imports: stream::iter([import]).map(Ok).boxed(),
+        };
+
+        let content = ExportPresenter::present(&JsonBackupPresenter::new(), backup)
+            .try_collect::<String>()
+            .await
+            .unwrap();
+        let archive = serde_json::from_str::<serde_json::Value>(&content).unwrap();
+
+        assert_eq!(archive["version"], ARCHIVE_VERSION);
+        let ids = archive["todos"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|todo| todo["id"].as_str().unwrap().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, todos.clone().map(|todo| todo.id().to_string()));
+
+        let imports = archive["imports"].as_array().unwrap();
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0]["uid"], "first@example.com");
+        assert_eq!(imports[0]["todoId"], todos[0].id().to_string());
+    }
+}
@@ -0,0 +1,84 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::adapters::presenters::json::todo::TodoView;
+use crate::application::repositories::backup::{ImportRecord, TableSummary};
+
+/// Archive of the todos of a tenant and the entries they were imported from,
+/// which is restored as it was. Webhooks are left out, as their receivers and
+/// secrets belong to the environment
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct BackupArchiveView {
+    /// Version of the archive format. Restores also accept version 1, whose
+    /// archives have no imports and leave them as they are
+    #[schema(example = 2)]
+    pub version: u32,
+    /// Date time with offset in `RFC-3339` format
+    #[serde(rename = "exportedAt")]
+    #[schema(format = DateTime)]
+    pub exported_at: String,
+    pub todos: Vec<TodoView>,
+    pub imports: Vec<BackupImportView>,
+}
+
+/// Entry of an imported file, so importing it again after a restore is skipped
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct BackupImportView {
+    /// Format of the imported file, one of `csv`, `ics` or `txt`
+    #[schema(example = "ics")]
+    pub format: String,
+    /// Unique identifier of the entry in the file
+    pub uid: String,
+    /// Id of the todo of the archive the entry was imported as
+    #[serde(rename = "todoId")]
+    #[schema(format = Uuid)]
+    pub todo_id: String,
+    /// Date time with offset in `RFC-3339` format
+    #[serde(rename = "createdAt")]
+    #[schema(format = DateTime)]
+    pub created_at: String,
+}
+
+impl From<ImportRecord> for BackupImportView {
+    fn from(record: ImportRecord) -> Self {
+        Self {
+            format: record.origin.format.to_owned(),
+            uid: record.origin.uid,
+            todo_id: record.todo_id.to_string(),
+            created_at: record.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Rows changed by a restore, for every table
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct RestoreReportView {
+    /// Either `merge` or `replace`
+    #[schema(example = "merge")]
+    pub mode: String,
+    pub todos: TableSummaryView,
+    pub imports: TableSummaryView,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct TableSummaryView {
+    /// Rows of the archive that did not exist
+    pub created: u64,
+    /// Rows of the archive that existed with other values
+    pub updated: u64,
+    /// Rows of the archive that existed with the same values
+    pub unchanged: u64,
+    /// Rows missing from the archive, deleted when replacing
+    pub deleted: u64,
+}
+
+impl From<TableSummary> for TableSummaryView {
+    fn from(summary: TableSummary) -> Self {
+        Self {
+            created: summary.created,
+            updated: summary.updated,
+            unchanged: summary.unchanged,
+            deleted: summary.deleted,
+        }
+    }
+}
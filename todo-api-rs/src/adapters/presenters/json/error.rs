@@ -2,11 +2,19 @@ use std::error;
 
 use serde::Serialize;
 use serde_json::{json, Value};
+use utoipa::ToSchema;
+
+use super::problem::{FieldError, Problem};
+use crate::adapters::dtos::validation::InvalidField;
+
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
 
 #[derive(Debug)]
 pub struct JsonError {
     status: u16,
     src: Option<Box<dyn error::Error>>,
+    content_type: &'static str,
     pub content: Value,
 }
 
@@ -15,16 +23,17 @@ impl JsonError {
         Self {
             status,
             src: None,
+            content_type: JSON_CONTENT_TYPE,
             content: json!(content),
         }
     }
 
-    pub fn internal() -> Self {
-        let content = Content::internal();
+    pub fn problem(problem: Problem) -> Self {
         Self {
-            status: 500,
+            status: problem.status,
             src: None,
-            content: json!(content),
+            content_type: PROBLEM_CONTENT_TYPE,
+            content: json!(problem),
         }
     }
 
@@ -32,6 +41,11 @@ impl JsonError {
         self.status
     }
 
+    /// Media type of [`JsonError::content`]
+    pub fn content_type(&self) -> &'static str {
+        self.content_type
+    }
+
     pub fn src(&self) -> Option<&dyn error::Error> {
         self.src.as_deref()
     }
@@ -42,10 +56,16 @@ impl JsonError {
     }
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct Content {
+    /// Machine readable error code, such as `ParseError` or `NotFound`
+    #[schema(example = "ParseError")]
     code: String,
+    /// Human readable description of the error
     message: String,
+    /// Request fields that failed validation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<FieldError>,
 }
 
 impl Content {
@@ -53,13 +73,69 @@ impl Content {
         Self {
             code: code.into(),
             message: message.into(),
+            errors: Vec::new(),
         }
     }
 
     fn internal() -> Self {
-        Self {
-            code: String::from("InternalError"),
-            message: String::from("Internal server error"),
+        Self::new("InternalError", "Internal server error")
+    }
+}
+
+/// Shape of the body of error responses
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// Legacy `{ code, message }` body described by [`Content`]
+    #[default]
+    Content,
+    /// RFC 9457 problem details, `instance` being the URI of the failed request
+    Problem { instance: String },
+}
+
+impl ErrorFormat {
+    pub fn error(&self, status: u16, content: Content) -> JsonError {
+        self.error_with_fields(status, content, Vec::new())
+    }
+
+    /// Create an error caused by invalid request fields
+    pub fn validation(&self, content: Content, errors: Vec<FieldError>) -> JsonError {
+        self.error_with_fields(400, content, errors)
+    }
+
+    /// Create a `ParseError` listing every field of `parse_errors`
+    pub fn parse_error<E: InvalidField>(&self, parse_errors: &[E]) -> JsonError {
+        let errors = parse_errors
+            .iter()
+            .map(FieldError::from)
+            .collect::<Vec<FieldError>>();
+
+        let message = errors
+            .iter()
+            .map(|err| err.detail.as_str())
+            .collect::<Vec<&str>>()
+            .join("; ");
+
+        self.validation(Content::new("ParseError", message), errors)
+    }
+
+    pub fn internal(&self) -> JsonError {
+        self.error(500, Content::internal())
+    }
+
+    fn error_with_fields(
+        &self,
+        status: u16,
+        content: Content,
+        errors: Vec<FieldError>,
+    ) -> JsonError {
+        match self {
+            Self::Content => JsonError::new(status, Content { errors, ..content }),
+            Self::Problem { instance } => {
+                let problem = Problem::new(status, content.code, content.message)
+                    .with_instance(instance.clone())
+                    .with_errors(errors);
+                JsonError::problem(problem)
+            }
         }
     }
 }
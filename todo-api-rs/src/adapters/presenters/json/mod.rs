@@ -1,2 +1,5 @@
+pub mod backup;
+pub mod error;
+pub mod problem;
 pub mod todo;
-pub mod error;
\ No newline at end of file
+pub mod webhook;
@@ -0,0 +1,179 @@
+use axum::http::StatusCode;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::adapters::dtos::validation::{FieldLocation, InvalidField};
+
+/// Problem details body as defined by RFC 9457
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct Problem {
+    /// URI reference identifying the problem type
+    #[serde(rename = "type")]
+    #[schema(example = "urn:todo-api:problem:ParseError")]
+    pub kind: String,
+    /// Short summary of the problem type
+    pub title: String,
+    /// HTTP status code of the response
+    pub status: u16,
+    /// Explanation specific to this occurrence of the problem
+    pub detail: String,
+    /// URI reference of the request where the problem occurred
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Same machine readable error code of the legacy error body
+    #[schema(example = "ParseError")]
+    pub code: String,
+    /// Request fields that failed validation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+impl Problem {
+    pub fn new(status: u16, code: impl Into<String>, detail: impl Into<String>) -> Self {
+        let code: String = code.into();
+        Self {
+            kind: format!("urn:todo-api:problem:{code}"),
+            title: Self::title(status).to_string(),
+            status,
+            detail: detail.into(),
+            instance: None,
+            code,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    pub fn with_errors(mut self, errors: Vec<FieldError>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    fn title(status: u16) -> &'static str {
+        StatusCode::from_u16(status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("Unknown Error")
+    }
+}
+
+/// Validation failure of a single request field
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Name of the offending field
+    #[schema(example = "title")]
+    pub field: String,
+    /// Machine readable code of the failure
+    #[schema(example = "title.empty")]
+    pub code: String,
+    /// One of `body`, `path` or `query`, the part of the request the field
+    /// is read from
+    #[schema(example = "body")]
+    pub location: String,
+    /// JSON pointer to the offending member of the body, missing for path
+    /// and query parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "#/title")]
+    pub pointer: Option<String>,
+    /// Explanation of why the field is invalid
+    pub detail: String,
+}
+
+impl FieldError {
+    /// Error of the body member named `field`
+    pub fn new(field: &str, code: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            code: code.into(),
+            location: FieldLocation::Body.to_string(),
+            pointer: Some(format!("#/{field}")),
+            detail: detail.into(),
+        }
+    }
+
+    /// Move the field to `location`, which only has a pointer in the body
+    pub fn at(mut self, location: FieldLocation) -> Self {
+        if location != FieldLocation::Body {
+            self.pointer = None;
+        }
+        self.location = location.to_string();
+        self
+    }
+}
+
+impl<E: InvalidField> From<&E> for FieldError {
+    fn from(err: &E) -> Self {
+        Self::new(err.field(), err.code(), err.to_string()).at(err.location())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn problem_serializes_to_rfc_9457_members() {
+        let problem = Problem::new(400, "ParseError", "Todo title cannot be empty")
+            .with_instance("/todos")
+            .with_errors(vec![FieldError::new(
+                "title",
+                "title.empty",
+                "Todo title cannot be empty",
+            )]);
+
+        let expected = json!({
+            "type": "urn:todo-api:problem:ParseError",
+            "title": "Bad Request",
+            "status": 400,
+            "detail": "Todo title cannot be empty",
+            "instance": "/todos",
+            "code": "ParseError",
+            "errors": [{
+                "field": "title",
+                "code": "title.empty",
+                "location": "body",
+                "pointer": "#/title",
+                "detail": "Todo title cannot be empty",
+            }],
+        });
+        assert_eq!(json!(problem), expected);
+    }
+
+    #[test]
+    fn problem_omits_empty_members() {
+        let problem = Problem::new(404, "NotFound", "Todo not found");
+        let value = json!(problem);
+        assert!(value.get("instance").is_none());
+        assert!(value.get("errors").is_none());
+        assert_eq!(value["title"], "Not Found");
+    }
+
+    #[test]
+    fn parameters_have_no_pointer() {
+        let error = FieldError::new("id", "id.invalid_format", "Invalid todo id format")
+            .at(FieldLocation::Path);
+
+        let expected = json!({
+            "field": "id",
+            "code": "id.invalid_format",
+            "location": "path",
+            "detail": "Invalid todo id format",
+        });
+        assert_eq!(json!(error), expected);
+    }
+
+    #[test]
+    fn title_is_the_reason_of_the_status() {
+        assert_eq!(
+            Problem::new(401, "Unauthenticated", "").title,
+            "Unauthorized"
+        );
+        assert_eq!(Problem::new(504, "Timeout", "").title, "Gateway Timeout");
+        assert_eq!(Problem::new(599, "Unknown", "").title, "Unknown Error");
+    }
+}
@@ -1,29 +1,43 @@
 use serde::Serialize;
+use utoipa::ToSchema;
 
-use super::error::{Content, JsonError};
-use super::TodoView;
+use super::error::{Content, ErrorFormat, JsonError};
+use super::{ImportReportView, ImportedEntryView, InvalidEntryView, SkippedEntryView, TodoView};
 
 use crate::adapters::dtos::todo::create::{CreatePresenter, CreateResponse, CreateResponseError};
 use crate::adapters::dtos::todo::delete::{DeletePresenter, DeleteResponse, DeleteResponseError};
 use crate::adapters::dtos::todo::find::{FindPresenter, FindResponse, FindResponseError};
+use crate::adapters::dtos::todo::import::{ImportPresenter, ImportResponse, ImportResponseError};
 use crate::adapters::dtos::todo::list::{ListPresenter, ListResponse, ListResponseError};
 use crate::adapters::dtos::todo::update::{UpdatePresenter, UpdateResponse, UpdateResponseError};
+use crate::adapters::presenters::json::problem::FieldError;
+use crate::application::dtos::todo::import::SkipReason;
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct TodosListView {
     pub page: u32,
-    #[serde(rename(serialize = "perPage"))]
+    #[serde(rename = "perPage")]
     pub per_page: u32,
     pub count: u64,
     pub items: Vec<TodoView>,
 }
 
 #[derive(Clone, Debug)]
-pub struct JsonTodoPresenter;
+pub struct JsonTodoPresenter {
+    errors: ErrorFormat,
+}
 
 impl JsonTodoPresenter {
     pub const fn new() -> Self {
-        Self
+        Self {
+            errors: ErrorFormat::Content,
+        }
+    }
+
+    /// Set the shape of error bodies, which defaults to [`ErrorFormat::Content`]
+    pub fn with_error_format(mut self, errors: ErrorFormat) -> Self {
+        self.errors = errors;
+        self
     }
 }
 
@@ -32,15 +46,12 @@ impl CreatePresenter for JsonTodoPresenter {
 
     fn present(&self, response: CreateResponse) -> Self::View {
         response.map(TodoView::from).map_err(|err| match err {
-            CreateResponseError::Input(parse_err) => {
-                let content = Content::new("ParseError", parse_err.to_string());
-                JsonError::new(400, content)
-            }
+            CreateResponseError::Input(report) => self.errors.parse_error(report.errors()),
             CreateResponseError::DuplicatedTitle(..) => {
                 let content = Content::new("DuplicatedTitle", err.to_string());
-                JsonError::new(409, content)
+                self.errors.error(409, content)
             }
-            CreateResponseError::Internal(src) => JsonError::internal().with_src(src),
+            CreateResponseError::Internal(src) => self.errors.internal().with_src(src),
         })
     }
 }
@@ -50,15 +61,12 @@ impl DeletePresenter for JsonTodoPresenter {
 
     fn present(&self, response: DeleteResponse) -> Self::View {
         response.map_err(|err| match err {
-            DeleteResponseError::Input(parse_err) => {
-                let content = Content::new("ParseError", parse_err.to_string());
-                JsonError::new(400, content)
-            }
+            DeleteResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
             DeleteResponseError::NotFound(..) => {
                 let content = Content::new("NotFound", err.to_string());
-                JsonError::new(404, content)
+                self.errors.error(404, content)
             }
-            DeleteResponseError::Internal(src) => JsonError::internal().with_src(src),
+            DeleteResponseError::Internal(src) => self.errors.internal().with_src(src),
         })
     }
 }
@@ -68,15 +76,68 @@ impl FindPresenter for JsonTodoPresenter {
 
     fn present(&self, response: FindResponse) -> Self::View {
         response.map(TodoView::from).map_err(|err| match err {
-            FindResponseError::Input(parse_err) => {
-                let content = Content::new("ParseError", parse_err.to_string());
-                JsonError::new(400, content)
-            }
+            FindResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
             FindResponseError::NotFound(..) => {
                 let content = Content::new("NotFound", err.to_string());
-                JsonError::new(404, content)
+                self.errors.error(404, content)
             }
-            FindResponseError::Internal(src) => JsonError::internal().with_src(src),
+            FindResponseError::Internal(src) => self.errors.internal().with_src(src),
+        })
+    }
+}
+
+impl ImportPresenter for JsonTodoPresenter {
+    type View = Result<ImportReportView, JsonError>;
+
+    fn present(&self, response: ImportResponse) -> Self::View {
+        let report = response.map_err(|err| match err {
+            ImportResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
+            ImportResponseError::Internal(src) => self.errors.internal().with_src(src),
+        })?;
+
+        let created = report
+            .created
+            .into_iter()
+            .map(|(source, todo)| ImportedEntryView {
+                line: source.line,
+                uid: source.uid,
+                todo: TodoView::from(todo),
+            })
+            .collect();
+
+        let skipped = report
+            .skipped
+            .into_iter()
+            .map(|(source, reason)| {
+                let code = match reason {
+                    SkipReason::AlreadyImported => "AlreadyImported",
+                    SkipReason::DuplicatedTitle(..) => "DuplicatedTitle",
+                };
+
+                SkippedEntryView {
+                    line: source.line,
+                    uid: source.uid,
+                    code: code.to_owned(),
+                    message: reason.to_string(),
+                }
+            })
+            .collect();
+
+        let invalid = report
+            .invalid
+            .into_iter()
+            .map(|entry| InvalidEntryView {
+                line: entry.source.line,
+                uid: entry.source.uid,
+                errors: entry.errors.errors().iter().map(FieldError::from).collect(),
+            })
+            .collect();
+
+        Ok(ImportReportView {
+            dry_run: report.dry_run,
+            created,
+            skipped,
+            invalid,
         })
     }
 }
@@ -93,11 +154,8 @@ impl ListPresenter for JsonTodoPresenter {
                 items: list.items.into_iter().map(TodoView::from).collect(),
             })
             .map_err(|err| match err {
-                ListResponseError::Input(parse_err) => {
-                    let content = Content::new("ParseError", parse_err.to_string());
-                    JsonError::new(400, content)
-                }
-                ListResponseError::Internal(src) => JsonError::internal().with_src(src),
+                ListResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
+                ListResponseError::Internal(src) => self.errors.internal().with_src(src),
             })
     }
 }
@@ -107,19 +165,20 @@ impl UpdatePresenter for JsonTodoPresenter {
 
     fn present(&self, response: UpdateResponse) -> Self::View {
         response.map_err(|err| match err {
-            UpdateResponseError::Input(parse_err) => {
-                let content = Content::new("ParseError", parse_err.to_string());
-                JsonError::new(400, content)
-            }
+            UpdateResponseError::Input(report) => self.errors.parse_error(report.errors()),
             UpdateResponseError::DuplicatedTitle(..) => {
                 let content = Content::new("DuplicatedTitle", err.to_string());
-                JsonError::new(409, content)
+                self.errors.error(409, content)
             }
             UpdateResponseError::NotFound(..) => {
                 let content = Content::new("NotFound", err.to_string());
-                JsonError::new(404, content)
+                self.errors.error(404, content)
+            }
+            UpdateResponseError::Conflict(..) => {
+                let content = Content::new("Conflict", err.to_string());
+                self.errors.error(409, content)
             }
-            UpdateResponseError::Internal(src) => JsonError::internal().with_src(src),
+            UpdateResponseError::Internal(src) => self.errors.internal().with_src(src),
         })
     }
 }
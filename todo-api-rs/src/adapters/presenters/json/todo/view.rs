@@ -1,22 +1,30 @@
 use serde::Serialize;
+use utoipa::ToSchema;
 
+use crate::adapters::presenters::json::problem::FieldError;
 use crate::domain::entities::todo::TodoEntity;
 
 /// Presentable format of `TodoEntity`
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct TodoView {
+    #[schema(format = Uuid)]
     pub id: String,
     pub title: String,
     pub description: Option<String>,
+    /// One of `todo`, `in_progress` or `done`
+    #[schema(example = "todo")]
     pub status: String,
     /// Date in YYYY-MM-DD UTC format
-    #[serde(rename(serialize = "todoAt"))]
+    #[serde(rename = "todoAt")]
+    #[schema(format = Date)]
     pub todo_at: Option<String>,
     /// Date time with offset in `RFC-3339` format
-    #[serde(rename(serialize = "createdAt"))]
+    #[serde(rename = "createdAt")]
+    #[schema(format = DateTime)]
     pub created_at: String,
     /// Date time with offset in `RFC-3339` format
-    #[serde(rename(serialize = "updatedAt"))]
+    #[serde(rename = "updatedAt")]
+    #[schema(format = DateTime)]
     pub updated_at: String,
 }
 
@@ -28,12 +36,57 @@ impl From<TodoEntity> for TodoView {
 
         Self {
             id,
-            title: entity.title.into_inner(),
-            description: entity.description.map(|d| d.into_inner()),
-            status: entity.status.to_string(),
-            todo_at: entity.todo_at.map(|at| at.to_ymd()),
+            title: entity.title().to_string(),
+            description: entity.description().map(|d| d.to_string()),
+            status: entity.status().to_string(),
+            todo_at: entity.todo_at().map(|at| at.to_ymd()),
             created_at,
             updated_at,
         }
     }
 }
+
+/// Outcome of every entry of an imported file
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ImportReportView {
+    /// Whether this is a dry run, which reports what importing would do
+    /// without creating anything
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    /// Entries todos were created from, or would be on a dry run
+    pub created: Vec<ImportedEntryView>,
+    /// Entries that were already imported, or whose title already exists
+    pub skipped: Vec<SkippedEntryView>,
+    /// Entries that failed validation
+    pub invalid: Vec<InvalidEntryView>,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ImportedEntryView {
+    /// Line the entry starts at in the imported file
+    pub line: usize,
+    /// Unique identifier of the entry in the imported file, when it has one
+    pub uid: Option<String>,
+    pub todo: TodoView,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct SkippedEntryView {
+    /// Line the entry starts at in the imported file
+    pub line: usize,
+    /// Unique identifier of the entry in the imported file, when it has one
+    pub uid: Option<String>,
+    /// Either `AlreadyImported` or `DuplicatedTitle`
+    #[schema(example = "DuplicatedTitle")]
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct InvalidEntryView {
+    /// Line the entry starts at in the imported file
+    pub line: usize,
+    /// Unique identifier of the entry in the imported file, when it has one
+    pub uid: Option<String>,
+    pub errors: Vec<FieldError>,
+}
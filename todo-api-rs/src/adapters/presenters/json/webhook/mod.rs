@@ -0,0 +1,6 @@
+mod presenter;
+mod view;
+
+use super::error;
+pub use presenter::*;
+pub use view::*;
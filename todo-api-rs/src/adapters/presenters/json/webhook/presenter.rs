@@ -0,0 +1,140 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use super::error::{Content, ErrorFormat, JsonError};
+use super::{DeliveryView, WebhookView};
+
+use crate::adapters::dtos::webhook::create::{
+    CreatePresenter, CreateResponse, CreateResponseError,
+};
+use crate::adapters::dtos::webhook::delete::{
+    DeletePresenter, DeleteResponse, DeleteResponseError,
+};
+use crate::adapters::dtos::webhook::deliveries::{
+    DeliveriesPresenter, DeliveriesResponse, DeliveriesResponseError,
+};
+use crate::adapters::dtos::webhook::list::{ListPresenter, ListResponse, ListResponseError};
+use crate::adapters::dtos::webhook::replay::{
+    ReplayPresenter, ReplayResponse, ReplayResponseError,
+};
+use crate::adapters::dtos::webhook::update::{
+    UpdatePresenter, UpdateResponse, UpdateResponseError,
+};
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct WebhooksListView {
+    pub items: Vec<WebhookView>,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct DeliveriesListView {
+    pub items: Vec<DeliveryView>,
+}
+
+#[derive(Clone, Debug)]
+pub struct JsonWebhookPresenter {
+    errors: ErrorFormat,
+}
+
+impl JsonWebhookPresenter {
+    pub const fn new() -> Self {
+        Self {
+            errors: ErrorFormat::Content,
+        }
+    }
+
+    /// Set the shape of error bodies, which defaults to [`ErrorFormat::Content`]
+    pub fn with_error_format(mut self, errors: ErrorFormat) -> Self {
+        self.errors = errors;
+        self
+    }
+}
+
+impl CreatePresenter for JsonWebhookPresenter {
+    type View = Result<WebhookView, JsonError>;
+
+    fn present(&self, response: CreateResponse) -> Self::View {
+        response.map(WebhookView::from).map_err(|err| match err {
+            CreateResponseError::Input(report) => self.errors.parse_error(report.errors()),
+            CreateResponseError::Internal(src) => self.errors.internal().with_src(src),
+        })
+    }
+}
+
+impl DeletePresenter for JsonWebhookPresenter {
+    type View = Result<(), JsonError>;
+
+    fn present(&self, response: DeleteResponse) -> Self::View {
+        response.map_err(|err| match err {
+            DeleteResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
+            DeleteResponseError::NotFound(..) => {
+                let content = Content::new("NotFound", err.to_string());
+                self.errors.error(404, content)
+            }
+            DeleteResponseError::Internal(src) => self.errors.internal().with_src(src),
+        })
+    }
+}
+
+impl ListPresenter for JsonWebhookPresenter {
+    type View = Result<WebhooksListView, JsonError>;
+
+    fn present(&self, response: ListResponse) -> Self::View {
+        response
+            .map(|webhooks| WebhooksListView {
+                items: webhooks.into_iter().map(WebhookView::from).collect(),
+            })
+            .map_err(|err| match err {
+                ListResponseError::Internal(src) => self.errors.internal().with_src(src),
+            })
+    }
+}
+
+impl DeliveriesPresenter for JsonWebhookPresenter {
+    type View = Result<DeliveriesListView, JsonError>;
+
+    fn present(&self, response: DeliveriesResponse) -> Self::View {
+        response
+            .map(|deliveries| DeliveriesListView {
+                items: deliveries.into_iter().map(DeliveryView::from).collect(),
+            })
+            .map_err(|err| match err {
+                DeliveriesResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
+                DeliveriesResponseError::NotFound(..) => {
+                    let content = Content::new("NotFound", err.to_string());
+                    self.errors.error(404, content)
+                }
+                DeliveriesResponseError::Internal(src) => self.errors.internal().with_src(src),
+            })
+    }
+}
+
+impl ReplayPresenter for JsonWebhookPresenter {
+    type View = Result<(), JsonError>;
+
+    fn present(&self, response: ReplayResponse) -> Self::View {
+        response.map_err(|err| match err {
+            ReplayResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
+            ReplayResponseError::NotFound(..) => {
+                let content = Content::new("NotFound", err.to_string());
+                self.errors.error(404, content)
+            }
+            ReplayResponseError::Internal(src) => self.errors.internal().with_src(src),
+        })
+    }
+}
+
+impl UpdatePresenter for JsonWebhookPresenter {
+    type View = Result<WebhookView, JsonError>;
+
+    fn present(&self, response: UpdateResponse) -> Self::View {
+        response.map(WebhookView::from).map_err(|err| match err {
+            UpdateResponseError::Input(report) => self.errors.parse_error(report.errors()),
+            UpdateResponseError::NotFound(..) => {
+                let content = Content::new("NotFound", err.to_string());
+                self.errors.error(404, content)
+            }
+            UpdateResponseError::Internal(src) => self.errors.internal().with_src(src),
+        })
+    }
+}
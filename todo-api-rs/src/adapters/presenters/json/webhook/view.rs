@@ -0,0 +1,116 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::domain::entities::webhook::{DeliveryAttempt, DeliveryEntity, WebhookEntity};
+
+/// Presentable format of `WebhookEntity`, which never exposes its secret
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct WebhookView {
+    #[schema(format = Uuid)]
+    pub id: String,
+    #[schema(example = "https://example.com/hooks/todos")]
+    pub url: String,
+    /// Events delivered to the webhook, every event when empty
+    #[schema(example = json!(["todo.created", "todo.deleted"]))]
+    pub events: Vec<String>,
+    /// Why nothing is delivered to the webhook, until it's updated
+    #[serde(rename = "disabledReason")]
+    #[schema(example = "Webhook url must be an absolute https url")]
+    pub disabled_reason: Option<String>,
+    /// Date time with offset in `RFC-3339` format
+    #[serde(rename = "createdAt")]
+    #[schema(format = DateTime)]
+    pub created_at: String,
+}
+
+impl From<WebhookEntity> for WebhookView {
+    fn from(entity: WebhookEntity) -> Self {
+        Self {
+            id: entity.id().to_string(),
+            url: entity.url.to_string(),
+            events: entity.events.iter().map(ToString::to_string).collect(),
+            disabled_reason: entity.disabled_reason().map(String::from),
+            created_at: entity.created_at().to_rfc3339(),
+        }
+    }
+}
+
+/// Presentable format of `DeliveryEntity`
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct DeliveryView {
+    #[schema(format = Uuid)]
+    pub id: String,
+    #[serde(rename = "webhookId")]
+    #[schema(format = Uuid)]
+    pub webhook_id: String,
+    #[schema(example = "todo.created")]
+    pub event: String,
+    /// One of `pending`, `delivered` or `dead`
+    #[schema(example = "pending")]
+    pub status: String,
+    /// Attempts since the delivery was created or last replayed
+    #[serde(rename = "attemptCount")]
+    pub attempt_count: u32,
+    /// Date time with offset in `RFC-3339` format, only set while pending
+    #[serde(rename = "nextAttemptAt")]
+    #[schema(format = DateTime)]
+    pub next_attempt_at: Option<String>,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+    /// Every attempt, from oldest to newest
+    pub attempts: Vec<DeliveryAttemptView>,
+    /// Date time with offset in `RFC-3339` format
+    #[serde(rename = "createdAt")]
+    #[schema(format = DateTime)]
+    pub created_at: String,
+    /// Date time with offset in `RFC-3339` format
+    #[serde(rename = "updatedAt")]
+    #[schema(format = DateTime)]
+    pub updated_at: String,
+}
+
+impl From<DeliveryEntity> for DeliveryView {
+    fn from(entity: DeliveryEntity) -> Self {
+        Self {
+            id: entity.id.to_string(),
+            webhook_id: entity.webhook_id.to_string(),
+            event: entity.event.to_string(),
+            status: entity.status.to_string(),
+            attempt_count: entity.attempt_count,
+            next_attempt_at: entity.next_attempt_at.map(|at| at.to_rfc3339()),
+            last_error: entity.last_error,
+            attempts: entity
+                .attempts
+                .into_iter()
+                .map(DeliveryAttemptView::from)
+                .collect(),
+            created_at: entity.created_at.to_rfc3339(),
+            updated_at: entity.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct DeliveryAttemptView {
+    /// Date time with offset in `RFC-3339` format
+    #[serde(rename = "attemptedAt")]
+    #[schema(format = DateTime)]
+    pub attempted_at: String,
+    /// Status code of the response, missing when no response was received
+    #[serde(rename = "statusCode")]
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u32,
+}
+
+impl From<DeliveryAttempt> for DeliveryAttemptView {
+    fn from(attempt: DeliveryAttempt) -> Self {
+        Self {
+            attempted_at: attempt.attempted_at.to_rfc3339(),
+            status_code: attempt.status_code,
+            error: attempt.error,
+            duration_ms: attempt.duration_ms,
+        }
+    }
+}
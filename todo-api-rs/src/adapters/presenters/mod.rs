@@ -1 +1,4 @@
+pub mod csv;
+pub mod ical;
 pub mod json;
+pub mod txt;
@@ -0,0 +1,3 @@
+mod todo;
+
+pub use todo::*;
\ No newline at end of file
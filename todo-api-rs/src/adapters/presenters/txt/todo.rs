@@ -0,0 +1,151 @@
+use futures_util::{StreamExt, TryStreamExt};
+use time::UtcOffset;
+
+use crate::adapters::dtos::todo::export::{
+    ExportPresenter, ExportResponse, ExportResponseError, ExportStream,
+};
+use crate::adapters::presenters::json::error::{ErrorFormat, JsonError};
+use crate::application::dtos::todo::export::ExportTodosError;
+use crate::domain::entities::todo::{Status, TodoEntity};
+use crate::domain::types::{Date, DateTime};
+
+pub const TODO_TXT_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+
+/// Presents todos as lines of the todo.txt format, while errors keep being
+/// presented as JSON
+#[derive(Clone, Debug)]
+pub struct TxtTodoPresenter {
+    errors: ErrorFormat,
+}
+
+impl TxtTodoPresenter {
+    pub const fn new() -> Self {
+        Self {
+            errors: ErrorFormat::Content,
+        }
+    }
+
+    /// Set the shape of error bodies, which defaults to [`ErrorFormat::Content`]
+    pub fn with_error_format(mut self, errors: ErrorFormat) -> Self {
+        self.errors = errors;
+        self
+    }
+}
+
+impl ExportPresenter for TxtTodoPresenter {
+    type View = Result<ExportStream, JsonError>;
+
+    fn present(&self, response: ExportResponse) -> Self::View {
+        response
+            .map(|todos| {
+                todos
+                    .map_ok(|todo| todo_line(&todo))
+                    .map_err(|err| {
+                        let ExportTodosError::Internal(src) = err;
+                        src
+                    })
+                    .boxed()
+            })
+            .map_err(|err| match err {
+                ExportResponseError::Input(parse_err) => self.errors.parse_error(&[parse_err]),
+            })
+    }
+}
+
+/// Format a todo as a todo.txt line, such as
+/// `x 2024-02-03 2024-01-31 Buy milk due:2024-02-01 id:<uuid>`.
+///
+/// Todos have no priority nor completion date, so todos in progress have the
+/// `(A)` priority, and done todos are completed on the date they were last
+/// updated. The `due:` key is written, even without a date, when the title
+/// has a word starting with `due:`, as the last key of the line is the one
+/// read back. Descriptions do not fit on a line and are left out
+pub fn todo_line(todo: &TodoEntity) -> String {
+    let mut line = String::new();
+    match todo.status() {
+        Status::Done => {
+            line.push_str("x ");
+            line.push_str(&utc_date(todo.updated_at()).to_ymd());
+            line.push(' ');
+        }
+        Status::InProgress => line.push_str("(A) "),
+        Status::Todo => {}
+    }
+
+    line.push_str(&utc_date(todo.created_at()).to_ymd());
+    line.push(' ');
+    line.extend(todo.title().as_str().chars().map(|c| match c {
+        '\r' | '\n' => ' ',
+        _ => c,
+    }));
+
+    let titled_due = todo
+        .title()
+        .as_str()
+        .split_whitespace()
+        .any(|word| word.starts_with("due:"));
+    match todo.todo_at() {
+        Some(todo_at) => {
+            line.push_str(" due:");
+            line.push_str(&todo_at.to_ymd());
+        }
+        None if titled_due => line.push_str(" due:"),
+        None => {}
+    }
+
+    line.push_str(" id:");
+    line.push_str(&todo.id().to_string());
+    line.push('\n');
+    line
+}
+
+fn utc_date(date_time: DateTime) -> Date {
+    Date::from(date_time.time().to_offset(UtcOffset::UTC).date())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::todo::{InitProps, Title};
+    use crate::domain::types::Id;
+
+    #[test]
+    fn done_todos_are_completed() {
+        let todo = TodoEntity::init(InitProps {
+            id: Id::new(),
+            title: Title::new("Buy\nmilk").unwrap(),
+            description: None,
+            status: Status::Done,
+            todo_at: Some(Date::from(time::macros::date!(2024 - 02 - 01))),
+            created_at: time::macros::datetime!(2024-01-31 23:30 -02:00).into(),
+            updated_at: time::macros::datetime!(2024-02-03 08:30 UTC).into(),
+        });
+
+        assert_eq!(
+            todo_line(&todo),
+            format!(
+                "x 2024-02-03 2024-02-01 Buy milk due:2024-02-01 id:{}\n",
+                todo.id()
+            )
+        );
+    }
+
+    #[test]
+    fn todos_in_progress_have_the_highest_priority() {
+        let todo = TodoEntity::init(InitProps {
+            id: Id::new(),
+            title: Title::new("Pay due:rent").unwrap(),
+            description: None,
+            status: Status::InProgress,
+            todo_at: None,
+            created_at: time::macros::datetime!(2024-01-31 08:30 UTC).into(),
+            updated_at: time::macros::datetime!(2024-02-03 08:30 UTC).into(),
+        });
+
+        // the empty key is the one read back, rather than the word of the title
+        assert_eq!(
+            todo_line(&todo),
+            format!("(A) 2024-01-31 Pay due:rent due: id:{}\n", todo.id())
+        );
+    }
+}
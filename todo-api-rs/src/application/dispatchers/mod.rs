@@ -0,0 +1 @@
+pub mod todo;
\ No newline at end of file
@@ -0,0 +1,8 @@
+use crate::domain::events::todo::TodoEvent;
+
+/// Hand the events a todo recorded to whatever reacts to them in-process.
+/// Use cases dispatch once the change that recorded the events was saved,
+/// so dispatching cannot fail the use case
+pub trait TodoEventDispatcher {
+    fn dispatch(&self, events: Vec<TodoEvent>);
+}
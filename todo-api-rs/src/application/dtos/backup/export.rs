@@ -0,0 +1,14 @@
+use futures_util::stream::BoxStream;
+
+use crate::application::dtos::todo::export::{ExportTodosError, ExportedTodos};
+use crate::application::repositories::backup::ImportRecord;
+use crate::domain::types::DateTime;
+
+pub type ExportBackupOutput = Backup;
+
+/// Every table of a tenant, whose rows are fetched as they are consumed
+pub struct Backup {
+    pub exported_at: DateTime,
+    pub todos: ExportedTodos,
+    pub imports: BoxStream<'static, Result<ImportRecord, ExportTodosError>>,
+}
@@ -0,0 +1,34 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::application::repositories::backup::{RestoreMode, RestoreSummary, Snapshot};
+use crate::domain::use_case::UseCaseError;
+
+#[derive(Clone, Debug)]
+pub struct RestoreBackupInput {
+    pub snapshot: Snapshot,
+    pub mode: RestoreMode,
+}
+
+pub type RestoreBackupOutput = Result<RestoreSummary, RestoreBackupError>;
+
+#[derive(Debug, Error)]
+pub enum RestoreBackupError {
+    #[error("Todo title already exists")]
+    DuplicatedTitle,
+    #[error("Todo id is already taken")]
+    IdTaken,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+impl UseCaseError for RestoreBackupError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::DuplicatedTitle => "DuplicatedTitle",
+            Self::IdTaken => "IdTaken",
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
@@ -4,6 +4,7 @@ use thiserror::Error;
 
 use crate::domain::entities::todo::{Description, Title, TodoEntity, Status};
 use crate::domain::types::Date;
+use crate::domain::use_case::UseCaseError;
 
 #[derive(Clone, Debug)]
 pub struct CreateTodoInput {
@@ -22,3 +23,12 @@ pub enum CreateTodoError {
     #[error(transparent)]
     Internal(Box<dyn error::Error>),
 }
+
+impl UseCaseError for CreateTodoError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::DuplicatedTitle(..) => "DuplicatedTitle",
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
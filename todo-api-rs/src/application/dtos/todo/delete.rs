@@ -3,6 +3,7 @@ use std::error;
 use thiserror::Error;
 
 use crate::domain::types::Id;
+use crate::domain::use_case::UseCaseError;
 
 pub type DeleteTodoInput = Id;
 pub type DeleteTodoOutput = Result<(), DeleteTodoError>;
@@ -14,3 +15,12 @@ pub enum DeleteTodoError {
     #[error(transparent)]
     Internal(Box<dyn error::Error>),
 }
+
+impl UseCaseError for DeleteTodoError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "NotFound",
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::error;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::stream::BoxStream;
+use futures_util::{Stream, StreamExt};
+use thiserror::Error;
+
+use crate::domain::entities::todo::{Title, TodoEntity};
+
+#[derive(Clone, Debug)]
+pub struct ExportTodosInput {
+    pub title: Option<Title>,
+}
+
+pub type ExportTodosOutput = ExportedTodos;
+
+/// Every todo matching the input, newest first. Todos are fetched as the
+/// stream is polled, so an error may come after some of them
+pub struct ExportedTodos(BoxStream<'static, Result<TodoEntity, ExportTodosError>>);
+
+impl ExportedTodos {
+    pub fn new(todos: BoxStream<'static, Result<TodoEntity, ExportTodosError>>) -> Self {
+        Self(todos)
+    }
+}
+
+impl Stream for ExportedTodos {
+    type Item = Result<TodoEntity, ExportTodosError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ExportTodosError {
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
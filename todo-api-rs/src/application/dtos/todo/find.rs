@@ -4,6 +4,7 @@ use thiserror::Error;
 
 use crate::domain::entities::todo::TodoEntity;
 use crate::domain::types::Id;
+use crate::domain::use_case::UseCaseError;
 
 pub type FindTodoInput = Id;
 pub type FindTodoOutput = Result<TodoEntity, FindTodoError>;
@@ -15,3 +16,12 @@ pub enum FindTodoError {
     #[error(transparent)]
     Internal(Box<dyn error::Error>),
 }
+
+impl UseCaseError for FindTodoError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "NotFound",
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::application::dtos::todo::create::CreateTodoInput;
+use crate::application::repositories::todo::ImportOrigin;
+use crate::domain::entities::todo::{Title, TodoEntity};
+use crate::domain::use_case::UseCaseError;
+
+#[derive(Clone, Debug)]
+pub struct ImportTodosInput {
+    pub todos: Vec<ImportTodoInput>,
+    /// Report what importing would do, without creating anything
+    pub dry_run: bool,
+}
+
+/// Todo to create from an entry of an imported file
+#[derive(Clone, Debug)]
+pub struct ImportTodoInput {
+    /// Entry the todo comes from, when the file identifies its entries
+    pub origin: Option<ImportOrigin>,
+    pub todo: CreateTodoInput,
+}
+
+pub type ImportTodosOutput = Result<ImportedTodos, ImportTodosError>;
+
+/// Outcome of every imported entry, in the order of the input
+#[derive(Clone, Debug, Default)]
+pub struct ImportedTodos {
+    pub outcomes: Vec<ImportOutcome>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ImportOutcome {
+    /// The todo was created, or would be on a dry run
+    Created(TodoEntity),
+    Skipped(SkipReason),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum SkipReason {
+    #[error("Entry was already imported")]
+    AlreadyImported,
+    #[error("Todo with title {0} already exists")]
+    DuplicatedTitle(Title),
+}
+
+#[derive(Debug, Error)]
+pub enum ImportTodosError {
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+impl UseCaseError for ImportTodosError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
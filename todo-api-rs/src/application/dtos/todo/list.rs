@@ -4,6 +4,7 @@ use std::num::NonZeroU32;
 use thiserror::Error;
 
 use crate::domain::entities::todo::{Title, TodoEntity};
+use crate::domain::use_case::UseCaseError;
 
 #[derive(Clone, Debug)]
 pub struct ListTodosInput {
@@ -27,3 +28,11 @@ pub enum ListTodosError {
     #[error(transparent)]
     Internal(Box<dyn error::Error>),
 }
+
+impl UseCaseError for ListTodosError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
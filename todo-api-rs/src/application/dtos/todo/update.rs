@@ -4,6 +4,7 @@ use thiserror::Error;
 
 use crate::domain::entities::todo::{Description, Title, Status};
 use crate::domain::types::{Date, Id};
+use crate::domain::use_case::UseCaseError;
 
 #[derive(Clone, Debug)]
 pub struct UpdateTodoInput {
@@ -20,8 +21,21 @@ pub type UpdateTodoOutput = Result<(), UpdateTodoError>;
 pub enum UpdateTodoError {
     #[error("Todo could not be found")]
     NotFound,
+    #[error("Todo was updated concurrently")]
+    Conflict,
     #[error("Todo with title {0} already exists")]
     DuplicatedTitle(Title),
     #[error(transparent)]
     Internal(Box<dyn error::Error>),
 }
+
+impl UseCaseError for UpdateTodoError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "NotFound",
+            Self::Conflict => "Conflict",
+            Self::DuplicatedTitle(..) => "DuplicatedTitle",
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::domain::entities::webhook::{WebhookEntity, WebhookEvent, WebhookSecret, WebhookUrl};
+use crate::domain::use_case::UseCaseError;
+
+#[derive(Clone, Debug)]
+pub struct CreateWebhookInput {
+    pub url: WebhookUrl,
+    pub events: Vec<WebhookEvent>,
+    pub secret: WebhookSecret,
+}
+
+pub type CreateWebhookOutput = Result<WebhookEntity, CreateWebhookError>;
+
+#[derive(Debug, Error)]
+pub enum CreateWebhookError {
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+impl UseCaseError for CreateWebhookError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::domain::types::Id;
+use crate::domain::use_case::UseCaseError;
+
+pub type DeleteWebhookInput = Id;
+pub type DeleteWebhookOutput = Result<(), DeleteWebhookError>;
+
+#[derive(Debug, Error)]
+pub enum DeleteWebhookError {
+    #[error("Webhook could not be found")]
+    NotFound,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+impl UseCaseError for DeleteWebhookError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "NotFound",
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
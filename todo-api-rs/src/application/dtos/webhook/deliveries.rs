@@ -0,0 +1,32 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::domain::entities::webhook::{DeliveryEntity, DeliveryStatus};
+use crate::domain::types::Id;
+use crate::domain::use_case::UseCaseError;
+
+#[derive(Clone, Debug)]
+pub struct ListDeliveriesInput {
+    pub webhook_id: Id,
+    pub status: Option<DeliveryStatus>,
+}
+
+pub type ListDeliveriesOutput = Result<Vec<DeliveryEntity>, ListDeliveriesError>;
+
+#[derive(Debug, Error)]
+pub enum ListDeliveriesError {
+    #[error("Webhook could not be found")]
+    NotFound,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+impl UseCaseError for ListDeliveriesError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "NotFound",
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
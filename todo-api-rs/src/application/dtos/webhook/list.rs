@@ -0,0 +1,22 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::domain::entities::webhook::WebhookEntity;
+use crate::domain::use_case::UseCaseError;
+
+pub type ListWebhooksOutput = Result<Vec<WebhookEntity>, ListWebhooksError>;
+
+#[derive(Debug, Error)]
+pub enum ListWebhooksError {
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+impl UseCaseError for ListWebhooksError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
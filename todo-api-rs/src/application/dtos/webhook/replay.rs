@@ -0,0 +1,26 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::domain::types::Id;
+use crate::domain::use_case::UseCaseError;
+
+pub type ReplayDeliveryInput = Id;
+pub type ReplayDeliveryOutput = Result<(), ReplayDeliveryError>;
+
+#[derive(Debug, Error)]
+pub enum ReplayDeliveryError {
+    #[error("Webhook delivery could not be found")]
+    NotFound,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+impl UseCaseError for ReplayDeliveryError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "NotFound",
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
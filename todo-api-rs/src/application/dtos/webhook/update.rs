@@ -0,0 +1,34 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::domain::entities::webhook::{WebhookEntity, WebhookEvent, WebhookSecret, WebhookUrl};
+use crate::domain::types::Id;
+use crate::domain::use_case::UseCaseError;
+
+#[derive(Clone, Debug)]
+pub struct UpdateWebhookInput {
+    pub id: Id,
+    pub url: WebhookUrl,
+    pub events: Vec<WebhookEvent>,
+    pub secret: WebhookSecret,
+}
+
+pub type UpdateWebhookOutput = Result<WebhookEntity, UpdateWebhookError>;
+
+#[derive(Debug, Error)]
+pub enum UpdateWebhookError {
+    #[error("Webhook could not be found")]
+    NotFound,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+impl UseCaseError for UpdateWebhookError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound => "NotFound",
+            Self::Internal(..) => "Internal",
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod repositories;
+pub mod dispatchers;
 pub mod dtos;
+pub mod publishers;
 pub mod use_cases;
\ No newline at end of file
@@ -0,0 +1,29 @@
+use crate::domain::entities::todo::TodoEntity;
+
+/// Change that happened to a todo, carrying its state after the change,
+/// or before it in case it was deleted. Changes are published to interested
+/// parties, such as subscribers of an event stream, once the transaction that
+/// made them committed, so publishing cannot fail the use case that caused them
+#[derive(Clone, Debug)]
+pub enum TodoChange {
+    Created(TodoEntity),
+    Updated(TodoEntity),
+    Deleted(TodoEntity),
+}
+
+impl TodoChange {
+    /// Name of the change such as `todo.created`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Created(_) => "todo.created",
+            Self::Updated(_) => "todo.updated",
+            Self::Deleted(_) => "todo.deleted",
+        }
+    }
+
+    pub fn todo(&self) -> &TodoEntity {
+        match self {
+            Self::Created(todo) | Self::Updated(todo) | Self::Deleted(todo) => todo,
+        }
+    }
+}
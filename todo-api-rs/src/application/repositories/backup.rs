@@ -0,0 +1,78 @@
+use std::error;
+
+use futures_util::stream::BoxStream;
+use thiserror::Error;
+
+use crate::application::repositories::todo::{ImportOrigin, ListError, TodoStream};
+use crate::domain::entities::todo::TodoEntity;
+use crate::domain::types::{DateTime, Id};
+
+/// Backup of the tables of a tenant, for migrating between environments.
+/// Webhooks are left out, as their receivers and secrets belong to the
+/// environment, and so are their deliveries and the outbox, which only keep
+/// events until they are sent
+pub trait BackupRepository {
+    /// Every todo, fetched in batches as the stream is polled
+    fn todos(&self) -> TodoStream;
+    /// Every entry of an imported file a todo was created from
+    fn imports(&self) -> ImportStream;
+    /// Restore every table from `snapshot` at once, so a failed restore
+    /// changes nothing
+    async fn restore(
+        &mut self,
+        snapshot: Snapshot,
+        mode: RestoreMode,
+    ) -> Result<RestoreSummary, RestoreError>;
+}
+
+/// Rows of every table of a backup
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub todos: Vec<TodoEntity>,
+    /// Entries the todos were imported from, none for archives written before
+    /// they were backed up, which leave the entries as they are
+    pub imports: Option<Vec<ImportRecord>>,
+}
+
+/// Entry of an imported file, remembered with the todo it was imported as
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportRecord {
+    pub origin: ImportOrigin,
+    pub todo_id: Id,
+    pub created_at: DateTime,
+}
+
+pub type ImportStream = BoxStream<'static, Result<ImportRecord, ListError>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestoreMode {
+    /// Overwrite the rows of the snapshot, keeping the ones it does not have
+    Merge,
+    /// Overwrite the rows of the snapshot, deleting the ones it does not have
+    Replace,
+}
+
+/// Number of rows of a table changed by a restore
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TableSummary {
+    pub created: u64,
+    pub updated: u64,
+    pub unchanged: u64,
+    pub deleted: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RestoreSummary {
+    pub todos: TableSummary,
+    pub imports: TableSummary,
+}
+
+#[derive(Debug, Error)]
+pub enum RestoreError {
+    #[error("Todo title already exists")]
+    DuplicatedTitle,
+    #[error("Todo id is already taken")]
+    IdTaken,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
@@ -1,26 +1,46 @@
 use std::error;
 use std::num::NonZeroU32;
 
+use futures_util::stream::BoxStream;
 use thiserror::Error;
 
-use crate::domain::entities::todo::{Description, Title, TodoEntity, Status};
-use crate::domain::types::{Date, Id};
+use crate::domain::entities::todo::{Title, TodoEntity};
+use crate::domain::types::{DateTime, Id};
 
 pub trait TodoRepository {
+    /// Check what importing a todo with `title` from `origin` would do,
+    /// without creating it
+    async fn check_import(
+        &self,
+        title: &Title,
+        origin: Option<&ImportOrigin>,
+    ) -> Result<(), ImportError>;
     async fn create(&mut self, todo: TodoEntity) -> Result<(), CreateError>;
-    async fn delete(&mut self, todo_id: Id) -> Result<(), DeleteError>;
+    async fn delete(&mut self, todo_id: Id) -> Result<TodoEntity, DeleteError>;
     async fn find(&self, todo_id: Id) -> Result<TodoEntity, FindError>;
+    /// Create a todo from an imported entry, remembering its `origin` when the
+    /// entry has one
+    async fn import(
+        &mut self,
+        todo: TodoEntity,
+        origin: Option<ImportOrigin>,
+    ) -> Result<(), ImportError>;
     async fn list(&self, query: ListQuery) -> Result<PaginatedList, ListError>;
-    async fn update(&mut self, query: UpdateQuery) -> Result<(), UpdateError>;
+    /// Every todo matching `query`, newest first, fetched in batches as the
+    /// stream is polled rather than all at once
+    fn stream(&self, query: StreamQuery) -> TodoStream;
+    /// Save the changes of a todo, unless it was updated by someone else since
+    /// it was found, when it was last updated at `found_at`
+    async fn update(&mut self, todo: TodoEntity, found_at: DateTime) -> Result<(), UpdateError>;
 }
 
-#[derive(Clone, Debug)]
-pub struct UpdateQuery {
-    pub id: Id,
-    pub title: Title,
-    pub description: Option<Description>,
-    pub status: Status,
-    pub todo_at: Option<Date>,
+/// Entry of an imported file a todo was created from, such as the UID of an
+/// iCalendar VTODO, remembered so importing the same entry again is skipped
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ImportOrigin {
+    /// Format of the imported file, such as `ics`
+    pub format: &'static str,
+    pub uid: String,
 }
 
 #[derive(Clone, Debug)]
@@ -30,6 +50,13 @@ pub struct ListQuery {
     pub title: Option<Title>,
 }
 
+#[derive(Clone, Debug)]
+pub struct StreamQuery {
+    pub title: Option<Title>,
+}
+
+pub type TodoStream = BoxStream<'static, Result<TodoEntity, ListError>>;
+
 #[derive(Clone, Debug)]
 pub struct PaginatedList {
     pub count: u64,
@@ -60,6 +87,16 @@ pub enum FindError {
     Internal(Box<dyn error::Error>),
 }
 
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Todo was already imported")]
+    AlreadyImported,
+    #[error("Todo title already exists")]
+    DuplicatedTitle,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
 #[derive(Debug, Error)]
 pub enum ListError {
     #[error(transparent)]
@@ -70,6 +107,8 @@ pub enum ListError {
 pub enum UpdateError {
     #[error("Todo could not be found")]
     NotFound,
+    #[error("Todo was updated concurrently")]
+    Conflict,
     #[error("Todo title already exists")]
     DuplicatedTitle,
     #[error(transparent)]
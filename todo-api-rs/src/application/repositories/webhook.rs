@@ -0,0 +1,77 @@
+use std::error;
+
+use thiserror::Error;
+
+use crate::domain::entities::webhook::{DeliveryEntity, DeliveryStatus, WebhookEntity};
+use crate::domain::types::Id;
+
+pub trait WebhookRepository {
+    async fn create(&mut self, webhook: WebhookEntity) -> Result<(), CreateError>;
+    async fn delete(&mut self, webhook_id: Id) -> Result<(), DeleteError>;
+    async fn find(&self, webhook_id: Id) -> Result<WebhookEntity, FindError>;
+    async fn list(&self) -> Result<Vec<WebhookEntity>, ListError>;
+    async fn list_deliveries(
+        &self,
+        query: DeliveriesQuery,
+    ) -> Result<Vec<DeliveryEntity>, ListDeliveriesError>;
+    async fn replay_delivery(&mut self, delivery_id: Id) -> Result<(), ReplayError>;
+    async fn update(&mut self, webhook: WebhookEntity) -> Result<(), UpdateError>;
+}
+
+#[derive(Clone, Debug)]
+pub struct DeliveriesQuery {
+    pub webhook_id: Id,
+    pub status: Option<DeliveryStatus>,
+}
+
+#[derive(Debug, Error)]
+pub enum CreateError {
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteError {
+    #[error("Webhook could not be found")]
+    NotFound,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Debug, Error)]
+pub enum FindError {
+    #[error("Webhook could not be found")]
+    NotFound,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Debug, Error)]
+pub enum ListError {
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Debug, Error)]
+pub enum ListDeliveriesError {
+    #[error("Webhook could not be found")]
+    NotFound,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Webhook delivery could not be found")]
+    NotFound,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateError {
+    #[error("Webhook could not be found")]
+    NotFound,
+    #[error(transparent)]
+    Internal(Box<dyn error::Error>),
+}
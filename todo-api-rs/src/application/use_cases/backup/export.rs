@@ -0,0 +1,37 @@
+use futures_util::{StreamExt, TryStreamExt};
+
+use crate::application::dtos::backup::export::{Backup, ExportBackupOutput};
+use crate::application::dtos::todo::export::{ExportTodosError, ExportedTodos};
+use crate::application::repositories::backup::BackupRepository;
+use crate::application::repositories::todo::ListError;
+use crate::domain::types::DateTime;
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ExportBackupUseCase<T> {
+    repository: T,
+}
+
+impl<T: BackupRepository> ExportBackupUseCase<T> {
+    pub fn new(repository: T) -> Self {
+        Self { repository }
+    }
+}
+
+impl<T: BackupRepository> UseCase<(), ExportBackupOutput> for ExportBackupUseCase<T> {
+    #[tracing::instrument(name = "ExportBackupUseCase::exec", level = "debug", skip_all)]
+    async fn exec(self, _: ()) -> ExportBackupOutput {
+        let todos = self.repository.todos().map_err(|err| match err {
+            ListError::Internal(err) => ExportTodosError::Internal(err),
+        });
+        let imports = self.repository.imports().map_err(|err| match err {
+            ListError::Internal(err) => ExportTodosError::Internal(err),
+        });
+
+        Backup {
+            exported_at: DateTime::now(),
+            todos: ExportedTodos::new(todos.boxed()),
+            imports: imports.boxed(),
+        }
+    }
+}
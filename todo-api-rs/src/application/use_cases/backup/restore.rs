@@ -0,0 +1,32 @@
+use crate::application::dtos::backup::restore::{
+    RestoreBackupError, RestoreBackupInput, RestoreBackupOutput,
+};
+use crate::application::repositories::backup::{BackupRepository, RestoreError};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct RestoreBackupUseCase<T> {
+    repository: T,
+}
+
+impl<T: BackupRepository> RestoreBackupUseCase<T> {
+    pub fn new(repository: T) -> Self {
+        Self { repository }
+    }
+}
+
+impl<T: BackupRepository> UseCase<RestoreBackupInput, RestoreBackupOutput>
+    for RestoreBackupUseCase<T>
+{
+    #[tracing::instrument(name = "RestoreBackupUseCase::exec", level = "debug", skip_all)]
+    async fn exec(mut self, input: RestoreBackupInput) -> RestoreBackupOutput {
+        self.repository
+            .restore(input.snapshot, input.mode)
+            .await
+            .map_err(|err| match err {
+                RestoreError::DuplicatedTitle => RestoreBackupError::DuplicatedTitle,
+                RestoreError::IdTaken => RestoreBackupError::IdTaken,
+                RestoreError::Internal(err) => RestoreBackupError::Internal(err),
+            })
+    }
+}
@@ -1,27 +1,36 @@
+use crate::application::dispatchers::todo::TodoEventDispatcher;
 use crate::application::dtos::todo::create::{CreateTodoError, CreateTodoInput, CreateTodoOutput};
 use crate::application::repositories::todo::{CreateError, TodoRepository};
 use crate::domain::entities::todo::{NewProps, TodoEntity};
 use crate::domain::use_case::UseCase;
 
 #[derive(Debug)]
-pub struct CreateTodoUseCase<T> {
+pub struct CreateTodoUseCase<T, D> {
     repository: T,
+    dispatcher: D,
 }
 
-impl<T: TodoRepository> CreateTodoUseCase<T> {
-    pub fn new(repository: T) -> Self {
-        Self { repository }
+impl<T: TodoRepository, D: TodoEventDispatcher> CreateTodoUseCase<T, D> {
+    pub fn new(repository: T, dispatcher: D) -> Self {
+        Self {
+            repository,
+            dispatcher,
+        }
     }
 }
 
-impl<T: TodoRepository> UseCase<CreateTodoInput, CreateTodoOutput> for CreateTodoUseCase<T> {
+impl<T: TodoRepository, D: TodoEventDispatcher> UseCase<CreateTodoInput, CreateTodoOutput>
+    for CreateTodoUseCase<T, D>
+{
+    #[tracing::instrument(name = "CreateTodoUseCase::exec", level = "debug", skip_all)]
     async fn exec(mut self, input: CreateTodoInput) -> CreateTodoOutput {
-        let entity = TodoEntity::new(NewProps {
+        let mut entity = TodoEntity::new(NewProps {
             title: input.title.clone(),
             status: input.status,
             description: input.description,
             todo_at: input.todo_at,
         });
+        let events = entity.drain_events();
 
         if let Err(err) = self.repository.create(entity.clone()).await {
             return Err(match err {
@@ -30,6 +39,8 @@ impl<T: TodoRepository> UseCase<CreateTodoInput, CreateTodoOutput> for CreateTod
             });
         }
 
+        self.dispatcher.dispatch(events);
+
         Ok(entity)
     }
 }
@@ -1,26 +1,40 @@
+use crate::application::dispatchers::todo::TodoEventDispatcher;
 use crate::application::dtos::todo::delete::{DeleteTodoError, DeleteTodoInput, DeleteTodoOutput};
 use crate::application::repositories::todo::{DeleteError, TodoRepository};
 use crate::domain::use_case::UseCase;
 
 #[derive(Debug)]
-pub struct DeleteTodoUseCase<T> {
+pub struct DeleteTodoUseCase<T, D> {
     repository: T,
+    dispatcher: D,
 }
 
-impl<T: TodoRepository> DeleteTodoUseCase<T> {
-    pub fn new(repository: T) -> Self {
-        Self { repository }
+impl<T: TodoRepository, D: TodoEventDispatcher> DeleteTodoUseCase<T, D> {
+    pub fn new(repository: T, dispatcher: D) -> Self {
+        Self {
+            repository,
+            dispatcher,
+        }
     }
 }
 
-impl<T: TodoRepository> UseCase<DeleteTodoInput, DeleteTodoOutput> for DeleteTodoUseCase<T> {
+impl<T: TodoRepository, D: TodoEventDispatcher> UseCase<DeleteTodoInput, DeleteTodoOutput>
+    for DeleteTodoUseCase<T, D>
+{
+    #[tracing::instrument(name = "DeleteTodoUseCase::exec", level = "debug", skip_all)]
     async fn exec(mut self, todo_id: DeleteTodoInput) -> DeleteTodoOutput {
-        self.repository
+        let mut entity = self
+            .repository
             .delete(todo_id)
             .await
             .map_err(|err| match err {
                 DeleteError::NotFound => DeleteTodoError::NotFound,
                 DeleteError::Internal(err) => DeleteTodoError::Internal(err),
-            })
+            })?;
+
+        entity.delete();
+        self.dispatcher.dispatch(entity.drain_events());
+
+        Ok(())
     }
 }
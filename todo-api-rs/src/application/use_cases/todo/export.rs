@@ -0,0 +1,31 @@
+use futures_util::{StreamExt, TryStreamExt};
+
+use crate::application::dtos::todo::export::{
+    ExportTodosError, ExportTodosInput, ExportTodosOutput, ExportedTodos,
+};
+use crate::application::repositories::todo::{ListError, StreamQuery, TodoRepository};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ExportTodosUseCase<T> {
+    repository: T,
+}
+
+impl<T: TodoRepository> ExportTodosUseCase<T> {
+    pub fn new(repository: T) -> Self {
+        Self { repository }
+    }
+}
+
+impl<T: TodoRepository> UseCase<ExportTodosInput, ExportTodosOutput> for ExportTodosUseCase<T> {
+    #[tracing::instrument(name = "ExportTodosUseCase::exec", level = "debug", skip_all)]
+    async fn exec(self, input: ExportTodosInput) -> ExportTodosOutput {
+        let query = StreamQuery { title: input.title };
+
+        let todos = self.repository.stream(query).map_err(|err| match err {
+            ListError::Internal(err) => ExportTodosError::Internal(err),
+        });
+
+        ExportedTodos::new(todos.boxed())
+    }
+}
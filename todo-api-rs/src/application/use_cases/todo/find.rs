@@ -14,6 +14,7 @@ impl<T: TodoRepository> FindTodoUseCase<T> {
 }
 
 impl<T: TodoRepository> UseCase<FindTodoInput, FindTodoOutput> for FindTodoUseCase<T> {
+    #[tracing::instrument(name = "FindTodoUseCase::exec", level = "debug", skip_all)]
     async fn exec(self, todo_id: FindTodoInput) -> FindTodoOutput {
         self.repository
             .find(todo_id)
@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+
+use crate::application::dispatchers::todo::TodoEventDispatcher;
+use crate::application::dtos::todo::import::{
+    ImportOutcome, ImportTodosError, ImportTodosInput, ImportTodosOutput, ImportedTodos, SkipReason,
+};
+use crate::application::repositories::todo::{ImportError, TodoRepository};
+use crate::domain::entities::todo::{NewProps, TodoEntity};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ImportTodosUseCase<T, D> {
+    repository: T,
+    dispatcher: D,
+}
+
+impl<T: TodoRepository, D: TodoEventDispatcher> ImportTodosUseCase<T, D> {
+    pub fn new(repository: T, dispatcher: D) -> Self {
+        Self {
+            repository,
+            dispatcher,
+        }
+    }
+}
+
+impl<T: TodoRepository, D: TodoEventDispatcher> UseCase<ImportTodosInput, ImportTodosOutput>
+    for ImportTodosUseCase<T, D>
+{
+    /// Every todo is created on its own, so an error stops the import but keeps
+    /// the todos created until then, which are skipped when importing again
+    #[tracing::instrument(name = "ImportTodosUseCase::exec", level = "debug", skip_all)]
+    async fn exec(mut self, input: ImportTodosInput) -> ImportTodosOutput {
+        // a dry run creates nothing, so entries of the file are also checked
+        // against the ones before them
+        let mut seen_titles = HashSet::new();
+        let mut seen_origins = HashSet::new();
+
+        let mut imported = ImportedTodos::default();
+        for entry in input.todos {
+            let mut entity = TodoEntity::new(NewProps {
+                title: entry.todo.title.clone(),
+                status: entry.todo.status,
+                description: entry.todo.description,
+                todo_at: entry.todo.todo_at,
+            });
+            let events = entity.drain_events();
+
+            let result = if input.dry_run {
+                let title = entity.title();
+                let origin = entry.origin.as_ref();
+                if origin.is_some_and(|origin| !seen_origins.insert(origin.clone())) {
+                    Err(ImportError::AlreadyImported)
+                } else if !seen_titles.insert(title.clone()) {
+                    Err(ImportError::DuplicatedTitle)
+                } else {
+                    self.repository.check_import(title, origin).await
+                }
+            } else {
+                self.repository
+                    .import(entity.clone(), entry.origin)
+                    .await
+                    .inspect(|()| self.dispatcher.dispatch(events))
+            };
+
+            let outcome = match result {
+                Ok(()) => ImportOutcome::Created(entity),
+                Err(ImportError::AlreadyImported) => {
+                    ImportOutcome::Skipped(SkipReason::AlreadyImported)
+                }
+                Err(ImportError::DuplicatedTitle) => {
+                    ImportOutcome::Skipped(SkipReason::DuplicatedTitle(entry.todo.title))
+                }
+                Err(ImportError::Internal(err)) => return Err(ImportTodosError::Internal(err)),
+            };
+            imported.outcomes.push(outcome);
+        }
+
+        Ok(imported)
+    }
+}
@@ -16,6 +16,7 @@ impl<T: TodoRepository> ListTodosUseCase<T> {
 }
 
 impl<T: TodoRepository> UseCase<ListTodosInput, ListTodosOutput> for ListTodosUseCase<T> {
+    #[tracing::instrument(name = "ListTodosUseCase::exec", level = "debug", skip_all)]
     async fn exec(self, input: ListTodosInput) -> ListTodosOutput {
         let query = ListQuery {
             page: input.page,
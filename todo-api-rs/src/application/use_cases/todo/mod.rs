@@ -1,5 +1,7 @@
 pub mod create;
 pub mod delete;
+pub mod export;
 pub mod find;
+pub mod import;
 pub mod list;
 pub mod update;
\ No newline at end of file
@@ -1,35 +1,69 @@
+use crate::application::dispatchers::todo::TodoEventDispatcher;
 use crate::application::dtos::todo::update::{UpdateTodoError, UpdateTodoInput, UpdateTodoOutput};
-use crate::application::repositories::todo::{TodoRepository, UpdateError, UpdateQuery};
+use crate::application::repositories::todo::{FindError, TodoRepository, UpdateError};
+use crate::domain::entities::todo::Status;
 use crate::domain::use_case::UseCase;
 
 #[derive(Debug)]
-pub struct UpdateTodoUseCase<T> {
+pub struct UpdateTodoUseCase<T, D> {
     repository: T,
+    dispatcher: D,
 }
 
-impl<T: TodoRepository> UpdateTodoUseCase<T> {
-    pub fn new(repository: T) -> Self {
-        Self { repository }
+impl<T: TodoRepository, D: TodoEventDispatcher> UpdateTodoUseCase<T, D> {
+    pub fn new(repository: T, dispatcher: D) -> Self {
+        Self {
+            repository,
+            dispatcher,
+        }
     }
 }
 
-impl<T: TodoRepository> UseCase<UpdateTodoInput, UpdateTodoOutput> for UpdateTodoUseCase<T> {
+impl<T: TodoRepository, D: TodoEventDispatcher> UseCase<UpdateTodoInput, UpdateTodoOutput>
+    for UpdateTodoUseCase<T, D>
+{
+    #[tracing::instrument(name = "UpdateTodoUseCase::exec", level = "debug", skip_all)]
     async fn exec(mut self, input: UpdateTodoInput) -> UpdateTodoOutput {
-        let query = UpdateQuery {
-            id: input.id,
-            title: input.title.clone(),
-            description: input.description,
-            status: input.status,
-            todo_at: input.todo_at,
-        };
+        let mut entity = self
+            .repository
+            .find(input.id)
+            .await
+            .map_err(|err| match err {
+                FindError::NotFound => UpdateTodoError::NotFound,
+                FindError::Internal(err) => UpdateTodoError::Internal(err),
+            })?;
+        let found_at = entity.updated_at();
+
+        entity.rename(input.title.clone());
+        entity.describe(input.description);
+        entity.reschedule(input.todo_at);
+        if entity.status() != &input.status {
+            // cannot fail since the status differs from the current one
+            match input.status {
+                Status::Todo => entity.reopen(),
+                Status::InProgress => entity.start(),
+                Status::Done => entity.complete(),
+            }
+            .map_err(|err| UpdateTodoError::Internal(err.into()))?;
+        }
+
+        let events = entity.drain_events();
+        if events.is_empty() {
+            return Ok(());
+        }
 
         self.repository
-            .update(query)
+            .update(entity, found_at)
             .await
             .map_err(|err| match err {
                 UpdateError::NotFound => UpdateTodoError::NotFound,
+                UpdateError::Conflict => UpdateTodoError::Conflict,
                 UpdateError::DuplicatedTitle => UpdateTodoError::DuplicatedTitle(input.title),
                 UpdateError::Internal(err) => UpdateTodoError::Internal(err),
-            })
+            })?;
+
+        self.dispatcher.dispatch(events);
+
+        Ok(())
     }
 }
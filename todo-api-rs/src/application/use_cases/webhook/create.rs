@@ -0,0 +1,39 @@
+use crate::application::dtos::webhook::create::{
+    CreateWebhookError, CreateWebhookInput, CreateWebhookOutput,
+};
+use crate::application::repositories::webhook::{CreateError, WebhookRepository};
+use crate::domain::entities::webhook::{NewProps, WebhookEntity};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct CreateWebhookUseCase<T> {
+    repository: T,
+}
+
+impl<T: WebhookRepository> CreateWebhookUseCase<T> {
+    pub fn new(repository: T) -> Self {
+        Self { repository }
+    }
+}
+
+impl<T: WebhookRepository> UseCase<CreateWebhookInput, CreateWebhookOutput>
+    for CreateWebhookUseCase<T>
+{
+    #[tracing::instrument(name = "CreateWebhookUseCase::exec", level = "debug", skip_all)]
+    async fn exec(mut self, input: CreateWebhookInput) -> CreateWebhookOutput {
+        let entity = WebhookEntity::new(NewProps {
+            url: input.url,
+            events: input.events,
+            secret: input.secret,
+        });
+
+        self.repository
+            .create(entity.clone())
+            .await
+            .map_err(|err| match err {
+                CreateError::Internal(src) => CreateWebhookError::Internal(src),
+            })?;
+
+        Ok(entity)
+    }
+}
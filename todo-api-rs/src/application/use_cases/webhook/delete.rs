@@ -0,0 +1,31 @@
+use crate::application::dtos::webhook::delete::{
+    DeleteWebhookError, DeleteWebhookInput, DeleteWebhookOutput,
+};
+use crate::application::repositories::webhook::{DeleteError, WebhookRepository};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct DeleteWebhookUseCase<T> {
+    repository: T,
+}
+
+impl<T: WebhookRepository> DeleteWebhookUseCase<T> {
+    pub fn new(repository: T) -> Self {
+        Self { repository }
+    }
+}
+
+impl<T: WebhookRepository> UseCase<DeleteWebhookInput, DeleteWebhookOutput>
+    for DeleteWebhookUseCase<T>
+{
+    #[tracing::instrument(name = "DeleteWebhookUseCase::exec", level = "debug", skip_all)]
+    async fn exec(mut self, webhook_id: DeleteWebhookInput) -> DeleteWebhookOutput {
+        self.repository
+            .delete(webhook_id)
+            .await
+            .map_err(|err| match err {
+                DeleteError::NotFound => DeleteWebhookError::NotFound,
+                DeleteError::Internal(src) => DeleteWebhookError::Internal(src),
+            })
+    }
+}
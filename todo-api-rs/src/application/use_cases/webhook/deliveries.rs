@@ -0,0 +1,38 @@
+use crate::application::dtos::webhook::deliveries::{
+    ListDeliveriesError, ListDeliveriesInput, ListDeliveriesOutput,
+};
+use crate::application::repositories::webhook::{
+    DeliveriesQuery, ListDeliveriesError as RepositoryError, WebhookRepository,
+};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ListDeliveriesUseCase<T> {
+    repository: T,
+}
+
+impl<T: WebhookRepository> ListDeliveriesUseCase<T> {
+    pub fn new(repository: T) -> Self {
+        Self { repository }
+    }
+}
+
+impl<T: WebhookRepository> UseCase<ListDeliveriesInput, ListDeliveriesOutput>
+    for ListDeliveriesUseCase<T>
+{
+    #[tracing::instrument(name = "ListDeliveriesUseCase::exec", level = "debug", skip_all)]
+    async fn exec(self, input: ListDeliveriesInput) -> ListDeliveriesOutput {
+        let query = DeliveriesQuery {
+            webhook_id: input.webhook_id,
+            status: input.status,
+        };
+
+        self.repository
+            .list_deliveries(query)
+            .await
+            .map_err(|err| match err {
+                RepositoryError::NotFound => ListDeliveriesError::NotFound,
+                RepositoryError::Internal(src) => ListDeliveriesError::Internal(src),
+            })
+    }
+}
@@ -0,0 +1,23 @@
+use crate::application::dtos::webhook::list::{ListWebhooksError, ListWebhooksOutput};
+use crate::application::repositories::webhook::{ListError, WebhookRepository};
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct ListWebhooksUseCase<T> {
+    repository: T,
+}
+
+impl<T: WebhookRepository> ListWebhooksUseCase<T> {
+    pub fn new(repository: T) -> Self {
+        Self { repository }
+    }
+}
+
+impl<T: WebhookRepository> UseCase<(), ListWebhooksOutput> for ListWebhooksUseCase<T> {
+    #[tracing::instrument(name = "ListWebhooksUseCase::exec", level = "debug", skip_all)]
+    async fn exec(self, _input: ()) -> ListWebhooksOutput {
+        self.repository.list().await.map_err(|err| match err {
+            ListError::Internal(src) => ListWebhooksError::Internal(src),
+        })
+    }
+}
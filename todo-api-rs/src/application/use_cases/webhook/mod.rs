@@ -0,0 +1,6 @@
+pub mod create;
+pub mod delete;
+pub mod deliveries;
+pub mod list;
+pub mod replay;
+pub mod update;
\ No newline at end of file
@@ -0,0 +1,32 @@
+use crate::application::dtos::webhook::replay::{
+    ReplayDeliveryError, ReplayDeliveryInput, ReplayDeliveryOutput,
+};
+use crate::application::repositories::webhook::{ReplayError, WebhookRepository};
+use crate::domain::use_case::UseCase;
+
+/// Schedule a delivery to be attempted again right away, with a fresh retry budget
+#[derive(Debug)]
+pub struct ReplayDeliveryUseCase<T> {
+    repository: T,
+}
+
+impl<T: WebhookRepository> ReplayDeliveryUseCase<T> {
+    pub fn new(repository: T) -> Self {
+        Self { repository }
+    }
+}
+
+impl<T: WebhookRepository> UseCase<ReplayDeliveryInput, ReplayDeliveryOutput>
+    for ReplayDeliveryUseCase<T>
+{
+    #[tracing::instrument(name = "ReplayDeliveryUseCase::exec", level = "debug", skip_all)]
+    async fn exec(mut self, delivery_id: ReplayDeliveryInput) -> ReplayDeliveryOutput {
+        self.repository
+            .replay_delivery(delivery_id)
+            .await
+            .map_err(|err| match err {
+                ReplayError::NotFound => ReplayDeliveryError::NotFound,
+                ReplayError::Internal(src) => ReplayDeliveryError::Internal(src),
+            })
+    }
+}
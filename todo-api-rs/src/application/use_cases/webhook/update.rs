@@ -0,0 +1,49 @@
+use crate::application::dtos::webhook::update::{
+    UpdateWebhookError, UpdateWebhookInput, UpdateWebhookOutput,
+};
+use crate::application::repositories::webhook::{FindError, UpdateError, WebhookRepository};
+use crate::domain::entities::webhook::NewProps;
+use crate::domain::use_case::UseCase;
+
+#[derive(Debug)]
+pub struct UpdateWebhookUseCase<T> {
+    repository: T,
+}
+
+impl<T: WebhookRepository> UpdateWebhookUseCase<T> {
+    pub fn new(repository: T) -> Self {
+        Self { repository }
+    }
+}
+
+impl<T: WebhookRepository> UseCase<UpdateWebhookInput, UpdateWebhookOutput>
+    for UpdateWebhookUseCase<T>
+{
+    #[tracing::instrument(name = "UpdateWebhookUseCase::exec", level = "debug", skip_all)]
+    async fn exec(mut self, input: UpdateWebhookInput) -> UpdateWebhookOutput {
+        let mut entity = self
+            .repository
+            .find(input.id)
+            .await
+            .map_err(|err| match err {
+                FindError::NotFound => UpdateWebhookError::NotFound,
+                FindError::Internal(src) => UpdateWebhookError::Internal(src),
+            })?;
+
+        entity.change(NewProps {
+            url: input.url,
+            events: input.events,
+            secret: input.secret,
+        });
+
+        self.repository
+            .update(entity.clone())
+            .await
+            .map_err(|err| match err {
+                UpdateError::NotFound => UpdateWebhookError::NotFound,
+                UpdateError::Internal(src) => UpdateWebhookError::Internal(src),
+            })?;
+
+        Ok(entity)
+    }
+}
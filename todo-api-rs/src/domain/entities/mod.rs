@@ -1 +1,2 @@
-pub mod todo;
\ No newline at end of file
+pub mod todo;
+pub mod webhook;
\ No newline at end of file
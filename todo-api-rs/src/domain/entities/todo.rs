@@ -1,31 +1,36 @@
-use std::fmt;
+use std::{fmt, mem};
 
 use thiserror::Error;
 
+use crate::domain::events::todo::TodoEvent;
 use crate::domain::types::{Date, DateTime, Id};
 
 #[derive(Clone, Debug)]
 pub struct TodoEntity {
     id: Id,
-    pub title: Title,
-    pub description: Option<Description>,
-    pub status: Status,
-    pub todo_at: Option<Date>,
+    title: Title,
+    description: Option<Description>,
+    status: Status,
+    todo_at: Option<Date>,
     created_at: DateTime,
     updated_at: DateTime,
+    events: Vec<TodoEvent>,
 }
 
 impl TodoEntity {
+    /// Create a todo that did not exist yet, recording [`TodoEvent::Created`]
     pub fn new(props: NewProps) -> Self {
         let now = DateTime::now();
+        let id = Id::new();
         Self {
-            id: Id::new(),
+            id,
             title: props.title,
             description: props.description,
             status: props.status,
             todo_at: props.todo_at,
             created_at: now,
             updated_at: now,
+            events: vec![TodoEvent::Created { id }],
         }
     }
 
@@ -38,6 +43,7 @@ impl TodoEntity {
             todo_at: props.todo_at,
             created_at: props.created_at,
             updated_at: props.updated_at,
+            events: Vec::new(),
         }
     }
 
@@ -45,6 +51,22 @@ impl TodoEntity {
         self.id
     }
 
+    pub fn title(&self) -> &Title {
+        &self.title
+    }
+
+    pub fn description(&self) -> Option<&Description> {
+        self.description.as_ref()
+    }
+
+    pub fn status(&self) -> &Status {
+        &self.status
+    }
+
+    pub fn todo_at(&self) -> Option<Date> {
+        self.todo_at
+    }
+
     pub fn created_at(&self) -> DateTime {
         self.created_at
     }
@@ -52,6 +74,87 @@ impl TodoEntity {
     pub fn updated_at(&self) -> DateTime {
         self.updated_at
     }
+
+    /// Change the title, doing nothing if it is the same
+    pub fn rename(&mut self, title: Title) {
+        if self.title == title {
+            return;
+        }
+
+        self.title = title.clone();
+        self.record(TodoEvent::Renamed { id: self.id, title });
+    }
+
+    /// Change or remove the description, doing nothing if it is the same
+    pub fn describe(&mut self, description: Option<Description>) {
+        if self.description == description {
+            return;
+        }
+
+        self.description = description.clone();
+        self.record(TodoEvent::Described {
+            id: self.id,
+            description,
+        });
+    }
+
+    /// Change or remove the date the todo is planned for, doing nothing if it is the same
+    pub fn reschedule(&mut self, todo_at: Option<Date>) {
+        if self.todo_at == todo_at {
+            return;
+        }
+
+        self.todo_at = todo_at;
+        self.record(TodoEvent::Rescheduled {
+            id: self.id,
+            todo_at,
+        });
+    }
+
+    /// Mark the todo as in progress
+    pub fn start(&mut self) -> Result<(), TransitionError> {
+        self.transition(Status::InProgress)?;
+        self.record(TodoEvent::Started { id: self.id });
+        Ok(())
+    }
+
+    /// Mark the todo as done
+    pub fn complete(&mut self) -> Result<(), TransitionError> {
+        self.transition(Status::Done)?;
+        self.record(TodoEvent::Completed { id: self.id });
+        Ok(())
+    }
+
+    /// Move the todo back to [`Status::Todo`], whether it was done or in progress
+    pub fn reopen(&mut self) -> Result<(), TransitionError> {
+        self.transition(Status::Todo)?;
+        self.record(TodoEvent::Reopened { id: self.id });
+        Ok(())
+    }
+
+    /// Record that the todo was deleted
+    pub fn delete(&mut self) {
+        self.record(TodoEvent::Deleted { id: self.id });
+    }
+
+    /// Take the events recorded since the todo was created, loaded or last drained
+    pub fn drain_events(&mut self) -> Vec<TodoEvent> {
+        mem::take(&mut self.events)
+    }
+
+    fn transition(&mut self, status: Status) -> Result<(), TransitionError> {
+        if self.status == status {
+            return Err(TransitionError(status));
+        }
+
+        self.status = status;
+        Ok(())
+    }
+
+    fn record(&mut self, event: TodoEvent) {
+        self.updated_at = DateTime::now();
+        self.events.push(event);
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -98,10 +201,6 @@ impl Title {
         Ok(Self(title))
     }
 
-    pub fn into_inner(self) -> String {
-        self.0
-    }
-
     pub fn as_str(&self) -> &str {
         self.0.as_str()
     }
@@ -128,10 +227,6 @@ impl Description {
         Ok(Self(description))
     }
 
-    pub fn into_inner(self) -> String {
-        self.0
-    }
-
     pub fn as_str(&self) -> &str {
         &self.0
     }
@@ -198,8 +293,29 @@ pub enum DescriptionError {
 )]
 pub struct StatusError;
 
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("Todo status is already {0}")]
+pub struct TransitionError(pub Status);
+
+/// Todos shared by the tests of every layer
+#[cfg(test)]
+pub mod fixtures {
+    use super::*;
+
+    /// New todo with `title` and `status`, without description nor date
+    pub fn todo(title: &str, status: Status) -> TodoEntity {
+        TodoEntity::new(NewProps {
+            title: Title::new(title).unwrap(),
+            description: None,
+            status,
+            todo_at: None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::fixtures::todo;
     use super::*;
 
     #[test]
@@ -274,6 +390,75 @@ mod tests {
         assert_eq!(Err(StatusError), invalid);
     }
 
+    #[test]
+    fn new_todo_records_creation() {
+        let mut todo = todo("Title", Status::Todo);
+        let id = todo.id();
+        assert_eq!(vec![TodoEvent::Created { id }], todo.drain_events());
+        assert!(todo.drain_events().is_empty());
+    }
+
+    #[test]
+    fn changes_record_events() {
+        let mut todo = todo("Title", Status::Todo);
+        let id = todo.id();
+        let created_at = todo.updated_at();
+        todo.drain_events();
+
+        let title = Title::new("Renamed").unwrap();
+        let description = Description::new("Description").ok();
+        let todo_at = Some(Date::now());
+        todo.rename(title.clone());
+        todo.describe(description.clone());
+        todo.reschedule(todo_at);
+        todo.start().unwrap();
+        todo.complete().unwrap();
+        todo.reopen().unwrap();
+        todo.delete();
+
+        assert_eq!(&title, todo.title());
+        assert_eq!(description.as_ref(), todo.description());
+        assert_eq!(todo_at, todo.todo_at());
+        assert_eq!(&Status::Todo, todo.status());
+        assert!(todo.updated_at() >= created_at);
+        assert_eq!(
+            vec![
+                TodoEvent::Renamed { id, title },
+                TodoEvent::Described { id, description },
+                TodoEvent::Rescheduled { id, todo_at },
+                TodoEvent::Started { id },
+                TodoEvent::Completed { id },
+                TodoEvent::Reopened { id },
+                TodoEvent::Deleted { id },
+            ],
+            todo.drain_events(),
+        );
+    }
+
+    #[test]
+    fn unchanged_values_record_nothing() {
+        let mut todo = todo("Title", Status::Todo);
+        todo.drain_events();
+
+        todo.rename(Title::new("Title").unwrap());
+        todo.describe(None);
+        todo.reschedule(None);
+        assert!(todo.drain_events().is_empty());
+    }
+
+    #[test]
+    fn transition_to_same_status_fails() {
+        let mut todo = todo("Title", Status::Done);
+        todo.drain_events();
+
+        assert_eq!(Err(TransitionError(Status::Done)), todo.complete());
+        todo.start().unwrap();
+        assert_eq!(Err(TransitionError(Status::InProgress)), todo.start());
+        todo.reopen().unwrap();
+        assert_eq!(Err(TransitionError(Status::Todo)), todo.reopen());
+        assert_eq!(2, todo.drain_events().len());
+    }
+
     #[test]
     fn status_formats_to_string() {
         let srcs = ["todo", "in_progress", "done"];
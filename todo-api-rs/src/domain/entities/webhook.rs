@@ -0,0 +1,340 @@
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::domain::types::{DateTime, Id};
+
+/// Subscription of an integrator to todo changes, which are pushed to `url`
+/// and signed with `secret`
+#[derive(Clone, Debug)]
+pub struct WebhookEntity {
+    id: Id,
+    pub url: WebhookUrl,
+    pub events: Vec<WebhookEvent>,
+    pub secret: WebhookSecret,
+    disabled_reason: Option<String>,
+    created_at: DateTime,
+}
+
+impl WebhookEntity {
+    pub fn new(props: NewProps) -> Self {
+        Self {
+            id: Id::new(),
+            url: props.url,
+            events: props.events,
+            secret: props.secret,
+            disabled_reason: None,
+            created_at: DateTime::now(),
+        }
+    }
+
+    pub fn init(props: InitProps) -> Self {
+        Self {
+            id: props.id,
+            url: props.url,
+            events: props.events,
+            secret: props.secret,
+            disabled_reason: props.disabled_reason,
+            created_at: props.created_at,
+        }
+    }
+
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Why nothing is delivered to the webhook, until it's changed
+    pub fn disabled_reason(&self) -> Option<&str> {
+        self.disabled_reason.as_deref()
+    }
+
+    pub fn created_at(&self) -> DateTime {
+        self.created_at
+    }
+
+    /// Replace the url, events and secret of the webhook, which enables it
+    /// again when it was disabled
+    pub fn change(&mut self, props: NewProps) {
+        self.url = props.url;
+        self.events = props.events;
+        self.secret = props.secret;
+        self.disabled_reason = None;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NewProps {
+    pub url: WebhookUrl,
+    pub events: Vec<WebhookEvent>,
+    pub secret: WebhookSecret,
+}
+
+#[derive(Clone, Debug)]
+pub struct InitProps {
+    pub id: Id,
+    pub url: WebhookUrl,
+    pub events: Vec<WebhookEvent>,
+    pub secret: WebhookSecret,
+    pub disabled_reason: Option<String>,
+    pub created_at: DateTime,
+}
+
+/// Attempt to push an event to a webhook, retried until delivered or dead
+#[derive(Clone, Debug)]
+pub struct DeliveryEntity {
+    pub id: Id,
+    pub webhook_id: Id,
+    pub event: WebhookEvent,
+    pub status: DeliveryStatus,
+    /// Attempts since the delivery was created or last replayed
+    pub attempt_count: u32,
+    pub next_attempt_at: Option<DateTime>,
+    pub last_error: Option<String>,
+    pub attempts: Vec<DeliveryAttempt>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Clone, Debug)]
+pub struct DeliveryAttempt {
+    pub attempted_at: DateTime,
+    /// Status code of the response, missing when no response was received
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    /// Every attempt failed, so it's only retried when replayed
+    Dead,
+}
+
+impl DeliveryStatus {
+    const PENDING_STR: &'static str = "pending";
+    const DELIVERED_STR: &'static str = "delivered";
+    const DEAD_STR: &'static str = "dead";
+
+    pub fn parse_str(value: &str) -> Result<Self, DeliveryStatusError> {
+        match value {
+            Self::PENDING_STR => Ok(Self::Pending),
+            Self::DELIVERED_STR => Ok(Self::Delivered),
+            Self::DEAD_STR => Ok(Self::Dead),
+            _ => Err(DeliveryStatusError),
+        }
+    }
+}
+
+impl fmt::Display for DeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pending => f.write_str(Self::PENDING_STR),
+            Self::Delivered => f.write_str(Self::DELIVERED_STR),
+            Self::Dead => f.write_str(Self::DEAD_STR),
+        }
+    }
+}
+
+/// Event a webhook subscribes to, named after the resource it's about
+#[allow(clippy::enum_variant_names)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WebhookEvent {
+    TodoCreated,
+    TodoUpdated,
+    TodoDeleted,
+}
+
+impl WebhookEvent {
+    const TODO_CREATED_STR: &'static str = "todo.created";
+    const TODO_UPDATED_STR: &'static str = "todo.updated";
+    const TODO_DELETED_STR: &'static str = "todo.deleted";
+
+    pub fn parse_str(value: &str) -> Result<Self, WebhookEventError> {
+        match value {
+            Self::TODO_CREATED_STR => Ok(Self::TodoCreated),
+            Self::TODO_UPDATED_STR => Ok(Self::TodoUpdated),
+            Self::TODO_DELETED_STR => Ok(Self::TodoDeleted),
+            _ => Err(WebhookEventError),
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TodoCreated => f.write_str(Self::TODO_CREATED_STR),
+            Self::TodoUpdated => f.write_str(Self::TODO_UPDATED_STR),
+            Self::TodoDeleted => f.write_str(Self::TODO_DELETED_STR),
+        }
+    }
+}
+
+/// Absolute `https` URL, as deliveries carry the todos of a tenant
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WebhookUrl(String);
+
+impl WebhookUrl {
+    pub const MAX_LENGTH: usize = 2048;
+
+    pub fn new(url: impl Into<String>) -> Result<Self, WebhookUrlError> {
+        let url: String = url.into();
+        if url.len() > Self::MAX_LENGTH {
+            return Err(WebhookUrlError::Length);
+        }
+
+        let host = url
+            .strip_prefix("https://")
+            .and_then(|rest| rest.split(['/', '?', '#']).next())
+            .filter(|host| !host.is_empty());
+
+        if host.is_none() || url.chars().any(char::is_whitespace) {
+            return Err(WebhookUrlError::Invalid);
+        }
+
+        Ok(Self(url))
+    }
+
+    /// Url of a stored webhook, which is not checked again as it may predate
+    /// the current rules, such as the `http` url of a disabled webhook
+    pub fn init(url: impl Into<String>) -> Self {
+        Self(url.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for WebhookUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Shared secret used to sign deliveries, so receivers can verify their origin
+#[derive(Clone, PartialEq, Eq)]
+pub struct WebhookSecret(String);
+
+impl WebhookSecret {
+    pub const MIN_LENGTH: usize = 16;
+    pub const MAX_LENGTH: usize = 256;
+
+    pub fn new(secret: impl Into<String>) -> Result<Self, WebhookSecretError> {
+        let secret: String = secret.into();
+        if secret.len() < Self::MIN_LENGTH || secret.len() > Self::MAX_LENGTH {
+            return Err(WebhookSecretError::Length);
+        }
+
+        Ok(Self(secret))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for WebhookSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WebhookSecret(..)")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum WebhookUrlError {
+    #[error("Webhook url must be an absolute https url")]
+    Invalid,
+    #[error("Webhook url cannot have more than 2048 characters")]
+    Length,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum WebhookSecretError {
+    #[error("Webhook secret must have between 16 and 256 characters")]
+    Length,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error(
+    "Webhook event must be one the following values: {}, {}, {}",
+    WebhookEvent::TODO_CREATED_STR,
+    WebhookEvent::TODO_UPDATED_STR,
+    WebhookEvent::TODO_DELETED_STR
+)]
+pub struct WebhookEventError;
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error(
+    "Delivery status must be one the following values: {}, {}, {}",
+    DeliveryStatus::PENDING_STR,
+    DeliveryStatus::DELIVERED_STR,
+    DeliveryStatus::DEAD_STR
+)]
+pub struct DeliveryStatusError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_must_be_absolute_https() {
+        assert!(WebhookUrl::new("https://example.com/hooks").is_ok());
+        assert!(WebhookUrl::new("https://127.0.0.1:8443").is_ok());
+
+        let invalid = [
+            "",
+            "example.com",
+            "http://example.com/hooks",
+            "ftp://example.com",
+            "https://",
+            "https://a b",
+        ];
+        for url in invalid {
+            assert_eq!(WebhookUrl::new(url), Err(WebhookUrlError::Invalid), "{url}");
+        }
+
+        let long = format!("https://example.com/{}", "a".repeat(WebhookUrl::MAX_LENGTH));
+        assert_eq!(WebhookUrl::new(long), Err(WebhookUrlError::Length));
+    }
+
+    #[test]
+    fn changes_enable_disabled_webhooks() {
+        let mut webhook = WebhookEntity::init(InitProps {
+            id: Id::new(),
+            url: WebhookUrl::init("http://example.com/hooks"),
+            events: Vec::new(),
+            secret: WebhookSecret::new("0123456789abcdef").unwrap(),
+            disabled_reason: Some(String::from("Webhook url must be an absolute https url")),
+            created_at: DateTime::now(),
+        });
+        assert!(webhook.disabled_reason().is_some());
+
+        webhook.change(NewProps {
+            url: WebhookUrl::new("https://example.com/hooks").unwrap(),
+            events: vec![WebhookEvent::TodoCreated],
+            secret: WebhookSecret::new("fedcba9876543210").unwrap(),
+        });
+        assert_eq!(webhook.disabled_reason(), None);
+        assert_eq!(webhook.url.as_str(), "https://example.com/hooks");
+    }
+
+    #[test]
+    fn secret_length_is_bounded() {
+        assert!(WebhookSecret::new("a".repeat(WebhookSecret::MIN_LENGTH)).is_ok());
+        assert_eq!(WebhookSecret::new("short"), Err(WebhookSecretError::Length));
+    }
+
+    #[test]
+    fn events_round_trip() {
+        for event in ["todo.created", "todo.updated", "todo.deleted"] {
+            let parsed = WebhookEvent::parse_str(event);
+            assert_eq!(
+                Ok(event),
+                parsed.as_ref().map(WebhookEvent::to_string).as_deref()
+            );
+        }
+
+        assert_eq!(WebhookEvent::parse_str("todo"), Err(WebhookEventError));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::domain::entities::todo::{Description, Title};
+use crate::domain::types::{Date, Id};
+
+/// Something that happened to a todo, recorded by
+/// [`TodoEntity`](crate::domain::entities::todo::TodoEntity) as it changes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TodoEvent {
+    Created {
+        id: Id,
+    },
+    Renamed {
+        id: Id,
+        title: Title,
+    },
+    Described {
+        id: Id,
+        description: Option<Description>,
+    },
+    Rescheduled {
+        id: Id,
+        todo_at: Option<Date>,
+    },
+    Started {
+        id: Id,
+    },
+    Completed {
+        id: Id,
+    },
+    Reopened {
+        id: Id,
+    },
+    Deleted {
+        id: Id,
+    },
+}
+
+impl TodoEvent {
+    /// Name of the event such as `todo.renamed`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Created { .. } => "todo.created",
+            Self::Renamed { .. } => "todo.renamed",
+            Self::Described { .. } => "todo.described",
+            Self::Rescheduled { .. } => "todo.rescheduled",
+            Self::Started { .. } => "todo.started",
+            Self::Completed { .. } => "todo.completed",
+            Self::Reopened { .. } => "todo.reopened",
+            Self::Deleted { .. } => "todo.deleted",
+        }
+    }
+
+    pub fn todo_id(&self) -> Id {
+        match self {
+            Self::Created { id }
+            | Self::Renamed { id, .. }
+            | Self::Described { id, .. }
+            | Self::Rescheduled { id, .. }
+            | Self::Started { id }
+            | Self::Completed { id }
+            | Self::Reopened { id }
+            | Self::Deleted { id } => *id,
+        }
+    }
+}
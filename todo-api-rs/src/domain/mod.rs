@@ -1,3 +1,4 @@
 pub mod entities;
+pub mod events;
 pub mod types;
 pub mod use_case;
@@ -60,6 +60,12 @@ impl DateTime {
         // probably safe to unwrap since it's using a well known/supported format
         self.0.format(&Rfc3339).unwrap()
     }
+
+    pub fn parse_str(input: &str) -> Result<Self, ParseDateTimeError> {
+        OffsetDateTime::parse(input, &Rfc3339)
+            .map(Self::from)
+            .or(Err(ParseDateTimeError::Invalid))
+    }
 }
 
 impl From<OffsetDateTime> for DateTime {
@@ -74,6 +80,12 @@ pub enum ParseDateError {
     Invalid,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum ParseDateTimeError {
+    #[error("Date time should be on RFC 3339 format")]
+    Invalid,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +126,16 @@ mod tests {
         assert!(parsed.is_err());
         assert_eq!(parsed, Err(ParseDateError::Invalid))
     }
+
+    #[test]
+    fn parse_date_time_rfc3339_works() {
+        let now = DateTime::now();
+        assert_eq!(DateTime::parse_str(&now.to_rfc3339()), Ok(now));
+
+        let parsed = DateTime::parse_str("2024-02-17T10:30:00+02:00").map(|at| at.time());
+        assert_eq!(parsed, Ok(time::macros::datetime!(2024-02-17 10:30 +02:00)));
+
+        let parsed = DateTime::parse_str("2024-02-17");
+        assert_eq!(parsed, Err(ParseDateTimeError::Invalid));
+    }
 }
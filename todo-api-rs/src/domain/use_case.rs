@@ -1,3 +1,9 @@
 pub trait UseCase<I, O> {
     async fn exec(self, input: I) -> O;
 }
+
+/// Error of a use case, whose variant names what went wrong so outcomes can be
+/// counted without their details
+pub trait UseCaseError {
+    fn kind(&self) -> &'static str;
+}
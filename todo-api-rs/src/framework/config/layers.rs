@@ -0,0 +1,517 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use clap::{Arg, ArgMatches, Command};
+
+use super::SettingError;
+use crate::adapters::dtos::validation::ValidationReport;
+
+/// Setting that can be set in every layer, under a key of the TOML file, an
+/// env var and a CLI flag
+pub(super) struct Setting {
+    pub key: &'static str,
+    pub env: &'static str,
+    pub flag: &'static str,
+    pub default: Option<&'static str>,
+    pub help: &'static str,
+}
+
+pub(super) const SETTINGS: [Setting; 43] = [
+    Setting {
+        key: "server.bind",
+        env: "BIND_ADDRESS",
+        flag: "bind",
+        default: Some("127.0.0.1:8000"),
+        help: "Address the server listens on",
+    },
+    Setting {
+        key: "server.admin_bind",
+        env: "ADMIN_BIND_ADDRESS",
+        flag: "admin-bind",
+        default: Some("127.0.0.1:9090"),
+        help: "Address the metrics scraped by Prometheus are served on, apart from the API",
+    },
+    Setting {
+        key: "server.body_limit",
+        env: "BODY_LIMIT",
+        flag: "body-limit",
+        default: Some("2MiB"),
+        help: "Largest request body, such as 512KiB or 2MiB",
+    },
+    Setting {
+        key: "server.restore_body_limit",
+        env: "RESTORE_BODY_LIMIT",
+        flag: "restore-body-limit",
+        default: Some("64MiB"),
+        help: "Largest archive restored from a backup",
+    },
+    Setting {
+        key: "server.todo_body_limit",
+        env: "TODO_BODY_LIMIT",
+        flag: "todo-body-limit",
+        default: Some("64KiB"),
+        help: "Largest body of a created or updated todo",
+    },
+    Setting {
+        key: "server.request_timeout",
+        env: "REQUEST_TIMEOUT",
+        flag: "request-timeout",
+        default: Some("30s"),
+        help: "Time a request has to start its response before it fails with 504",
+    },
+    Setting {
+        key: "server.bulk_request_timeout",
+        env: "BULK_REQUEST_TIMEOUT",
+        flag: "bulk-request-timeout",
+        default: Some("5m"),
+        help: "Time restores and imports have to start their response, instead of the request timeout",
+    },
+    Setting {
+        key: "server.max_concurrent_requests",
+        env: "MAX_CONCURRENT_REQUESTS",
+        flag: "max-concurrent-requests",
+        default: Some("512"),
+        help: "Requests handled at once, past which new ones are rejected with 503",
+    },
+    Setting {
+        key: "server.drain_delay",
+        env: "DRAIN_DELAY",
+        flag: "drain-delay",
+        default: Some("5s"),
+        help: "Time the server keeps serving while failing its readiness probe on shutdown",
+    },
+    Setting {
+        key: "server.shutdown_timeout",
+        env: "SHUTDOWN_TIMEOUT",
+        flag: "shutdown-timeout",
+        default: Some("30s"),
+        help: "Time in-flight requests and workers have to finish on shutdown",
+    },
+    Setting {
+        key: "database.url",
+        env: "DATABASE_URL",
+        flag: "database-url",
+        default: None,
+        help: "Postgres URL, used instead of the host, port, user, password and name",
+    },
+    Setting {
+        key: "database.host",
+        env: "DB_HOST",
+        flag: "db-host",
+        default: None,
+        help: "Host of the postgres database",
+    },
+    Setting {
+        key: "database.port",
+        env: "DB_PORT",
+        flag: "db-port",
+        default: Some("5432"),
+        help: "Port of the postgres database",
+    },
+    Setting {
+        key: "database.user",
+        env: "DB_USER",
+        flag: "db-user",
+        default: None,
+        help: "User connecting to the postgres database",
+    },
+    Setting {
+        key: "database.password",
+        env: "DB_PASSWORD",
+        flag: "db-password",
+        default: None,
+        help: "Password of the database user",
+    },
+    Setting {
+        key: "database.name",
+        env: "DB_NAME",
+        flag: "db-name",
+        default: None,
+        help: "Name of the postgres database",
+    },
+    Setting {
+        key: "database.min_connections",
+        env: "DB_MIN_CONNECTIONS",
+        flag: "db-min-connections",
+        default: Some("0"),
+        help: "Connections the pool keeps open even when idle",
+    },
+    Setting {
+        key: "database.max_connections",
+        env: "DB_MAX_CONNECTIONS",
+        flag: "db-max-connections",
+        default: Some("5"),
+        help: "Connections the pool opens at most",
+    },
+    Setting {
+        key: "database.connect_timeout",
+        env: "DB_CONNECT_TIMEOUT",
+        flag: "db-connect-timeout",
+        default: Some("30s"),
+        help: "Time to wait for a connection of the pool, such as 500ms or 30s",
+    },
+    Setting {
+        key: "database.idle_timeout",
+        env: "DB_IDLE_TIMEOUT",
+        flag: "db-idle-timeout",
+        default: Some("10m"),
+        help: "Time after which idle connections above the minimum are closed",
+    },
+    Setting {
+        key: "database.statement_timeout",
+        env: "DB_STATEMENT_TIMEOUT",
+        flag: "db-statement-timeout",
+        default: Some("10s"),
+        help: "Time a statement may run before the database cancels it",
+    },
+    Setting {
+        key: "health.check_timeout",
+        env: "HEALTH_CHECK_TIMEOUT",
+        flag: "health-check-timeout",
+        default: Some("2s"),
+        help: "Time every readiness check has before it fails",
+    },
+    Setting {
+        key: "auth.jwt_secret",
+        env: "JWT_SECRET",
+        flag: "jwt-secret",
+        default: None,
+        help: "Secret of the HS256 bearer tokens clients authenticate with",
+    },
+    Setting {
+        key: "auth.tenant_claim",
+        env: "JWT_TENANT_CLAIM",
+        flag: "jwt-tenant-claim",
+        default: Some("tenant_id"),
+        help: "Claim of bearer tokens holding the id of the tenant of the client",
+    },
+    Setting {
+        key: "auth.trust_tenant_header",
+        env: "TRUST_TENANT_HEADER",
+        flag: "trust-tenant-header",
+        default: Some("false"),
+        help: "Whether the tenant is taken from the X-Tenant-Id header, only behind a gateway that authenticates clients",
+    },
+    Setting {
+        key: "cors.origins",
+        env: "CORS_ORIGINS",
+        flag: "cors-origins",
+        default: Some("*"),
+        help: "Comma separated origins allowed to call the API, such as https://todo.example.com or https://*.example.com, or * for any",
+    },
+    Setting {
+        key: "cors.methods",
+        env: "CORS_METHODS",
+        flag: "cors-methods",
+        default: Some("GET,POST,PUT,DELETE"),
+        help: "Comma separated methods allowed in cross origin requests, or * for any",
+    },
+    Setting {
+        key: "cors.headers",
+        env: "CORS_HEADERS",
+        flag: "cors-headers",
+        default: Some("accept,authorization,content-type,content-encoding,x-tenant-id,x-request-id,traceparent"),
+        help: "Comma separated request headers allowed in cross origin requests, or * for any",
+    },
+    Setting {
+        key: "cors.expose_headers",
+        env: "CORS_EXPOSE_HEADERS",
+        flag: "cors-expose-headers",
+        default: Some("etag,location,retry-after,ratelimit-limit,ratelimit-remaining,ratelimit-reset,ratelimit-policy,x-request-id"),
+        help: "Comma separated response headers readable by cross origin clients",
+    },
+    Setting {
+        key: "cors.max_age",
+        env: "CORS_MAX_AGE",
+        flag: "cors-max-age",
+        default: Some("10m"),
+        help: "Time browsers may cache the result of a preflight request",
+    },
+    Setting {
+        key: "cors.allow_credentials",
+        env: "CORS_ALLOW_CREDENTIALS",
+        flag: "cors-allow-credentials",
+        default: Some("false"),
+        help: "Whether cross origin requests may send cookies, which requires listed origins, methods and headers",
+    },
+    Setting {
+        key: "compression.enabled",
+        env: "COMPRESSION_ENABLED",
+        flag: "compression-enabled",
+        default: Some("true"),
+        help: "Whether responses are compressed with gzip, brotli or zstd as accepted by clients",
+    },
+    Setting {
+        key: "compression.min_size",
+        env: "COMPRESSION_MIN_SIZE",
+        flag: "compression-min-size",
+        default: Some("1KiB"),
+        help: "Smallest response body that is compressed, up to 64KiB",
+    },
+    Setting {
+        key: "cache.default",
+        env: "CACHE_DEFAULT",
+        flag: "cache-default",
+        default: Some("private no-cache"),
+        help: "Cache-Control directives of responses, such as private no-cache",
+    },
+    Setting {
+        key: "cache.routes",
+        env: "CACHE_ROUTES",
+        flag: "cache-routes",
+        default: None,
+        help: "Comma separated directives of successful responses of routes, such as GET /todos/:id private max-age=60",
+    },
+    Setting {
+        key: "rate_limit.backend",
+        env: "RATE_LIMIT_BACKEND",
+        flag: "rate-limit-backend",
+        default: Some("memory"),
+        help: "Storage of the buckets of clients, either memory or postgres to share them between instances",
+    },
+    Setting {
+        key: "rate_limit.default",
+        env: "RATE_LIMIT_DEFAULT",
+        flag: "rate-limit-default",
+        default: Some("600/m"),
+        help: "Requests of a client to each route without its own quota, such as 600/m, or unlimited",
+    },
+    Setting {
+        key: "rate_limit.routes",
+        env: "RATE_LIMIT_ROUTES",
+        flag: "rate-limit-routes",
+        default: None,
+        help: "Comma separated quotas of routes, such as POST /todos 60/m",
+    },
+    Setting {
+        key: "log.format",
+        env: "LOG_FORMAT",
+        flag: "log-format",
+        default: Some("text"),
+        help: "Format of log lines, either text or json",
+    },
+    Setting {
+        key: "log.redact_descriptions",
+        env: "LOG_REDACT_DESCRIPTIONS",
+        flag: "log-redact-descriptions",
+        default: Some("true"),
+        help: "Whether descriptions of todos are left out of logs, either true or false",
+    },
+    Setting {
+        key: "telemetry.otlp_endpoint",
+        env: "OTEL_EXPORTER_OTLP_ENDPOINT",
+        flag: "otlp-endpoint",
+        default: None,
+        help: "Base URL of the OTLP/HTTP collector traces are exported to, none when unset",
+    },
+    Setting {
+        key: "telemetry.service_name",
+        env: "OTEL_SERVICE_NAME",
+        flag: "service-name",
+        default: Some("todo-api-rs"),
+        help: "Name of the service in exported traces",
+    },
+    Setting {
+        key: "telemetry.sample_ratio",
+        env: "OTEL_TRACES_SAMPLER_ARG",
+        flag: "trace-sample-ratio",
+        default: Some("1.0"),
+        help: "Share of new traces sampled, from 0 to 1",
+    },
+];
+
+/// Flag and env var naming the TOML file, which is not read when unset
+const CONFIG_FLAG: &str = "config";
+const CONFIG_ENV: &str = "CONFIG_FILE";
+
+/// Layer a value was set in, to point at it when it is invalid
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    File(PathBuf),
+    Env(&'static str),
+    Flag(&'static str),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::File(path) => write!(f, "file {}", path.display()),
+            Self::Env(name) => write!(f, "env {name}"),
+            Self::Flag(name) => write!(f, "flag --{name}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Value {
+    pub raw: String,
+    pub origin: Origin,
+}
+
+/// Raw values of the settings, each layer overriding the values of the
+/// previous ones. Values are only parsed once every layer is read, so errors
+/// of every layer are reported together
+#[derive(Debug)]
+pub(super) struct Layers {
+    values: HashMap<&'static str, Value>,
+    pub report: ValidationReport<SettingError>,
+}
+
+impl Layers {
+    /// Start from the default values
+    pub fn new() -> Self {
+        let values = SETTINGS
+            .iter()
+            .filter_map(|setting| {
+                let raw = setting.default?.to_owned();
+                let origin = Origin::Default;
+                Some((setting.key, Value { raw, origin }))
+            })
+            .collect();
+
+        Self {
+            values,
+            report: ValidationReport::new(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    /// Read the TOML file named by the `--config` flag or the `CONFIG_FILE`
+    /// env var, when there is one
+    pub fn read_config_file(&mut self, matches: &ArgMatches, env: impl Fn(&str) -> Option<String>) {
+        let path = matches
+            .get_one::<String>(CONFIG_FLAG)
+            .cloned()
+            .or_else(|| env(CONFIG_ENV).filter(|path| !path.is_empty()));
+
+        if let Some(path) = path {
+            self.read_file(Path::new(&path));
+        }
+    }
+
+    fn read_file(&mut self, path: &Path) {
+        match std::fs::read_to_string(path) {
+            Ok(content) => self.read_toml(&content, path),
+            Err(err) => self.report.push(SettingError {
+                key: CONFIG_FLAG.to_owned(),
+                origin: Some(Origin::File(path.to_owned())),
+                reason: format!("could not be read: {err}"),
+            }),
+        }
+    }
+
+    /// Read a TOML document whose tables are the sections of the keys, such
+    /// as `bind` in `[server]` for `server.bind`
+    pub fn read_toml(&mut self, content: &str, path: &Path) {
+        let origin = Origin::File(path.to_owned());
+        let table = match content.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(err) => {
+                return self.report.push(SettingError {
+                    key: CONFIG_FLAG.to_owned(),
+                    origin: Some(origin),
+                    reason: format!("is not valid TOML: {}", err.message()),
+                })
+            }
+        };
+
+        for (section, value) in table {
+            let toml::Value::Table(table) = value else {
+                self.report
+                    .push(SettingError::unknown(section, origin.clone()));
+                continue;
+            };
+
+            for (name, value) in table {
+                let key = format!("{section}.{name}");
+                let Some(setting) = SETTINGS.iter().find(|setting| setting.key == key) else {
+                    self.report.push(SettingError::unknown(key, origin.clone()));
+                    continue;
+                };
+
+                match toml_raw(value) {
+                    Some(raw) => self.set(setting.key, raw, origin.clone()),
+                    None => self.report.push(SettingError {
+                        key,
+                        origin: Some(origin.clone()),
+                        reason: String::from("must be a string, a number or a list of strings"),
+                    }),
+                }
+            }
+        }
+    }
+
+    /// Read the env vars of the settings, ignoring the empty ones
+    pub fn read_env(&mut self, env: impl Fn(&str) -> Option<String>) {
+        for setting in &SETTINGS {
+            if let Some(raw) = env(setting.env).filter(|raw| !raw.is_empty()) {
+                self.set(setting.key, raw, Origin::Env(setting.env));
+            }
+        }
+    }
+
+    pub fn read_flags(&mut self, matches: &ArgMatches) {
+        for setting in &SETTINGS {
+            if let Some(raw) = matches.get_one::<String>(setting.key) {
+                self.set(setting.key, raw.clone(), Origin::Flag(setting.flag));
+            }
+        }
+    }
+
+    fn set(&mut self, key: &'static str, raw: String, origin: Origin) {
+        self.values.insert(key, Value { raw, origin });
+    }
+}
+
+/// Command line of the server, with a flag for every setting
+pub(super) fn command() -> Command {
+    let config = Arg::new(CONFIG_FLAG)
+        .long(CONFIG_FLAG)
+        .value_name("PATH")
+        .help("TOML file of settings, overridden by env vars and flags");
+
+    SETTINGS.iter().fold(
+        Command::new("todo-api-rs").arg(config),
+        |command, setting| {
+            let mut help = format!(
+                "{} [key: {}] [env: {}]",
+                setting.help, setting.key, setting.env
+            );
+            if let Some(default) = setting.default {
+                help.push_str(&format!(" [default: {default}]"));
+            }
+
+            command.arg(
+                Arg::new(setting.key)
+                    .long(setting.flag)
+                    .value_name("VALUE")
+                    .help(help),
+            )
+        },
+    )
+}
+
+/// Value of a TOML file as it would be written in an env var
+fn toml_raw(value: toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(raw) => Some(raw),
+        toml::Value::Integer(raw) => Some(raw.to_string()),
+        toml::Value::Float(raw) => Some(raw.to_string()),
+        toml::Value::Boolean(raw) => Some(raw.to_string()),
+        toml::Value::Array(values) => values
+            .into_iter()
+            .map(|value| match value {
+                toml::Value::String(raw) => Some(raw),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|values| values.join(",")),
+        _ => None,
+    }
+}
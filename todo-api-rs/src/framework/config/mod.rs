@@ -0,0 +1,1087 @@
+mod layers;
+
+use std::fmt;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use sqlx::postgres::PgConnectOptions;
+use thiserror::Error;
+
+use crate::adapters::dtos::validation::ValidationReport;
+use crate::framework::rate_limit::{Quota, RouteQuota};
+use crate::framework::rest_api::caching::{CachePolicy, RouteCachePolicy};
+use crate::framework::rest_api::cors::{Allowed, OriginPattern};
+use layers::{Layers, Origin, Value};
+
+/// Settings of the server, read from defaults, then a TOML file, then env
+/// vars, then CLI flags, each layer overriding the previous ones
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub health: HealthConfig,
+    pub auth: AuthConfig,
+    pub cors: CorsConfig,
+    pub compression: CompressionConfig,
+    pub cache: CacheConfig,
+    pub rate_limit: RateLimitConfig,
+    pub log: LogConfig,
+    pub telemetry: TelemetryConfig,
+}
+
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    pub bind: SocketAddr,
+    /// Address of the metrics, kept off the API as they span every tenant
+    pub admin_bind: SocketAddr,
+    /// Largest request body in bytes
+    pub body_limit: usize,
+    /// Largest archive restored from a backup in bytes
+    pub restore_body_limit: usize,
+    /// Largest body of a created or updated todo in bytes
+    pub todo_body_limit: usize,
+    /// Time a request has to start its response
+    pub request_timeout: Duration,
+    /// Time restores and imports have to start their response, as they read
+    /// and save a whole archive or file first
+    pub bulk_request_timeout: Duration,
+    /// Requests handled at once, past which new ones are shed
+    pub max_concurrent_requests: usize,
+    /// Time the server keeps accepting requests once a shutdown starts, while
+    /// its readiness fails, so load balancers stop routing to it first
+    pub drain_delay: Duration,
+    /// Time in-flight requests and background workers have to finish once a
+    /// shutdown starts
+    pub shutdown_timeout: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct DatabaseConfig {
+    pub connection: PgConnectOptions,
+    pub min_connections: u32,
+    pub max_connections: u32,
+    /// Time to wait for a connection of the pool, including opening it
+    pub connect_timeout: Duration,
+    /// Time after which idle connections above the minimum are closed
+    pub idle_timeout: Duration,
+    /// Time a statement may run before the database cancels it
+    pub statement_timeout: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct HealthConfig {
+    /// Time every readiness check has before it fails
+    pub check_timeout: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct AuthConfig {
+    /// Secret bearer tokens are signed with, none when only the header of a
+    /// trusted gateway identifies tenants
+    pub jwt_secret: Option<JwtSecret>,
+    pub tenant_claim: String,
+    /// Whether the `X-Tenant-Id` header identifies the tenant of requests
+    /// without a bearer token. Anyone reaching the server can set it, so it
+    /// must only be trusted behind a gateway that authenticates clients
+    pub trust_tenant_header: bool,
+}
+
+/// Secret that is left out of debug output, so configs can be logged
+#[derive(Clone, PartialEq, Eq)]
+pub struct JwtSecret(pub Vec<u8>);
+
+impl fmt::Debug for JwtSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("JwtSecret(..)")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API
+    pub origins: Allowed<OriginPattern>,
+    pub methods: Allowed<Method>,
+    /// Request headers allowed in cross origin requests
+    pub headers: Allowed<HeaderName>,
+    /// Response headers readable by cross origin clients
+    pub expose_headers: Vec<HeaderName>,
+    /// Time browsers may cache the result of a preflight request
+    pub max_age: Duration,
+    /// Whether cross origin requests may send cookies, never along with any
+    /// origin, method or header
+    pub allow_credentials: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct CompressionConfig {
+    pub enabled: bool,
+    /// Smallest response body in bytes that is compressed
+    pub min_size: u16,
+}
+
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    /// Policy of the responses of routes without their own, and of every
+    /// error response
+    pub default: CachePolicy,
+    pub routes: Vec<RouteCachePolicy>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RateLimitConfig {
+    pub backend: RateLimitBackend,
+    /// Quota of the routes without their own, none when they are unlimited
+    pub default_quota: Option<Quota>,
+    pub routes: Vec<RouteQuota>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitBackend {
+    /// Buckets of every instance of the server
+    Memory,
+    /// Buckets shared by the instances of the server through the database
+    Postgres,
+}
+
+#[derive(Clone, Debug)]
+pub struct LogConfig {
+    pub format: LogFormat,
+    /// Whether descriptions of todos are left out of logs, as they may hold
+    /// personal data
+    pub redact_descriptions: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Clone, Debug)]
+pub struct TelemetryConfig {
+    /// Base URL of the OTLP/HTTP collector traces are exported to, traces are
+    /// not exported when none
+    pub otlp_endpoint: Option<String>,
+    pub service_name: String,
+    /// Share of the traces started by the server that are sampled, from 0 to
+    /// 1. Traces continued from a request keep the decision of their parent
+    pub sample_ratio: f64,
+}
+
+/// Invalid value of a setting
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub struct SettingError {
+    pub key: String,
+    /// Layer the value was set in, none when it is missing
+    pub origin: Option<Origin>,
+    pub reason: String,
+}
+
+impl SettingError {
+    fn unknown(key: String, origin: Origin) -> Self {
+        Self {
+            key,
+            origin: Some(origin),
+            reason: String::from("is not a known setting"),
+        }
+    }
+}
+
+impl fmt::Display for SettingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.origin {
+            Some(origin) => write!(f, "{} from {origin} {}", self.key, self.reason),
+            None => write!(f, "{} {}", self.key, self.reason),
+        }
+    }
+}
+
+impl Config {
+    /// Read every layer of settings, reporting every invalid setting at once.
+    /// Exits on `--help` or unknown flags, as the command line is parsed here
+    pub fn load() -> Result<Self, ValidationReport<SettingError>> {
+        let matches = layers::command().get_matches();
+        let env = |name: &str| std::env::var(name).ok();
+
+        let mut layers = Layers::new();
+        layers.read_config_file(&matches, env);
+        layers.read_env(env);
+        layers.read_flags(&matches);
+        Self::from_layers(layers)
+    }
+
+    fn from_layers(mut layers: Layers) -> Result<Self, ValidationReport<SettingError>> {
+        let mut parser = Parser {
+            report: std::mem::take(&mut layers.report),
+            layers: &layers,
+        };
+
+        let server = parser.server();
+        let database = parser.database();
+        let health = parser.health();
+        let auth = parser.auth();
+        let cors = parser.cors();
+        let compression = parser.compression();
+        let cache = parser.cache();
+        let rate_limit = parser.rate_limit();
+        let log = parser.log();
+        let telemetry = parser.telemetry();
+
+        let report = parser.report;
+        match (
+            server,
+            database,
+            health,
+            auth,
+            cors,
+            compression,
+            cache,
+            rate_limit,
+            log,
+            telemetry,
+        ) {
+            (
+                Some(server),
+                Some(database),
+                Some(health),
+                Some(auth),
+                Some(cors),
+                Some(compression),
+                Some(cache),
+                Some(rate_limit),
+                Some(log),
+                Some(telemetry),
+            ) if report.errors().is_empty() => Ok(Self {
+                server,
+                database,
+                health,
+                auth,
+                cors,
+                compression,
+                cache,
+                rate_limit,
+                log,
+                telemetry,
+            }),
+            _ => Err(report),
+        }
+    }
+}
+
+/// Parses the values of the layers, recording the errors. Every value is
+/// parsed before any is returned, so a missing one is always reported
+struct Parser<'a> {
+    layers: &'a Layers,
+    report: ValidationReport<SettingError>,
+}
+
+impl Parser<'_> {
+    /// Parse the value of a setting, which is none when it is unset or invalid
+    fn parse<T>(
+        &mut self,
+        key: &'static str,
+        parse: impl FnOnce(&str) -> Result<T, String>,
+    ) -> Option<T> {
+        let Value { raw, origin } = self.layers.get(key)?;
+        let result = parse(raw.trim()).map_err(|reason| SettingError {
+            key: key.to_owned(),
+            origin: Some(origin.clone()),
+            reason,
+        });
+
+        self.report.check(result)
+    }
+
+    fn server(&mut self) -> Option<ServerConfig> {
+        let bind = self.parse("server.bind", parse_address);
+        let admin_bind = self.parse("server.admin_bind", parse_address);
+        let body_limit = self.parse("server.body_limit", parse_size);
+        let restore_body_limit = self.parse("server.restore_body_limit", parse_size);
+        let todo_body_limit = self.parse("server.todo_body_limit", parse_size);
+        let request_timeout = self.parse("server.request_timeout", parse_timeout);
+        let bulk_request_timeout = self.parse("server.bulk_request_timeout", parse_timeout);
+        let max_concurrent_requests = self.parse("server.max_concurrent_requests", |raw| {
+            parse_number::<usize>(raw).and_then(|max| match max {
+                0 => Err(String::from("must be at least 1")),
+                max => Ok(max),
+            })
+        });
+        let drain_delay = self.parse("server.drain_delay", parse_duration);
+        let shutdown_timeout = self.parse("server.shutdown_timeout", parse_timeout);
+
+        Some(ServerConfig {
+            bind: bind?,
+            admin_bind: admin_bind?,
+            body_limit: body_limit?,
+            restore_body_limit: restore_body_limit?,
+            todo_body_limit: todo_body_limit?,
+            request_timeout: request_timeout?,
+            bulk_request_timeout: bulk_request_timeout?,
+            max_concurrent_requests: max_concurrent_requests?,
+            drain_delay: drain_delay?,
+            shutdown_timeout: shutdown_timeout?,
+        })
+    }
+
+    fn database(&mut self) -> Option<DatabaseConfig> {
+        let min_connections = self.parse("database.min_connections", parse_number::<u32>);
+        let max_connections = self.parse("database.max_connections", |raw| {
+            parse_number::<u32>(raw).and_then(|max| match max {
+                0 => Err(String::from("must be at least 1")),
+                max => Ok(max),
+            })
+        });
+        if let (Some(min), Some(max)) = (min_connections, max_connections) {
+            if min > max {
+                let origin = self.layers.get("database.min_connections");
+                self.report.push(SettingError {
+                    key: String::from("database.min_connections"),
+                    origin: origin.map(|value| value.origin.clone()),
+                    reason: format!("must not be more than database.max_connections ({max})"),
+                });
+            }
+        }
+
+        let connect_timeout = self.parse("database.connect_timeout", parse_timeout);
+        let idle_timeout = self.parse("database.idle_timeout", parse_duration);
+        let statement_timeout = self.parse("database.statement_timeout", parse_timeout);
+
+        let connection = match self.layers.get("database.url") {
+            Some(_) => self.parse("database.url", |raw| {
+                raw.parse::<PgConnectOptions>()
+                    .map_err(|err| format!("is not a postgres URL: {err}"))
+            }),
+            None => self.connection(),
+        };
+
+        Some(DatabaseConfig {
+            connection: connection?,
+            min_connections: min_connections?,
+            max_connections: max_connections?,
+            connect_timeout: connect_timeout?,
+            idle_timeout: idle_timeout?,
+            statement_timeout: statement_timeout?,
+        })
+    }
+
+    /// Options of a connection to the database with the given host, port,
+    /// user, password and name, which are all required without a URL
+    fn connection(&mut self) -> Option<PgConnectOptions> {
+        let host = self.without_url("database.host");
+        let user = self.without_url("database.user");
+        let password = self.without_url("database.password");
+        let name = self.without_url("database.name");
+        let port = self.parse("database.port", parse_number::<u16>);
+
+        Some(
+            PgConnectOptions::new()
+                .host(&host?)
+                .port(port?)
+                .username(&user?)
+                .password(&password?)
+                .database(&name?),
+        )
+    }
+
+    /// Value of a setting required when the database URL is not set
+    fn without_url(&mut self, key: &'static str) -> Option<String> {
+        let value = self.layers.get(key).map(|value| value.raw.clone());
+        if value.is_none() {
+            self.report.push(SettingError {
+                key: key.to_owned(),
+                origin: None,
+                reason: String::from("is required when database.url is not set"),
+            });
+        }
+
+        value
+    }
+
+    fn health(&mut self) -> Option<HealthConfig> {
+        let check_timeout = self.parse("health.check_timeout", parse_timeout);
+
+        Some(HealthConfig {
+            check_timeout: check_timeout?,
+        })
+    }
+
+    fn auth(&mut self) -> Option<AuthConfig> {
+        let jwt_secret = match self.layers.get("auth.jwt_secret") {
+            Some(_) => self
+                .parse("auth.jwt_secret", |raw| match raw.len() {
+                    0..=31 => Err(String::from("must be at least 32 bytes long")),
+                    _ => Ok(JwtSecret(raw.as_bytes().to_vec())),
+                })
+                .map(Some),
+            None => Some(None),
+        };
+        let tenant_claim = self.parse("auth.tenant_claim", |raw| match raw {
+            "" => Err(String::from("must not be empty")),
+            claim => Ok(claim.to_owned()),
+        });
+        let trust_tenant_header = self.parse("auth.trust_tenant_header", parse_bool);
+
+        // without either no request could be authenticated
+        if jwt_secret == Some(None) && trust_tenant_header == Some(false) {
+            self.report.push(SettingError {
+                key: String::from("auth.jwt_secret"),
+                origin: None,
+                reason: String::from("is required when auth.trust_tenant_header is false"),
+            });
+        }
+
+        Some(AuthConfig {
+            jwt_secret: jwt_secret?,
+            tenant_claim: tenant_claim?,
+            trust_tenant_header: trust_tenant_header?,
+        })
+    }
+
+    fn cors(&mut self) -> Option<CorsConfig> {
+        let origins = self.parse("cors.origins", |raw| parse_allowed(raw, parse_origin));
+        let methods = self.parse("cors.methods", |raw| {
+            parse_allowed(raw, |method| {
+                parse_method(method).ok_or_else(|| format!("{method} is not a method, such as GET"))
+            })
+        });
+        let headers = self.parse("cors.headers", |raw| parse_allowed(raw, parse_header_name));
+        let expose_headers = self.parse("cors.expose_headers", |raw| {
+            parse_list(raw).map(parse_header_name).collect()
+        });
+        let max_age = self.parse("cors.max_age", parse_duration);
+        let allow_credentials = self.parse("cors.allow_credentials", parse_bool);
+
+        // browsers reject credentials along with wildcards, so this policy
+        // would silently fail rather than be as permissive as it looks
+        if allow_credentials == Some(true) {
+            let wildcards = [
+                ("cors.origins", origins.as_ref().is_some_and(Allowed::is_any)),
+                ("cors.methods", methods.as_ref().is_some_and(Allowed::is_any)),
+                ("cors.headers", headers.as_ref().is_some_and(Allowed::is_any)),
+            ];
+            for (key, _) in wildcards.into_iter().filter(|(_, any)| *any) {
+                let origin = self.layers.get("cors.allow_credentials");
+                self.report.push(SettingError {
+                    key: String::from("cors.allow_credentials"),
+                    origin: origin.map(|value| value.origin.clone()),
+                    reason: format!("must not be true when {key} is *"),
+                });
+            }
+        }
+
+        Some(CorsConfig {
+            origins: origins?,
+            methods: methods?,
+            headers: headers?,
+            expose_headers: expose_headers?,
+            max_age: max_age?,
+            allow_credentials: allow_credentials?,
+        })
+    }
+
+    fn compression(&mut self) -> Option<CompressionConfig> {
+        let enabled = self.parse("compression.enabled", parse_bool);
+        let min_size = self.parse("compression.min_size", |raw| {
+            parse_size(raw).and_then(|size| {
+                u16::try_from(size).map_err(|_| String::from("must not be more than 64KiB"))
+            })
+        });
+
+        Some(CompressionConfig {
+            enabled: enabled?,
+            min_size: min_size?,
+        })
+    }
+
+    fn cache(&mut self) -> Option<CacheConfig> {
+        let default = self.parse("cache.default", parse_cache_policy);
+        let routes = match self.layers.get("cache.routes") {
+            Some(_) => self.parse("cache.routes", parse_route_cache_policies),
+            None => Some(Vec::new()),
+        };
+
+        Some(CacheConfig {
+            default: default?,
+            routes: routes?,
+        })
+    }
+
+    fn rate_limit(&mut self) -> Option<RateLimitConfig> {
+        let backend = self.parse("rate_limit.backend", |raw| match raw {
+            "memory" => Ok(RateLimitBackend::Memory),
+            "postgres" => Ok(RateLimitBackend::Postgres),
+            _ => Err(String::from("must be one of memory or postgres")),
+        });
+        let default_quota = self.parse("rate_limit.default", parse_quota);
+        let routes = match self.layers.get("rate_limit.routes") {
+            Some(_) => self.parse("rate_limit.routes", parse_route_quotas),
+            None => Some(Vec::new()),
+        };
+
+        Some(RateLimitConfig {
+            backend: backend?,
+            default_quota: default_quota?,
+            routes: routes?,
+        })
+    }
+
+    fn log(&mut self) -> Option<LogConfig> {
+        let format = self.parse("log.format", |raw| match raw {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(String::from("must be one of text or json")),
+        });
+        let redact_descriptions = self.parse("log.redact_descriptions", parse_bool);
+
+        Some(LogConfig {
+            format: format?,
+            redact_descriptions: redact_descriptions?,
+        })
+    }
+
+    fn telemetry(&mut self) -> Option<TelemetryConfig> {
+        let otlp_endpoint = match self.layers.get("telemetry.otlp_endpoint") {
+            Some(_) => self
+                .parse("telemetry.otlp_endpoint", parse_endpoint)
+                .map(Some),
+            None => Some(None),
+        };
+        let service_name = self.parse("telemetry.service_name", |raw| match raw {
+            "" => Err(String::from("must not be empty")),
+            name => Ok(name.to_owned()),
+        });
+        let sample_ratio = self.parse("telemetry.sample_ratio", |raw| {
+            raw.parse::<f64>()
+                .ok()
+                .filter(|ratio| (0.0..=1.0).contains(ratio))
+                .ok_or_else(|| String::from("must be a number from 0 to 1, such as 0.25"))
+        });
+
+        Some(TelemetryConfig {
+            otlp_endpoint: otlp_endpoint?,
+            service_name: service_name?,
+            sample_ratio: sample_ratio?,
+        })
+    }
+}
+
+fn parse_address(raw: &str) -> Result<SocketAddr, String> {
+    raw.parse()
+        .map_err(|_| String::from("must be an IP address and a port, such as 127.0.0.1:8000"))
+}
+
+fn parse_bool(raw: &str) -> Result<bool, String> {
+    match raw {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(String::from("must be one of true or false")),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(raw: &str) -> Result<T, String> {
+    raw.parse()
+        .map_err(|_| String::from("must be a positive whole number"))
+}
+
+/// Parse a duration such as `500ms`, `30s`, `10m` or `1h`, in seconds when it
+/// has no unit
+fn parse_duration(raw: &str) -> Result<Duration, String> {
+    let invalid = || String::from("must be a duration such as 500ms, 30s, 10m or 1h");
+
+    let (amount, unit) = raw.split_at(raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len()));
+    let amount = amount.parse::<u64>().map_err(|_| invalid())?;
+    let seconds = match unit.trim() {
+        "ms" => return Ok(Duration::from_millis(amount)),
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    amount
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(invalid)
+}
+
+/// Parse a duration which must not be zero
+fn parse_timeout(raw: &str) -> Result<Duration, String> {
+    parse_duration(raw).and_then(|timeout| match timeout.is_zero() {
+        true => Err(String::from("must be more than 0")),
+        false => Ok(timeout),
+    })
+}
+
+/// Parse a size such as `512KiB`, `2MiB` or `1GB`, in bytes when it has no
+/// unit
+fn parse_size(raw: &str) -> Result<usize, String> {
+    let invalid = || String::from("must be a size such as 512KiB, 2MiB or 1GB");
+
+    let (amount, unit) = raw.split_at(raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len()));
+    let amount = amount.parse::<usize>().map_err(|_| invalid())?;
+    let factor = match unit.trim() {
+        "" | "B" => 1,
+        "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => return Err(invalid()),
+    };
+
+    amount.checked_mul(factor).ok_or_else(invalid)
+}
+
+/// Values of a comma separated list, without the empty ones
+fn parse_list(raw: &str) -> impl Iterator<Item = &str> {
+    raw.split(',').map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// Parse a comma separated list of values, or `*` alone for any value
+fn parse_allowed<T>(
+    raw: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<Allowed<T>, String> {
+    if raw == "*" {
+        return Ok(Allowed::Any);
+    }
+
+    parse_list(raw)
+        .map(|value| match value {
+            "*" => Err(String::from("must be * alone to allow any value")),
+            value => parse(value),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Allowed::List)
+}
+
+/// Parse an origin such as `https://todo.example.com`, or a pattern of the
+/// subdomains of a domain such as `https://*.example.com`
+fn parse_origin(origin: &str) -> Result<OriginPattern, String> {
+    let invalid = || {
+        format!("{origin} must be a scheme and a host without a path, such as https://todo.example.com or https://*.example.com")
+    };
+
+    let (scheme, host) = origin.split_once("://").ok_or_else(invalid)?;
+    if !matches!(scheme, "http" | "https") || host.is_empty() || host.contains('/') {
+        return Err(invalid());
+    }
+
+    match host.strip_prefix('*') {
+        // the domain must have a dot so patterns such as *.com are rejected
+        Some(suffix) => {
+            let domain = suffix.strip_prefix('.').unwrap_or_default();
+            let name = domain.split(':').next().unwrap_or_default();
+            if !name.contains('.') || name.starts_with('.') || domain.contains('*') {
+                return Err(format!(
+                    "{origin} must have a wildcard before a domain with a dot, such as https://*.example.com"
+                ));
+            }
+
+            Ok(OriginPattern::Subdomains {
+                scheme: format!("{scheme}://"),
+                suffix: suffix.to_owned(),
+            })
+        }
+        None if host.contains('*') => Err(invalid()),
+        None => HeaderValue::from_str(origin)
+            .map(OriginPattern::Exact)
+            .map_err(|_| format!("{origin} is not a valid origin")),
+    }
+}
+
+/// Parse the name of a header such as `content-type`, in any case
+fn parse_header_name(name: &str) -> Result<HeaderName, String> {
+    HeaderName::from_bytes(name.as_bytes())
+        .map_err(|_| format!("{name} is not a header name, such as content-type"))
+}
+
+/// Parse `Cache-Control` directives separated by whitespace, such as
+/// `private max-age=60`, as commas separate the values of lists
+fn parse_cache_policy(raw: &str) -> Result<CachePolicy, String> {
+    let invalid = |directive: &str| {
+        format!("{directive} must be one of public, private, no-cache, no-store, must-revalidate, immutable, max-age=<seconds> or stale-while-revalidate=<seconds>")
+    };
+
+    let directives = raw
+        .split_whitespace()
+        .map(|directive| {
+            let valid = match directive.split_once('=') {
+                Some((name, seconds)) => {
+                    matches!(name, "max-age" | "stale-while-revalidate")
+                        && seconds.parse::<u32>().is_ok()
+                }
+                None => matches!(
+                    directive,
+                    "public" | "private" | "no-cache" | "no-store" | "must-revalidate" | "immutable"
+                ),
+            };
+            match valid {
+                true => Ok(directive.to_owned()),
+                false => Err(invalid(directive)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if directives.is_empty() {
+        return Err(String::from("must not be empty"));
+    }
+    if directives.iter().any(|d| d == "public") && directives.iter().any(|d| d == "private") {
+        return Err(String::from("must not be both public and private"));
+    }
+
+    Ok(CachePolicy { directives })
+}
+
+/// Parse comma separated policies of routes such as
+/// `GET /todos/:id private max-age=60`
+fn parse_route_cache_policies(raw: &str) -> Result<Vec<RouteCachePolicy>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|route| !route.is_empty())
+        .map(|route| {
+            let invalid = || {
+                format!("{route} must be a method, a route and directives, such as GET /todos/:id private max-age=60")
+            };
+
+            let parts = route.split_whitespace().collect::<Vec<_>>();
+            let [method, path, ref directives @ ..] = parts[..] else {
+                return Err(invalid());
+            };
+            let policy = parse_cache_policy(&directives.join(" "))
+                .map_err(|reason| format!("{method} {path} {reason}"))?;
+            let method = parse_method(method).ok_or_else(invalid)?;
+            if !path.starts_with('/') {
+                return Err(invalid());
+            }
+
+            Ok(RouteCachePolicy {
+                method,
+                route: path.to_owned(),
+                policy,
+            })
+        })
+        .collect()
+}
+
+/// Parse an uppercase method such as `GET`
+fn parse_method(raw: &str) -> Option<Method> {
+    raw.parse::<Method>()
+        .ok()
+        .filter(|_| raw.chars().all(|c| c.is_ascii_uppercase()))
+}
+
+/// Parse a quota such as `60/s`, `600/m` or `1000/h`, none when it is
+/// `unlimited`
+fn parse_quota(raw: &str) -> Result<Option<Quota>, String> {
+    let invalid =
+        || String::from("must be a number of requests per s, m or h, such as 600/m, or unlimited");
+    if raw == "unlimited" {
+        return Ok(None);
+    }
+
+    let (limit, unit) = raw.split_once('/').ok_or_else(invalid)?;
+    let limit = limit.trim().parse::<u32>().ok().filter(|limit| *limit > 0);
+    let seconds = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Some(Quota {
+        limit: limit.ok_or_else(invalid)?,
+        period: Duration::from_secs(seconds),
+    }))
+}
+
+/// Parse comma separated quotas of routes such as `POST /todos 60/m`, where
+/// the route is the template the router matches, such as `/todos/:id`
+fn parse_route_quotas(raw: &str) -> Result<Vec<RouteQuota>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|route| !route.is_empty())
+        .map(|route| {
+            let invalid = || {
+                format!("{route} must be a method, a route and a quota, such as POST /todos 60/m")
+            };
+
+            let parts = route.split_whitespace().collect::<Vec<_>>();
+            let [method, path, quota] = parts[..] else {
+                return Err(invalid());
+            };
+            let method = parse_method(method).ok_or_else(invalid)?;
+            if !path.starts_with('/') {
+                return Err(invalid());
+            }
+
+            Ok(RouteQuota {
+                method,
+                route: path.to_owned(),
+                quota: parse_quota(quota).map_err(|reason| format!("{route} {reason}"))?,
+            })
+        })
+        .collect()
+}
+
+/// Parse the base URL of a collector such as `http://localhost:4318`, without
+/// the trailing slash
+fn parse_endpoint(raw: &str) -> Result<String, String> {
+    let (scheme, rest) = raw.split_once("://").unwrap_or_default();
+    match matches!(scheme, "http" | "https") && !rest.is_empty() {
+        true => Ok(raw.trim_end_matches('/').to_owned()),
+        false => Err(String::from(
+            "must be an http or https URL, such as http://localhost:4318",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use super::*;
+
+    fn load(file: &str, env: &[(&str, &str)], flags: &[&str]) -> Result<Config, Vec<String>> {
+        let env = env.iter().copied().collect::<HashMap<_, _>>();
+        let env = |name: &str| env.get(name).map(|value| value.to_string());
+        let matches = layers::command()
+            .try_get_matches_from(["todo-api-rs"].iter().chain(flags))
+            .unwrap();
+
+        let mut layers = Layers::new();
+        layers.read_toml(file, Path::new("config.toml"));
+        layers.read_env(env);
+        layers.read_flags(&matches);
+        Config::from_layers(layers)
+            .map_err(|report| report.errors().iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn layers_override_each_other() {
+        let file = r#"
+            [server]
+            bind = "0.0.0.0:80"
+            body_limit = 1024
+
+            [database]
+            url = "postgres://file@db/todos"
+            max_connections = 20
+
+            [cors]
+            origins = ["https://todo.example.com", "http://*.todo.test:3000"]
+            allow_credentials = true
+
+            [cache]
+            routes = ["GET /todos/:id private max-age=60"]
+
+            [rate_limit]
+            routes = ["POST /todos 60/m", "GET /todos/:id unlimited"]
+
+            [log]
+            redact_descriptions = false
+
+            [telemetry]
+            sample_ratio = 0.25
+        "#;
+        let env = [
+            ("DB_MAX_CONNECTIONS", "30"),
+            ("DB_IDLE_TIMEOUT", "90s"),
+            ("BIND_ADDRESS", "0.0.0.0:8080"),
+            ("LOG_FORMAT", ""),
+            ("RATE_LIMIT_BACKEND", "postgres"),
+            ("REQUEST_TIMEOUT", "5s"),
+            ("JWT_SECRET", "a secret of at least thirty two bytes"),
+        ];
+        let flags = ["--bind", "[::1]:9000", "--log-format", "json"];
+
+        let config = load(file, &env, &flags).unwrap();
+        assert_eq!(config.server.bind, "[::1]:9000".parse().unwrap());
+        assert_eq!(config.server.admin_bind, "127.0.0.1:9090".parse().unwrap());
+        assert_eq!(config.server.body_limit, 1024);
+        assert_eq!(config.server.restore_body_limit, 64 << 20);
+        assert_eq!(config.server.todo_body_limit, 64 << 10);
+        assert_eq!(config.server.request_timeout, Duration::from_secs(5));
+        assert_eq!(config.server.bulk_request_timeout, Duration::from_secs(300));
+        assert_eq!(config.server.max_concurrent_requests, 512);
+        assert_eq!(config.server.drain_delay, Duration::from_secs(5));
+        assert_eq!(config.server.shutdown_timeout, Duration::from_secs(30));
+        assert_eq!(config.database.connection.get_database(), Some("todos"));
+        assert_eq!(config.database.min_connections, 0);
+        assert_eq!(config.database.max_connections, 30);
+        assert_eq!(config.database.connect_timeout, Duration::from_secs(30));
+        assert_eq!(config.database.idle_timeout, Duration::from_secs(90));
+        assert_eq!(config.database.statement_timeout, Duration::from_secs(10));
+        assert_eq!(
+            config.cors.origins,
+            Allowed::List(vec![
+                OriginPattern::Exact(HeaderValue::from_static("https://todo.example.com")),
+                OriginPattern::Subdomains {
+                    scheme: String::from("http://"),
+                    suffix: String::from(".todo.test:3000"),
+                },
+            ])
+        );
+        assert_eq!(
+            config.cors.methods,
+            Allowed::List(vec![Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        );
+        assert_eq!(config.cors.expose_headers[..2], ["etag", "location"]);
+        assert_eq!(config.cors.max_age, Duration::from_secs(600));
+        assert!(config.cors.allow_credentials);
+        assert_eq!(
+            config.auth.jwt_secret,
+            Some(JwtSecret(b"a secret of at least thirty two bytes".to_vec()))
+        );
+        assert_eq!(config.auth.tenant_claim, "tenant_id");
+        assert!(!config.auth.trust_tenant_header);
+        assert!(config.compression.enabled);
+        assert_eq!(config.compression.min_size, 1024);
+        assert_eq!(config.cache.default.to_string(), "private, no-cache");
+        assert_eq!(
+            config.cache.routes,
+            [RouteCachePolicy {
+                method: Method::GET,
+                route: String::from("/todos/:id"),
+                policy: CachePolicy {
+                    directives: vec![String::from("private"), String::from("max-age=60")],
+                },
+            }]
+        );
+        assert_eq!(config.rate_limit.backend, RateLimitBackend::Postgres);
+        assert_eq!(
+            config.rate_limit.default_quota,
+            Some(Quota {
+                limit: 600,
+                period: Duration::from_secs(60),
+            })
+        );
+        assert_eq!(
+            config.rate_limit.routes,
+            [
+                RouteQuota {
+                    method: Method::POST,
+                    route: String::from("/todos"),
+                    quota: Some(Quota {
+                        limit: 60,
+                        period: Duration::from_secs(60),
+                    }),
+                },
+                RouteQuota {
+                    method: Method::GET,
+                    route: String::from("/todos/:id"),
+                    quota: None,
+                },
+            ]
+        );
+        assert_eq!(config.log.format, LogFormat::Json);
+        assert!(!config.log.redact_descriptions);
+        assert_eq!(config.telemetry.otlp_endpoint, None);
+        assert_eq!(config.telemetry.service_name, "todo-api-rs");
+        assert_eq!(config.telemetry.sample_ratio, 0.25);
+    }
+
+    #[test]
+    fn every_error_is_reported() {
+        let file = r#"
+            [server]
+            bind = "localhost"
+            port = 80
+
+            [database]
+            max_connections = 2
+            min_connections = 4
+        "#;
+        let env = [
+            ("DB_HOST", "localhost"),
+            ("DB_PORT", "not a port"),
+            ("DB_CONNECT_TIMEOUT", "0s"),
+            ("MAX_CONCURRENT_REQUESTS", "0"),
+            ("JWT_SECRET", "too short"),
+            ("CORS_ORIGINS", "https://todo.example.com/app"),
+            ("CORS_METHODS", "get"),
+            ("CORS_HEADERS", "*"),
+            ("CORS_ALLOW_CREDENTIALS", "true"),
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "localhost:4318"),
+            ("COMPRESSION_MIN_SIZE", "1MiB"),
+            ("CACHE_DEFAULT", "public private"),
+            ("CACHE_ROUTES", "GET /todos max-age=soon"),
+            ("RATE_LIMIT_DEFAULT", "10/d"),
+            ("RATE_LIMIT_ROUTES", "POST /todos 60/m, /todos 0/s"),
+            ("LOG_REDACT_DESCRIPTIONS", "yes"),
+            ("OTEL_TRACES_SAMPLER_ARG", "1.5"),
+        ];
+        let flags = ["--body-limit", "2 TB", "--db-user", "todo"];
+
+        let errors = load(file, &env, &flags).unwrap_err();
+        assert_eq!(
+            errors,
+            [
+                "server.port from file config.toml is not a known setting",
+                "server.bind from file config.toml must be an IP address and a port, such as 127.0.0.1:8000",
+                "server.body_limit from flag --body-limit must be a size such as 512KiB, 2MiB or 1GB",
+                "server.max_concurrent_requests from env MAX_CONCURRENT_REQUESTS must be at least 1",
+                "database.min_connections from file config.toml must not be more than database.max_connections (2)",
+                "database.connect_timeout from env DB_CONNECT_TIMEOUT must be more than 0",
+                "database.password is required when database.url is not set",
+                "database.name is required when database.url is not set",
+                "database.port from env DB_PORT must be a positive whole number",
+                "auth.jwt_secret from env JWT_SECRET must be at least 32 bytes long",
+                "cors.origins from env CORS_ORIGINS https://todo.example.com/app must be a scheme and a host without a path, such as https://todo.example.com or https://*.example.com",
+                "cors.methods from env CORS_METHODS get is not a method, such as GET",
+                "cors.allow_credentials from env CORS_ALLOW_CREDENTIALS must not be true when cors.headers is *",
+                "compression.min_size from env COMPRESSION_MIN_SIZE must not be more than 64KiB",
+                "cache.default from env CACHE_DEFAULT must not be both public and private",
+                "cache.routes from env CACHE_ROUTES GET /todos max-age=soon must be one of public, private, no-cache, no-store, must-revalidate, immutable, max-age=<seconds> or stale-while-revalidate=<seconds>",
+                "rate_limit.default from env RATE_LIMIT_DEFAULT must be a number of requests per s, m or h, such as 600/m, or unlimited",
+                "rate_limit.routes from env RATE_LIMIT_ROUTES /todos 0/s must be a method, a route and a quota, such as POST /todos 60/m",
+                "log.redact_descriptions from env LOG_REDACT_DESCRIPTIONS must be one of true or false",
+                "telemetry.otlp_endpoint from env OTEL_EXPORTER_OTLP_ENDPOINT must be an http or https URL, such as http://localhost:4318",
+                "telemetry.sample_ratio from env OTEL_TRACES_SAMPLER_ARG must be a number from 0 to 1, such as 0.25",
+            ]
+        );
+    }
+
+    #[test]
+    fn origins_are_parsed() {
+        assert_eq!(parse_allowed("*", parse_origin), Ok(Allowed::Any));
+        assert_eq!(
+            parse_origin("https://*.example.com:8443"),
+            Ok(OriginPattern::Subdomains {
+                scheme: String::from("https://"),
+                suffix: String::from(".example.com:8443"),
+            })
+        );
+        for origin in [
+            "https://*.com",
+            "https://*example.com",
+            "https://todo.*.example.com",
+            "https://*.*.example.com",
+            "ftp://todo.example.com",
+        ] {
+            assert!(parse_origin(origin).is_err(), "{origin}");
+        }
+        assert!(parse_allowed("https://todo.example.com, *", parse_origin).is_err());
+    }
+
+    #[test]
+    fn durations_and_sizes_are_parsed() {
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert!(parse_duration("1.5s").is_err());
+        assert!(parse_duration("s").is_err());
+
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("512KiB"), Ok(512 * 1024));
+        assert_eq!(parse_size("2 MB"), Ok(2_000_000));
+        assert!(parse_size("-1").is_err());
+        assert!(parse_size("2mb").is_err());
+    }
+}
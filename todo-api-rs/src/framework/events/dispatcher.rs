@@ -0,0 +1,38 @@
+use crate::application::dispatchers::todo::TodoEventDispatcher;
+use crate::domain::events::todo::TodoEvent;
+use crate::domain::types::Id;
+
+/// [`TodoEventDispatcher`] that traces every domain event. Integrations that
+/// must not miss a change read the outbox instead, since dispatching happens
+/// after the transaction committed and is lost if the process stops
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogDispatcher {
+    tenant_id: Option<Id>,
+}
+
+impl LogDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tag dispatched events with the tenant with `tenant_id`
+    pub fn with_tenant(mut self, tenant_id: Id) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+}
+
+impl TodoEventDispatcher for LogDispatcher {
+    fn dispatch(&self, events: Vec<TodoEvent>) {
+        // events hold the titles and descriptions of todos, so only what
+        // identifies them is logged
+        for event in events {
+            tracing::info!(
+                event.name = event.name(),
+                todo.id = %event.todo_id(),
+                tenant.id = self.tenant_id.map(tracing::field::display),
+                "Todo event"
+            );
+        }
+    }
+}
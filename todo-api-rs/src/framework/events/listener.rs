@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use super::todo::TodoBroadcaster;
+use crate::framework::storage::repositories::outbox::{decode_broadcast, BROADCAST_CHANNEL};
+
+/// Delay before connecting again when listening fails
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Spawn the listener that publishes the events relayed by any instance to
+/// the subscribers of `events`, until `shutdown` is cancelled. Events relayed
+/// while the listener is disconnected are missed
+pub fn start(pool: PgPool, events: TodoBroadcaster, shutdown: CancellationToken) -> JoinHandle<()> {
+    tokio::spawn(listen(pool, events, shutdown))
+}
+
+async fn listen(pool: PgPool, events: TodoBroadcaster, shutdown: CancellationToken) {
+    while !shutdown.is_cancelled() {
+        let mut listener = match connect(&pool).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::error!("Failed listening to todo events: {err}");
+                tokio::select! {
+                    _ = time::sleep(RETRY_DELAY) => continue,
+                    _ = shutdown.cancelled() => break,
+                }
+            }
+        };
+
+        loop {
+            let received = tokio::select! {
+                received = listener.try_recv() => received,
+                _ = shutdown.cancelled() => break,
+            };
+
+            match received {
+                Ok(Some(notification)) => publish(&events, notification.payload()),
+                // the listener reconnects on the next receive
+                Ok(None) => tracing::warn!(
+                    "Todo events listener disconnected, missing the events relayed meanwhile"
+                ),
+                Err(err) => {
+                    tracing::error!("Failed receiving todo events: {err}");
+                    break;
+                }
+            }
+        }
+    }
+
+    tracing::info!("Todo events listener stopped");
+}
+
+async fn connect(pool: &PgPool) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(BROADCAST_CHANNEL).await?;
+    Ok(listener)
+}
+
+fn publish(events: &TodoBroadcaster, payload: &str) {
+    match decode_broadcast(payload) {
+        Ok((tenant_id, event)) => events.clone().with_tenant(tenant_id).publish(event),
+        Err(err) => tracing::error!("Invalid todo event notification: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::application::repositories::todo::TodoRepository;
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::entities::todo::Status;
+    use crate::domain::types::Id;
+    use crate::framework::events::todo::Received;
+    use crate::framework::outbox::relay::{self, RelayConfig};
+    use crate::framework::outbox::sinks::BroadcastSink;
+    use crate::framework::storage::repositories::todo::PgTodoRepository;
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn events_reach_every_instance(pool: PgPool) {
+        let shutdown = CancellationToken::new();
+        let tenant_id = Id::new();
+
+        // only the first instance relays events, while both have subscribers
+        let instances = [TodoBroadcaster::new(8), TodoBroadcaster::new(8)];
+        let mut subscriptions = Vec::new();
+        for events in &instances {
+            start(pool.clone(), events.clone(), shutdown.clone());
+            let events = events.clone().with_tenant(tenant_id);
+            subscriptions.push(Box::pin(events.subscribe(None)));
+        }
+        relay::start(
+            pool.clone(),
+            RelayConfig::default(),
+            vec![Arc::new(BroadcastSink::new(pool.clone()))],
+            shutdown.clone(),
+        );
+
+        // give the listeners time to listen before the event is relayed
+        time::sleep(Duration::from_millis(200)).await;
+        let mut repository = PgTodoRepository::new(pool.clone()).with_tenant(tenant_id);
+        let todo = todo("Everywhere", Status::Todo);
+        repository.create(todo.clone()).await.unwrap();
+
+        let mut ids = Vec::new();
+        for subscription in &mut subscriptions {
+            let received = time::timeout(Duration::from_secs(5), subscription.next()).await;
+            let Ok(Some(Received::Event(event))) = received else {
+                panic!("Instance did not receive the relayed event");
+            };
+            assert_eq!(event.change.todo().id(), todo.id());
+            ids.push(event.id);
+        }
+
+        // subscribers may resume on any instance
+        assert_eq!(ids[0], ids[1]);
+        shutdown.cancel();
+    }
+}
@@ -0,0 +1,3 @@
+pub mod dispatcher;
+pub mod listener;
+pub mod todo;
\ No newline at end of file
@@ -0,0 +1,298 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use futures_util::stream::{self, Stream, StreamExt};
+use tokio::sync::broadcast::{self, error::RecvError, Receiver, Sender};
+
+use crate::application::publishers::todo::TodoChange;
+use crate::domain::types::Id;
+
+/// Tenants whose events are kept at most, past which the ones without
+/// subscribers that published last the longest ago are forgotten
+const MAX_TENANTS: usize = 4096;
+
+/// Change published by a tenant, identified by the order it was written to
+/// the outbox in, which is the same on every instance and across restarts
+#[derive(Clone, Debug)]
+pub struct TodoEvent {
+    pub id: u64,
+    pub change: TodoChange,
+}
+
+impl TodoEvent {
+    /// Name of the event such as `todo.created`
+    pub fn name(&self) -> &'static str {
+        self.change.name()
+    }
+}
+
+/// Item of a subscription
+#[derive(Clone, Debug)]
+pub enum Received {
+    /// Events after the last one the subscriber received are no longer kept,
+    /// so it must reload the todos before applying the following events
+    Reset,
+    Event(Arc<TodoEvent>),
+}
+
+/// Broadcast events to the subscribers of their tenant, keeping the latest
+/// events of every tenant so subscribers can resume after reconnecting. Every
+/// instance receives the events relayed by any of them, see
+/// [`listener`](super::listener), so subscribers may resume on another one
+#[derive(Clone)]
+pub struct TodoBroadcaster {
+    shared: Arc<Shared>,
+    tenant_id: Option<Id>,
+}
+
+struct Shared {
+    capacity: usize,
+    topics: Mutex<Topics>,
+}
+
+struct Topics {
+    by_tenant: HashMap<Option<Id>, Topic>,
+}
+
+/// Subscribers and latest events of a tenant
+struct Topic {
+    sender: Sender<Arc<TodoEvent>>,
+    events: VecDeque<Arc<TodoEvent>>,
+}
+
+impl TodoBroadcaster {
+    /// Create a broadcaster that keeps at most `capacity` events of every
+    /// tenant for resuming, which is also how far behind a subscriber can be
+    /// before being dropped
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                capacity: capacity.max(1),
+                topics: Mutex::new(Topics {
+                    by_tenant: HashMap::new(),
+                }),
+            }),
+            tenant_id: None,
+        }
+    }
+
+    /// Publish and subscribe to changes of the tenant with `tenant_id` only
+    pub fn with_tenant(mut self, tenant_id: Id) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
+    /// Stream the events published after the one with `last_event_id`, starting
+    /// with the ones still kept. When `last_event_id` is no longer kept, such as
+    /// after a restart, the stream starts with a [`Received::Reset`] followed by
+    /// every kept event. The stream ends when the subscriber falls too far
+    /// behind, so it may resume from the last event it received.
+    ///
+    /// Events are replayed in the order they were published, which is not the
+    /// order of their ids for events relayed again after a failure
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> impl Stream<Item = Received> {
+        // subscribing while holding the lock guarantees no event is missed nor
+        // duplicated between the replayed and the live ones
+        let (reset, replay, receiver) = {
+            let mut topics = self.lock();
+            let topic = topics.topic(self.tenant_id, self.shared.capacity);
+
+            let last = last_event_id.map(|id| topic.events.iter().position(|event| event.id == id));
+            let reset = matches!(last, Some(None));
+            let skipped = match last {
+                Some(Some(position)) => position + 1,
+                _ => 0,
+            };
+            let replay = topic
+                .events
+                .iter()
+                .skip(skipped)
+                .cloned()
+                .map(Received::Event)
+                .collect::<Vec<_>>();
+
+            (reset, replay, topic.sender.subscribe())
+        };
+
+        let reset = reset.then_some(Received::Reset);
+        stream::iter(reset.into_iter().chain(replay))
+            .chain(stream::unfold(receiver, receive).map(Received::Event))
+    }
+
+    /// Publish `event` to the subscribers of the tenant, unless an event with
+    /// the same id is still kept as events are relayed at least once
+    pub fn publish(&self, event: TodoEvent) {
+        let mut topics = self.lock();
+        let capacity = self.shared.capacity;
+        let topic = topics.topic(self.tenant_id, capacity);
+        if topic.events.iter().any(|kept| kept.id == event.id) {
+            return;
+        }
+
+        let event = Arc::new(event);
+        if topic.events.len() == capacity {
+            topic.events.pop_front();
+        }
+        topic.events.push_back(event.clone());
+
+        // sending only fails when there are no subscribers
+        _ = topic.sender.send(event);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Topics> {
+        self.shared
+            .topics
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Topics {
+    /// Topic of a tenant, created when it has none
+    fn topic(&mut self, tenant_id: Option<Id>, capacity: usize) -> &mut Topic {
+        if !self.by_tenant.contains_key(&tenant_id) && self.by_tenant.len() >= MAX_TENANTS {
+            self.forget_idle();
+        }
+
+        self.by_tenant.entry(tenant_id).or_insert_with(|| Topic {
+            sender: broadcast::channel(capacity).0,
+            events: VecDeque::with_capacity(capacity),
+        })
+    }
+
+    /// Forget the tenant without subscribers that published last the longest
+    /// ago. Tenants with subscribers are kept, as they are bounded by the
+    /// connections of the server
+    fn forget_idle(&mut self) {
+        let idle = self
+            .by_tenant
+            .iter()
+            .filter(|(_, topic)| topic.sender.receiver_count() == 0)
+            .min_by_key(|(_, topic)| topic.events.back().map_or(0, |event| event.id))
+            .map(|(tenant_id, _)| *tenant_id);
+
+        if let Some(tenant_id) = idle {
+            self.by_tenant.remove(&tenant_id);
+        }
+    }
+}
+
+async fn receive(
+    mut receiver: Receiver<Arc<TodoEvent>>,
+) -> Option<(Arc<TodoEvent>, Receiver<Arc<TodoEvent>>)> {
+    match receiver.recv().await {
+        Ok(event) => Some((event, receiver)),
+        Err(RecvError::Lagged(skipped)) => {
+            tracing::warn!(skipped, "Todo events subscriber lagged behind");
+            None
+        }
+        Err(RecvError::Closed) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::FutureExt;
+
+    use super::*;
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::entities::todo::Status;
+
+    /// Collect the ids of every event that is ready without waiting for new
+    /// ones, with none for a reset
+    fn ready_ids(events: impl Stream<Item = Received>) -> Vec<Option<u64>> {
+        let mut events = Box::pin(events);
+        let mut ids = Vec::new();
+        while let Some(Some(received)) = events.next().now_or_never() {
+            ids.push(match received {
+                Received::Reset => None,
+                Received::Event(event) => Some(event.id),
+            });
+        }
+        ids
+    }
+
+    /// Publish an event with every id, in order
+    fn publish(tenant: &TodoBroadcaster, ids: &[u64]) {
+        for id in ids {
+            tenant.publish(TodoEvent {
+                id: *id,
+                change: TodoChange::Created(todo("Published", Status::Todo)),
+            });
+        }
+    }
+
+    #[test]
+    fn subscribe_resumes_after_last_event() {
+        let tenant = TodoBroadcaster::new(8).with_tenant(Id::new());
+        publish(&tenant, &[1, 2, 3]);
+
+        assert_eq!(
+            ready_ids(tenant.subscribe(None)),
+            vec![Some(1), Some(2), Some(3)]
+        );
+        assert_eq!(ready_ids(tenant.subscribe(Some(1))), vec![Some(2), Some(3)]);
+        assert!(ready_ids(tenant.subscribe(Some(3))).is_empty());
+    }
+
+    #[test]
+    fn unknown_ids_reset_the_subscriber() {
+        let tenant = TodoBroadcaster::new(2).with_tenant(Id::new());
+        publish(&tenant, &[1, 2, 3]);
+
+        // the first event is no longer kept, and other ids were never published
+        for last_event_id in [1, 7] {
+            assert_eq!(
+                ready_ids(tenant.subscribe(Some(last_event_id))),
+                vec![None, Some(2), Some(3)]
+            );
+        }
+    }
+
+    #[test]
+    fn events_are_replayed_in_published_order() {
+        let tenant = TodoBroadcaster::new(8).with_tenant(Id::new());
+
+        // the event with id 2 was relayed again after failing
+        publish(&tenant, &[1, 3, 2, 4]);
+
+        assert_eq!(ready_ids(tenant.subscribe(Some(3))), vec![Some(2), Some(4)]);
+    }
+
+    #[test]
+    fn duplicates_are_skipped() {
+        let tenant = TodoBroadcaster::new(8).with_tenant(Id::new());
+        let live = tenant.subscribe(None);
+        publish(&tenant, &[1, 2, 1]);
+
+        assert_eq!(ready_ids(live), vec![Some(1), Some(2)]);
+        assert_eq!(ready_ids(tenant.subscribe(None)), vec![Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn tenants_only_receive_their_events() {
+        let broadcaster = TodoBroadcaster::new(2);
+        let tenant_a = broadcaster.clone().with_tenant(Id::new());
+        let tenant_b = broadcaster.with_tenant(Id::new());
+
+        let live_a = tenant_a.subscribe(None);
+        publish(&tenant_a, &[1]);
+        publish(&tenant_b, &[2, 3]);
+        publish(&tenant_a, &[4]);
+
+        // every tenant keeps its own history
+        assert_eq!(ready_ids(live_a), vec![Some(1), Some(4)]);
+        assert_eq!(ready_ids(tenant_a.subscribe(None)), vec![Some(1), Some(4)]);
+        assert_eq!(ready_ids(tenant_b.subscribe(None)), vec![Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn lagging_subscriber_stream_ends() {
+        let tenant = TodoBroadcaster::new(1).with_tenant(Id::new());
+        let mut live = Box::pin(tenant.subscribe(None));
+
+        publish(&tenant, &[1, 2]);
+
+        assert!(matches!(live.next().now_or_never(), Some(None)));
+    }
+}
@@ -0,0 +1,124 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use sqlx::{Error as SqlxError, PgPool};
+use thiserror::Error;
+
+use crate::framework::storage::health;
+
+/// Whether the server takes new work, which stops once it starts shutting
+/// down so load balancers route requests to other instances
+#[derive(Clone, Debug, Default)]
+pub struct Readiness {
+    draining: Arc<AtomicBool>,
+}
+
+impl Readiness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail every following readiness check
+    pub fn drain(&self) {
+        self.draining.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+}
+
+/// Checks every dependency the server needs to handle requests
+#[derive(Clone, Debug)]
+pub struct HealthChecker {
+    pool: PgPool,
+    readiness: Readiness,
+    /// Time every check has before it fails
+    timeout: Duration,
+}
+
+#[derive(Debug, Error)]
+pub enum CheckError {
+    #[error("Check timed out after {0:?}")]
+    Timeout(Duration),
+    #[error(transparent)]
+    Database(#[from] SqlxError),
+}
+
+#[derive(Debug)]
+pub struct ReadinessReport {
+    pub draining: bool,
+    /// Time the database took to answer
+    pub database: Result<Duration, CheckError>,
+    /// Versions of the migrations that are not applied
+    pub pending_migrations: Result<Vec<i64>, CheckError>,
+    pub pool: PoolUsage,
+}
+
+/// Connections of the pool, which is saturated when every connection it can
+/// open is in use and requests wait for one to be released
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolUsage {
+    pub size: u32,
+    pub idle: usize,
+    pub max: u32,
+}
+
+impl ReadinessReport {
+    /// Whether the server should receive requests. A saturated pool is not
+    /// a failure since requests still complete once a connection is released
+    pub fn is_ready(&self) -> bool {
+        let migrated = matches!(&self.pending_migrations, Ok(pending) if pending.is_empty());
+        !self.draining && self.database.is_ok() && migrated
+    }
+}
+
+impl PoolUsage {
+    pub fn is_saturated(&self) -> bool {
+        self.size >= self.max && self.idle == 0
+    }
+}
+
+impl HealthChecker {
+    pub fn new(pool: PgPool, readiness: Readiness, timeout: Duration) -> Self {
+        Self {
+            pool,
+            readiness,
+            timeout,
+        }
+    }
+
+    /// Check every dependency concurrently, each within the timeout
+    pub async fn check(&self) -> ReadinessReport {
+        let database = self.bounded(async {
+            let start = Instant::now();
+            health::ping(&self.pool).await?;
+            Ok(start.elapsed())
+        });
+        let pending_migrations = self.bounded(health::pending_migrations(&self.pool));
+        let (database, pending_migrations) = tokio::join!(database, pending_migrations);
+
+        ReadinessReport {
+            draining: self.readiness.is_draining(),
+            database,
+            pending_migrations,
+            pool: PoolUsage {
+                size: self.pool.size(),
+                idle: self.pool.num_idle(),
+                max: self.pool.options().get_max_connections(),
+            },
+        }
+    }
+
+    async fn bounded<T>(
+        &self,
+        check: impl Future<Output = Result<T, SqlxError>>,
+    ) -> Result<T, CheckError> {
+        match tokio::time::timeout(self.timeout, check).await {
+            Ok(result) => Ok(result?),
+            Err(_) => Err(CheckError::Timeout(self.timeout)),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use super::METRICS;
+
+/// Route of the requests that match none, so unknown paths share one series
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Middleware counting requests and timing them until their response is
+/// ready, by the template of their route and their status
+pub async fn track(req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(UNMATCHED_ROUTE, |path| path.as_str())
+        .to_owned();
+
+    let started_at = Instant::now();
+    let res = next.run(req).await;
+    METRICS.observe_request(&method, &route, res.status(), started_at.elapsed());
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::middleware;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_are_recorded_by_route_template() {
+        let app = Router::new()
+            .route("/metrics-test/:id", get(|| async { "found" }))
+            .layer(middleware::from_fn(track));
+
+        for uri in ["/metrics-test/1", "/metrics-test/2", "/metrics-test"] {
+            let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            app.clone().oneshot(req).await.unwrap();
+        }
+
+        let rendered = METRICS.render();
+        assert!(rendered.contains(
+            "http_requests_total{method=\"GET\",route=\"/metrics-test/:id\",status=\"200\"} 2\n"
+        ));
+        assert!(!rendered.contains("route=\"/metrics-test/1\""));
+    }
+}
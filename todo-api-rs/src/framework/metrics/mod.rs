@@ -0,0 +1,196 @@
+mod http;
+mod use_case;
+
+pub use http::track;
+pub use use_case::Measured;
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use axum::http::{Method, StatusCode};
+use prometheus::core::Collector;
+use prometheus::{
+    Histogram, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use sqlx::PgPool;
+
+use crate::domain::entities::todo::Status;
+
+/// Metrics of the server, shared by every part of it like its logs
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Statuses of todos, which each have a gauge even when no todo has them
+const STATUSES: [Status; 3] = [Status::Todo, Status::InProgress, Status::Done];
+
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    use_case_successes: IntCounterVec,
+    use_case_errors: IntCounterVec,
+    pool_connections: IntGauge,
+    pool_idle_connections: IntGauge,
+    pool_max_connections: IntGauge,
+    pool_acquire_duration: Histogram,
+    todos: IntGaugeVec,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+        let http_labels = ["method", "route", "status"];
+
+        Self {
+            http_requests: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("http_requests_total", "HTTP requests by route and status"),
+                    &http_labels,
+                ),
+            ),
+            http_request_duration: register(
+                &registry,
+                HistogramVec::new(
+                    HistogramOpts::new(
+                        "http_request_duration_seconds",
+                        "Time until the response of HTTP requests is ready",
+                    ),
+                    &http_labels,
+                ),
+            ),
+            use_case_successes: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("use_case_successes_total", "Use cases that succeeded"),
+                    &["use_case"],
+                ),
+            ),
+            use_case_errors: register(
+                &registry,
+                IntCounterVec::new(
+                    Opts::new("use_case_errors_total", "Use cases that failed by error"),
+                    &["use_case", "error"],
+                ),
+            ),
+            pool_connections: register(
+                &registry,
+                IntGauge::new("db_pool_connections", "Connections open in the pool"),
+            ),
+            pool_idle_connections: register(
+                &registry,
+                IntGauge::new("db_pool_idle_connections", "Connections idle in the pool"),
+            ),
+            pool_max_connections: register(
+                &registry,
+                IntGauge::new(
+                    "db_pool_max_connections",
+                    "Connections the pool opens at most",
+                ),
+            ),
+            pool_acquire_duration: register(
+                &registry,
+                Histogram::with_opts(HistogramOpts::new(
+                    "db_pool_acquire_duration_seconds",
+                    "Time waited for a connection of the pool",
+                )),
+            ),
+            todos: register(
+                &registry,
+                IntGaugeVec::new(
+                    Opts::new("todos", "Todos of every tenant by status"),
+                    &["status"],
+                ),
+            ),
+            registry,
+        }
+    }
+
+    /// Record a request under the template of its route, such as
+    /// `/todos/:id`, so ids do not create a series each
+    pub fn observe_request(
+        &self,
+        method: &Method,
+        route: &str,
+        status: StatusCode,
+        duration: Duration,
+    ) {
+        let labels = [method.as_str(), route, status.as_str()];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_request_duration
+            .with_label_values(&labels)
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_success(&self, use_case: &str) {
+        self.use_case_successes.with_label_values(&[use_case]).inc();
+    }
+
+    /// Record an error of a use case under the name of its variant, such as
+    /// `NotFound`
+    pub fn observe_error(&self, use_case: &str, error: &str) {
+        self.use_case_errors
+            .with_label_values(&[use_case, error])
+            .inc();
+    }
+
+    pub fn observe_pool_acquire(&self, duration: Duration) {
+        self.pool_acquire_duration.observe(duration.as_secs_f64());
+    }
+
+    /// Update the gauges of the pool with its current usage
+    pub fn observe_pool(&self, pool: &PgPool) {
+        self.pool_connections.set(i64::from(pool.size()));
+        self.pool_idle_connections
+            .set(i64::try_from(pool.num_idle()).unwrap_or(i64::MAX));
+        self.pool_max_connections
+            .set(i64::from(pool.options().get_max_connections()));
+    }
+
+    /// Update the gauges of todos, resetting the statuses without any todo
+    pub fn observe_todos(&self, counts: &[(Status, i64)]) {
+        for status in &STATUSES {
+            let count = counts
+                .iter()
+                .find(|(counted, _)| counted == status)
+                .map_or(0, |(_, count)| *count);
+            self.todos
+                .with_label_values(&[&status.to_string()])
+                .set(count);
+        }
+    }
+
+    /// Every metric in the Prometheus text format
+    pub fn render(&self) -> String {
+        TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .expect("Metrics are always valid")
+    }
+}
+
+fn register<T>(registry: &Registry, metric: prometheus::Result<T>) -> T
+where
+    T: Collector + Clone + 'static,
+{
+    let metric = metric.expect("Metric options are valid");
+    registry
+        .register(Box::new(metric.clone()))
+        .expect("Metric names are unique");
+    metric
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_status_has_a_gauge() {
+        let metrics = Metrics::new();
+        metrics.observe_todos(&[(Status::Done, 3)]);
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("todos{status=\"done\"} 3\n"));
+        assert!(rendered.contains("todos{status=\"in_progress\"} 0\n"));
+        assert!(rendered.contains("todos{status=\"todo\"} 0\n"));
+    }
+}
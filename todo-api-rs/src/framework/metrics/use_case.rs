@@ -0,0 +1,58 @@
+use crate::domain::use_case::{UseCase, UseCaseError};
+
+use super::METRICS;
+
+/// Use case counting its successes, and its errors by variant
+#[derive(Debug)]
+pub struct Measured<U> {
+    name: &'static str,
+    use_case: U,
+}
+
+impl<U> Measured<U> {
+    pub fn new(name: &'static str, use_case: U) -> Self {
+        Self { name, use_case }
+    }
+}
+
+impl<I, T, E, U> UseCase<I, Result<T, E>> for Measured<U>
+where
+    U: UseCase<I, Result<T, E>>,
+    E: UseCaseError,
+{
+    async fn exec(self, input: I) -> Result<T, E> {
+        let output = self.use_case.exec(input).await;
+        match &output {
+            Ok(_) => METRICS.observe_success(self.name),
+            Err(err) => METRICS.observe_error(self.name, err.kind()),
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::dtos::todo::find::FindTodoError;
+
+    struct FindNothing;
+
+    impl UseCase<(), Result<(), FindTodoError>> for FindNothing {
+        async fn exec(self, _: ()) -> Result<(), FindTodoError> {
+            Err(FindTodoError::NotFound)
+        }
+    }
+
+    #[tokio::test]
+    async fn errors_are_counted_by_variant() {
+        for _ in 0..2 {
+            let _ = Measured::new("find_nothing", FindNothing).exec(()).await;
+        }
+
+        let rendered = METRICS.render();
+        assert!(rendered
+            .contains("use_case_errors_total{error=\"NotFound\",use_case=\"find_nothing\"} 2\n"));
+        assert!(!rendered.contains("use_case_successes_total{use_case=\"find_nothing\"}"));
+    }
+}
@@ -1,2 +1,11 @@
+pub mod config;
+pub mod events;
+pub mod health;
+pub mod metrics;
+pub mod outbox;
+pub mod rate_limit;
 pub mod rest_api;
+pub mod shutdown;
 pub mod storage;
+pub mod telemetry;
+pub mod webhooks;
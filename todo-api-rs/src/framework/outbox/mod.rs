@@ -0,0 +1,32 @@
+pub mod relay;
+pub mod sinks;
+
+use std::error;
+
+use futures_util::future::BoxFuture;
+
+use crate::application::publishers::todo::TodoChange;
+use crate::domain::types::{DateTime, Id};
+
+/// Change written to the outbox by the transaction that made it
+#[derive(Clone, Debug)]
+pub struct OutboxEvent {
+    /// Same every time the event is relayed, so sinks can ignore duplicates
+    pub id: Id,
+    pub tenant_id: Id,
+    pub change: TodoChange,
+    /// Failed attempts to relay the event
+    pub attempt_count: u32,
+    pub occurred_at: DateTime,
+}
+
+pub type SinkError = Box<dyn error::Error + Send + Sync>;
+
+/// Destination of the events relayed from the outbox. Events are relayed at
+/// least once, since every sink receives an event again when any of them
+/// failed, so sinks are expected to deduplicate them by [`OutboxEvent::id`]
+pub trait OutboxSink: Send + Sync {
+    /// Name used to report errors of the sink
+    fn name(&self) -> &'static str;
+    fn dispatch<'a>(&'a self, event: &'a OutboxEvent) -> BoxFuture<'a, Result<(), SinkError>>;
+}
@@ -0,0 +1,427 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::postgres::PgListener;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tokio::time;
+use tokio_util::sync::CancellationToken;
+
+use super::{OutboxEvent, OutboxSink};
+use crate::framework::storage::repositories::outbox::PgOutbox;
+
+/// Channel notified by the outbox table whenever events are written
+const OUTBOX_CHANNEL: &str = "outbox";
+
+#[derive(Clone, Debug)]
+pub struct RelayConfig {
+    /// Attempts before an event is dead
+    pub max_attempts: u32,
+    /// Events claimed at once
+    pub batch_size: i64,
+    /// Time a claimed event is hidden from other relays, which must be longer
+    /// than dispatching a whole batch takes
+    pub lease: Duration,
+    /// How often the outbox is checked when no notification was received
+    pub poll_interval: Duration,
+    /// Delay before relaying a failed event again, doubled on every following failure
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 12,
+            batch_size: 64,
+            lease: Duration::from_secs(30),
+            poll_interval: Duration::from_secs(5),
+            base_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl RelayConfig {
+    /// Delay before the attempt following `attempt`, starting from 1
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Spawn the relay that dispatches outbox events to every sink, waking up
+/// when events are written or every poll interval otherwise. Events are
+/// relayed in the order they were written, except failed ones that are
+/// retried after the following ones.
+///
+/// Once `shutdown` is cancelled, the relay finishes dispatching the events it
+/// claimed and stops
+pub fn start(
+    pool: PgPool,
+    config: RelayConfig,
+    sinks: Vec<Arc<dyn OutboxSink>>,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(relay(pool, config, sinks, shutdown))
+}
+
+async fn relay(
+    pool: PgPool,
+    config: RelayConfig,
+    sinks: Vec<Arc<dyn OutboxSink>>,
+    shutdown: CancellationToken,
+) {
+    let outbox = PgOutbox::new(pool.clone());
+    let mut listener = listen(&pool).await;
+
+    while !shutdown.is_cancelled() {
+        match outbox.claim(config.batch_size, config.lease).await {
+            Ok(events) => {
+                let claimed = events.len();
+                for event in events {
+                    dispatch(&outbox, &config, &sinks, event).await;
+                }
+
+                // keep going while there may be more due events
+                if claimed as i64 == config.batch_size {
+                    continue;
+                }
+            }
+            Err(err) => tracing::error!("Failed claiming outbox events: {err}"),
+        }
+
+        tokio::select! {
+            _ = wait(listener.as_mut(), config.poll_interval) => {}
+            _ = shutdown.cancelled() => {}
+        }
+    }
+
+    tracing::info!("Outbox relay stopped");
+}
+
+/// Listen to outbox notifications, or only poll when listening fails
+async fn listen(pool: &PgPool) -> Option<PgListener> {
+    let mut listener = match PgListener::connect_with(pool).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            tracing::error!("Failed connecting outbox listener, polling only: {err}");
+            return None;
+        }
+    };
+
+    if let Err(err) = listener.listen(OUTBOX_CHANNEL).await {
+        tracing::error!("Failed listening to outbox notifications, polling only: {err}");
+        return None;
+    }
+
+    Some(listener)
+}
+
+/// Wait for an outbox notification for at most `timeout`
+async fn wait(listener: Option<&mut PgListener>, timeout: Duration) {
+    let Some(listener) = listener else {
+        return time::sleep(timeout).await;
+    };
+
+    if let Ok(Err(err)) = time::timeout(timeout, listener.recv()).await {
+        // the listener reconnects on the next receive, in the meantime events are polled
+        tracing::warn!("Outbox listener disconnected: {err}");
+        time::sleep(timeout).await;
+    }
+}
+
+async fn dispatch(
+    outbox: &PgOutbox,
+    config: &RelayConfig,
+    sinks: &[Arc<dyn OutboxSink>],
+    event: OutboxEvent,
+) {
+    let mut errors = Vec::new();
+    for sink in sinks {
+        if let Err(err) = sink.dispatch(&event).await {
+            errors.push(format!("{}: {err}", sink.name()));
+        }
+    }
+
+    if errors.is_empty() {
+        if let Err(err) = outbox.complete(&event).await {
+            tracing::error!(
+                outbox.id = %event.id,
+                tenant.id = %event.tenant_id,
+                error = %err,
+                "Failed completing outbox event"
+            );
+        }
+        return;
+    }
+
+    let error = errors.join("; ");
+    let attempt = event.attempt_count + 1;
+    let retry_at = (attempt < config.max_attempts)
+        .then(|| OffsetDateTime::now_utc() + config.backoff(attempt));
+    match retry_at {
+        Some(retry_at) => tracing::warn!(
+            outbox.id = %event.id,
+            tenant.id = %event.tenant_id,
+            attempt,
+            retry_at = %retry_at,
+            error,
+            "Outbox event attempt failed, retrying"
+        ),
+        None => tracing::error!(
+            outbox.id = %event.id,
+            tenant.id = %event.tenant_id,
+            attempt,
+            error,
+            "Outbox event failed after its last attempt"
+        ),
+    }
+
+    if let Err(err) = outbox.fail(&event, &error, retry_at).await {
+        tracing::error!(
+            outbox.id = %event.id,
+            tenant.id = %event.tenant_id,
+            error = %err,
+            "Failed recording outbox event failure"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures_util::future::{self, BoxFuture};
+
+    use super::*;
+    use crate::application::repositories::todo::TodoRepository;
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::entities::todo::{Status, Title};
+    use crate::domain::types::Id;
+    use crate::framework::outbox::SinkError;
+    use crate::framework::storage::repositories::tenant_pool;
+    use crate::framework::storage::repositories::todo::PgTodoRepository;
+
+    /// Sink that records every event it receives, failing the first `failures` ones
+    #[derive(Default)]
+    struct RecordingSink {
+        failures: Mutex<u32>,
+        received: Mutex<Vec<(Id, &'static str)>>,
+    }
+
+    impl OutboxSink for RecordingSink {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn dispatch<'a>(&'a self, event: &'a OutboxEvent) -> BoxFuture<'a, Result<(), SinkError>> {
+            let mut received = self.received.lock().unwrap();
+            received.push((event.id, event.change.name()));
+
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Box::pin(future::err("unavailable".into()));
+            }
+
+            Box::pin(future::ok(()))
+        }
+    }
+
+    fn config() -> RelayConfig {
+        RelayConfig {
+            base_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_millis(50),
+            poll_interval: Duration::from_secs(10),
+            ..RelayConfig::default()
+        }
+    }
+
+    async fn outbox_count(pool: &PgPool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM outbox")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// Wait until the sink received `count` events
+    async fn received(sink: &RecordingSink, count: usize) -> Vec<(Id, &'static str)> {
+        for _ in 0..200 {
+            let received = sink.received.lock().unwrap().clone();
+            if received.len() >= count {
+                return received;
+            }
+            time::sleep(Duration::from_millis(20)).await;
+        }
+
+        panic!("Sink did not receive {count} events");
+    }
+
+    /// Stop the relay, so it releases the pool before the test database is dropped
+    async fn stop(relay: JoinHandle<()>) {
+        relay.abort();
+        _ = relay.await;
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let config = RelayConfig {
+            base_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            ..RelayConfig::default()
+        };
+
+        let delays = (1..=5).map(|attempt| config.backoff(attempt).as_secs());
+        assert_eq!(delays.collect::<Vec<_>>(), [1, 2, 4, 5, 5]);
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn changes_are_relayed_in_order(pool: PgPool) {
+        // the relay runs as a regular role, which only reaches the events of
+        // every tenant through the claim function
+        let sink = Arc::new(RecordingSink::default());
+        let relay = start(
+            tenant_pool(&pool).await,
+            config(),
+            vec![sink.clone()],
+            CancellationToken::new(),
+        );
+
+        let mut repository = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let todo = todo("Relayed", Status::Todo);
+        repository.create(todo.clone()).await.unwrap();
+
+        // failed changes are not written to the outbox
+        assert!(repository.create(todo.clone()).await.is_err());
+
+        let mut updated = repository.find(todo.id()).await.unwrap();
+        let found_at = updated.updated_at();
+        updated.rename(Title::new("Relayed again").unwrap());
+        updated.complete().unwrap();
+        repository.update(updated, found_at).await.unwrap();
+        repository.delete(todo.id()).await.unwrap();
+
+        // notifications wake up the relay long before the poll interval
+        let received = received(&sink, 3).await;
+        let names = received.iter().map(|(_, name)| *name).collect::<Vec<_>>();
+        assert_eq!(names, ["todo.created", "todo.updated", "todo.deleted"]);
+
+        time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(outbox_count(&pool).await, 0);
+        stop(relay).await;
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn failed_events_are_relayed_again(pool: PgPool) {
+        let sink = Arc::new(RecordingSink {
+            failures: Mutex::new(2),
+            ..RecordingSink::default()
+        });
+        let config = RelayConfig {
+            poll_interval: Duration::from_millis(20),
+            ..config()
+        };
+        let relay = start(
+            tenant_pool(&pool).await,
+            config,
+            vec![sink.clone()],
+            CancellationToken::new(),
+        );
+
+        let mut repository = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let todo = todo("Retried", Status::Todo);
+        repository.create(todo).await.unwrap();
+
+        // retries are only picked up by polling, and carry the same deduplication id
+        let received = received(&sink, 3).await;
+        assert!(received.iter().all(|(id, _)| *id == received[0].0));
+
+        time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(outbox_count(&pool).await, 0);
+        stop(relay).await;
+    }
+
+    async fn dead_count(pool: &PgPool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM outbox WHERE status = 'dead'")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn events_are_dead_after_the_last_attempt(pool: PgPool) {
+        const INSERT_INVALID_Q: &str = r#"
+            INSERT INTO outbox (id, tenant_id, event, payload, next_attempt_at, created_at)
+            VALUES ($1, $2, 'todo.created', '{}'::jsonb, now(), now())
+        "#;
+
+        let sink = Arc::new(RecordingSink {
+            failures: Mutex::new(u32::MAX),
+            ..RecordingSink::default()
+        });
+        let config = RelayConfig {
+            max_attempts: 2,
+            poll_interval: Duration::from_millis(20),
+            ..config()
+        };
+
+        // events that cannot be decoded are dead without reaching the sinks
+        sqlx::query(INSERT_INVALID_Q)
+            .bind(Id::new().uuid())
+            .bind(Id::new().uuid())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let relay = start(
+            tenant_pool(&pool).await,
+            config,
+            vec![sink.clone()],
+            CancellationToken::new(),
+        );
+
+        let mut repository = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let todo = todo("Failing", Status::Todo);
+        repository.create(todo).await.unwrap();
+
+        received(&sink, 2).await;
+        time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(sink.received.lock().unwrap().len(), 2);
+        assert_eq!(outbox_count(&pool).await, 2);
+        assert_eq!(dead_count(&pool).await, 2);
+        stop(relay).await;
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn relay_stops_on_shutdown(pool: PgPool) {
+        let sink = Arc::new(RecordingSink::default());
+        let shutdown = CancellationToken::new();
+        let relay = start(
+            tenant_pool(&pool).await,
+            config(),
+            vec![sink.clone()],
+            shutdown.clone(),
+        );
+
+        let mut repository = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let todo = todo("Flushed", Status::Todo);
+        repository.create(todo).await.unwrap();
+        received(&sink, 1).await;
+
+        // the relay stops while waiting, long before the poll interval
+        shutdown.cancel();
+        time::timeout(Duration::from_secs(1), relay)
+            .await
+            .expect("Relay did not stop on shutdown")
+            .unwrap();
+        assert_eq!(outbox_count(&pool).await, 0);
+    }
+}
@@ -0,0 +1,52 @@
+use futures_util::future::{self, BoxFuture};
+use sqlx::PgPool;
+
+use super::{OutboxEvent, OutboxSink, SinkError};
+use crate::framework::storage::repositories::outbox::PgOutbox;
+
+/// Log every relayed event
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogSink;
+
+impl OutboxSink for LogSink {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn dispatch<'a>(&'a self, event: &'a OutboxEvent) -> BoxFuture<'a, Result<(), SinkError>> {
+        tracing::info!(
+            outbox.id = %event.id,
+            tenant.id = %event.tenant_id,
+            event.name = event.change.name(),
+            todo.id = %event.change.todo().id(),
+            "Outbox event"
+        );
+
+        Box::pin(future::ok(()))
+    }
+}
+
+/// Notify every instance of relayed events, which publish them to the
+/// subscribers of their [`TodoBroadcaster`](crate::framework::events::todo::TodoBroadcaster),
+/// see [`listener`](crate::framework::events::listener)
+pub struct BroadcastSink {
+    outbox: PgOutbox,
+}
+
+impl BroadcastSink {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            outbox: PgOutbox::new(pool),
+        }
+    }
+}
+
+impl OutboxSink for BroadcastSink {
+    fn name(&self) -> &'static str {
+        "broadcast"
+    }
+
+    fn dispatch<'a>(&'a self, event: &'a OutboxEvent) -> BoxFuture<'a, Result<(), SinkError>> {
+        Box::pin(async move { self.outbox.broadcast(event).await.map_err(SinkError::from) })
+    }
+}
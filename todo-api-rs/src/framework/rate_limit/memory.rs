@@ -0,0 +1,143 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+
+use super::{Decision, Quota, RateLimitStore, StoreError};
+
+/// Buckets kept at most, past which the least recently used one is forgotten
+/// for every new client
+const MAX_BUCKETS: usize = 100_000;
+
+/// Buckets of the clients of this instance of the server only, so every
+/// instance of a deployment has its own quota
+#[derive(Debug)]
+pub struct MemoryStore {
+    shared: Mutex<Buckets>,
+    max_buckets: usize,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    buckets: HashMap<String, Bucket>,
+    /// Keys of the buckets ordered by their last use, the least recently used
+    /// first
+    used: BTreeSet<(Instant, String)>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_BUCKETS)
+    }
+
+    /// Store keeping at most `max_buckets` buckets
+    fn with_capacity(max_buckets: usize) -> Self {
+        Self {
+            shared: Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                used: BTreeSet::new(),
+            }),
+            max_buckets,
+        }
+    }
+
+    fn take_now(&self, key: &str, quota: Quota) -> Decision {
+        let now = Instant::now();
+        let mut shared = self.shared.lock().unwrap_or_else(|err| err.into_inner());
+        let Buckets { buckets, used } = &mut *shared;
+
+        // buckets unused for the longest period are full, as if they were new
+        while let Some((used_at, _)) = used.first() {
+            if now.duration_since(*used_at) < Quota::MAX_PERIOD {
+                break;
+            }
+            if let Some((_, stale)) = used.pop_first() {
+                buckets.remove(&stale);
+            }
+        }
+
+        let (tokens, updated_at) = match buckets.get(key) {
+            Some(bucket) => {
+                used.remove(&(bucket.updated_at, key.to_owned()));
+                (bucket.tokens, bucket.updated_at)
+            }
+            None => {
+                if buckets.len() >= self.max_buckets {
+                    if let Some((_, oldest)) = used.pop_first() {
+                        buckets.remove(&oldest);
+                    }
+                }
+                (f64::from(quota.limit), now)
+            }
+        };
+        let (tokens, allowed) = quota.take(tokens, now.duration_since(updated_at));
+        buckets.insert(
+            key.to_owned(),
+            Bucket {
+                tokens,
+                updated_at: now,
+            },
+        );
+        used.insert((now, key.to_owned()));
+
+        Decision {
+            quota,
+            allowed,
+            tokens,
+        }
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimitStore for MemoryStore {
+    fn take<'a>(
+        &'a self,
+        key: &'a str,
+        quota: Quota,
+    ) -> BoxFuture<'a, Result<Decision, StoreError>> {
+        let decision = self.take_now(key, quota);
+        async move { Ok(decision) }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn least_recently_used_buckets_are_forgotten_past_the_capacity() {
+        let store = MemoryStore::with_capacity(2);
+        let quota = Quota {
+            limit: 1,
+            period: Duration::from_secs(60),
+        };
+
+        assert!(store.take_now("a", quota).allowed);
+        assert!(store.take_now("b", quota).allowed);
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(!store.take_now("a", quota).allowed);
+
+        // `b` is the least recently used bucket, so it is full again
+        assert!(store.take_now("c", quota).allowed);
+        let shared = store.shared.lock().unwrap();
+        assert_eq!((shared.buckets.len(), shared.used.len()), (2, 2));
+        drop(shared);
+        assert!(!store.take_now("a", quota).allowed);
+        assert!(store.take_now("b", quota).allowed);
+    }
+}
@@ -0,0 +1,363 @@
+pub mod memory;
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{ConnectInfo, MatchedPath, Request, State};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::Response;
+use futures_util::future::BoxFuture;
+
+use crate::adapters::presenters::json::error::Content;
+use crate::framework::config::RateLimitConfig;
+use crate::framework::rest_api::negotiation::{error_format, rejection};
+use crate::framework::rest_api::tenant::Tenant;
+
+const LIMIT_HEADER: HeaderName = HeaderName::from_static("ratelimit-limit");
+const REMAINING_HEADER: HeaderName = HeaderName::from_static("ratelimit-remaining");
+const RESET_HEADER: HeaderName = HeaderName::from_static("ratelimit-reset");
+const POLICY_HEADER: HeaderName = HeaderName::from_static("ratelimit-policy");
+
+/// Requests a client may make to a route in a period. Its bucket holds up to
+/// `limit` tokens and refills at `limit` per `period`, so clients may burst
+/// up to the whole quota
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quota {
+    pub limit: u32,
+    pub period: Duration,
+}
+
+impl Quota {
+    /// Longest period of a quota. Buckets unused for that long are full, so
+    /// stores may forget them
+    pub const MAX_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+    /// Tokens added to a bucket every second
+    pub fn rate(&self) -> f64 {
+        f64::from(self.limit) / self.period.as_secs_f64()
+    }
+
+    /// Take a token from a bucket which had `tokens` `elapsed` ago, returning
+    /// the tokens left and whether one was taken
+    pub fn take(&self, tokens: f64, elapsed: Duration) -> (f64, bool) {
+        let refilled = f64::min(
+            f64::from(self.limit),
+            tokens + elapsed.as_secs_f64() * self.rate(),
+        );
+        match refilled >= 1.0 {
+            true => (refilled - 1.0, true),
+            false => (refilled, false),
+        }
+    }
+}
+
+impl fmt::Display for Quota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{};w={}", self.limit, self.period.as_secs())
+    }
+}
+
+/// Quota of the requests to a route, such as `POST /todos`, none when the
+/// route is unlimited
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouteQuota {
+    pub method: Method,
+    pub route: String,
+    pub quota: Option<Quota>,
+}
+
+/// Outcome of taking a token from the bucket of a client
+#[derive(Clone, Debug, PartialEq)]
+pub struct Decision {
+    pub quota: Quota,
+    pub allowed: bool,
+    /// Tokens left in the bucket
+    pub tokens: f64,
+}
+
+impl Decision {
+    /// Time until the bucket has a token again
+    pub fn retry_after(&self) -> Duration {
+        Duration::from_secs_f64(f64::max(0.0, 1.0 - self.tokens) / self.quota.rate())
+    }
+
+    /// Time until the bucket is full again
+    pub fn reset(&self) -> Duration {
+        Duration::from_secs_f64((f64::from(self.quota.limit) - self.tokens) / self.quota.rate())
+    }
+
+    /// Error of the requests limited by the decision
+    pub fn rejection(&self) -> Content {
+        let retry_after = seconds(self.retry_after());
+        let message = format!("Too many requests, retry in {retry_after} seconds");
+        Content::new("RateLimited", message)
+    }
+
+    /// Set the `RateLimit-*` headers of the decision, and `Retry-After` when
+    /// the request was limited
+    fn write_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(LIMIT_HEADER, HeaderValue::from(self.quota.limit));
+        headers.insert(REMAINING_HEADER, HeaderValue::from(self.tokens as u32));
+        headers.insert(RESET_HEADER, HeaderValue::from(seconds(self.reset())));
+        if let Ok(policy) = HeaderValue::from_str(&self.quota.to_string()) {
+            headers.insert(POLICY_HEADER, policy);
+        }
+        if !self.allowed {
+            headers.insert(
+                axum::http::header::RETRY_AFTER,
+                HeaderValue::from(seconds(self.retry_after())),
+            );
+        }
+    }
+}
+
+pub type StoreError = Box<dyn error::Error + Send + Sync>;
+
+/// Storage of the token buckets of clients
+pub trait RateLimitStore: Send + Sync {
+    /// Take a token from the bucket of `key`, which is full when it is new
+    fn take<'a>(
+        &'a self,
+        key: &'a str,
+        quota: Quota,
+    ) -> BoxFuture<'a, Result<Decision, StoreError>>;
+}
+
+/// Quotas of routes and the buckets of clients, shared by the routes it
+/// limits
+#[derive(Clone)]
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    default_quota: Option<Quota>,
+    routes: Arc<HashMap<(Method, String), Option<Quota>>>,
+}
+
+impl RateLimiter {
+    pub fn new(store: Arc<dyn RateLimitStore>, config: &RateLimitConfig) -> Self {
+        let routes = config
+            .routes
+            .iter()
+            .map(|route| ((route.method.clone(), route.route.clone()), route.quota))
+            .collect();
+
+        Self {
+            store,
+            default_quota: config.default_quota,
+            routes: Arc::new(routes),
+        }
+    }
+
+    fn quota(&self, method: &Method, route: &str) -> Option<Quota> {
+        self.routes
+            .get(&(method.clone(), route.to_owned()))
+            .copied()
+            .unwrap_or(self.default_quota)
+    }
+
+    /// Take a token from the bucket of `client` for `method` `route`, none
+    /// when the route is unlimited or when the store failed, in which case the
+    /// request is let through rather than failing them all
+    pub async fn take(&self, client: &str, method: &Method, route: &str) -> Option<Decision> {
+        let quota = self.quota(method, route)?;
+        let key = format!("{client} {method} {route}");
+        match self.store.take(&key, quota).await {
+            Ok(decision) => {
+                if !decision.allowed {
+                    tracing::info!(key, "Rate limited request");
+                }
+                Some(decision)
+            }
+            Err(err) => {
+                tracing::error!("Failed checking rate limit, allowing request: {err}");
+                None
+            }
+        }
+    }
+}
+
+/// Middleware limiting the requests of every client to every route with a
+/// token bucket. Requests over the quota are rejected with a `RateLimited`
+/// error, and when the store fails requests are let through rather than
+/// failing them all. Sockets find the limiter in the extensions of their
+/// request, to take a token for each of their commands
+pub async fn limit(State(limiter): State<RateLimiter>, mut req: Request, next: Next) -> Response {
+    req.extensions_mut().insert(limiter.clone());
+    let Some(route) = req.extensions().get::<MatchedPath>() else {
+        return next.run(req).await;
+    };
+    let client = client_key(&req);
+    let Some(decision) = limiter.take(&client, req.method(), route.as_str()).await else {
+        return next.run(req).await;
+    };
+
+    let mut res = match decision.allowed {
+        true => next.run(req).await,
+        false => rejection(error_format(req.headers(), req.uri()).error(429, decision.rejection())),
+    };
+    decision.write_headers(res.headers_mut());
+    res
+}
+
+/// Identity of the client of a request: its tenant once [`authenticate`]
+/// verified it, or else its address. Headers the client sets as it likes are
+/// never used, as rotating them would give it a new quota every time
+///
+/// [`authenticate`]: crate::framework::rest_api::tenant::authenticate
+fn client_key(req: &Request) -> String {
+    if let Some(tenant) = req.extensions().get::<Tenant>() {
+        return tenant_key(tenant);
+    }
+
+    match req.extensions().get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => format!("ip:{}", addr.ip()),
+        None => String::from("ip:unknown"),
+    }
+}
+
+/// Identity of a client verified as `tenant`
+pub fn tenant_key(tenant: &Tenant) -> String {
+    format!("tenant:{}", tenant.id())
+}
+
+/// Whole seconds of a duration, rounded up so clients never retry too early
+fn seconds(duration: Duration) -> u64 {
+    duration.as_secs_f64().ceil() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::{get, post};
+    use axum::{middleware, Router};
+    use tower::ServiceExt;
+
+    use super::memory::MemoryStore;
+    use super::*;
+    use crate::domain::types::Id;
+    use crate::framework::config::RateLimitBackend;
+    use crate::framework::rest_api::tenant::{self, TENANT_HEADER};
+
+    const PER_MINUTE: Duration = Duration::from_secs(60);
+
+    fn app() -> Router {
+        let config = RateLimitConfig {
+            backend: RateLimitBackend::Memory,
+            default_quota: Some(Quota {
+                limit: 2,
+                period: PER_MINUTE,
+            }),
+            routes: vec![RouteQuota {
+                method: Method::GET,
+                route: String::from("/todos"),
+                quota: None,
+            }],
+        };
+        let limiter = RateLimiter::new(Arc::new(MemoryStore::new()), &config);
+
+        Router::new()
+            .route("/todos", get(|| async {}).post(|| async {}))
+            .route("/webhooks", post(|| async {}))
+            .layer(middleware::from_fn_with_state(limiter, limit))
+            .layer(middleware::from_fn_with_state(
+                tenant::tests::trusting_header(),
+                tenant::authenticate,
+            ))
+    }
+
+    fn request(method: Method, uri: &str, tenant_id: Id) -> Request {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(TENANT_HEADER, tenant_id.to_string())
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[test]
+    fn buckets_refill_up_to_their_limit() {
+        let quota = Quota {
+            limit: 10,
+            period: PER_MINUTE,
+        };
+        assert_eq!(quota.take(0.0, Duration::from_secs(3)), (0.5, false));
+        assert_eq!(quota.take(0.5, Duration::from_secs(3)), (0.0, true));
+        assert_eq!(quota.take(4.0, Duration::from_secs(3600)), (9.0, true));
+    }
+
+    #[tokio::test]
+    async fn clients_over_their_quota_are_limited() {
+        let app = app();
+        let (a, b) = (Id::new(), Id::new());
+        for remaining in ["1", "0"] {
+            let res = app
+                .clone()
+                .oneshot(request(Method::POST, "/todos", a))
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(res.headers()["ratelimit-limit"], "2");
+            assert_eq!(res.headers()["ratelimit-remaining"], remaining);
+            assert_eq!(res.headers()["ratelimit-policy"], "2;w=60");
+        }
+
+        let res = app
+            .clone()
+            .oneshot(request(Method::POST, "/todos", a))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers()["retry-after"], "30");
+        assert_eq!(res.headers()["ratelimit-reset"], "60");
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "RateLimited");
+
+        // other clients, other routes and unlimited routes have their own quota
+        for (method, uri, tenant_id) in [
+            (Method::POST, "/todos", b),
+            (Method::POST, "/webhooks", a),
+            (Method::GET, "/todos", a),
+        ] {
+            let res = app
+                .clone()
+                .oneshot(request(method, uri, tenant_id))
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK, "{uri} of {tenant_id}");
+        }
+    }
+
+    #[tokio::test]
+    async fn rotating_api_keys_does_not_reset_the_quota() {
+        let app = app();
+        let addr = SocketAddr::from(([203, 0, 113, 7], 40000));
+        let mut statuses = Vec::new();
+        for idx in 0..3 {
+            let mut req = Request::builder()
+                .method(Method::POST)
+                .uri("/todos")
+                .header("x-api-key", format!("key-{idx}"))
+                .body(Body::empty())
+                .unwrap();
+            req.extensions_mut().insert(ConnectInfo(addr));
+            statuses.push(app.clone().oneshot(req).await.unwrap().status());
+        }
+
+        assert_eq!(
+            statuses,
+            [
+                StatusCode::OK,
+                StatusCode::OK,
+                StatusCode::TOO_MANY_REQUESTS
+            ]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{header, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::framework::config::CacheConfig;
+use crate::framework::rest_api::tenant::TENANT_HEADER;
+
+/// Request headers every response varies by, as todos are encoded in the
+/// accepted format and belong to a tenant. The compression layer adds
+/// `Accept-Encoding` itself
+const VARY: [&str; 3] = ["accept", "authorization", TENANT_HEADER];
+
+/// Directives of a `Cache-Control` header, such as `private` and `max-age=60`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachePolicy {
+    pub directives: Vec<String>,
+}
+
+impl fmt::Display for CachePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.directives.join(", "))
+    }
+}
+
+/// Cache policy of the successful responses of a route, such as
+/// `GET /todos/:id`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteCachePolicy {
+    pub method: Method,
+    pub route: String,
+    pub policy: CachePolicy,
+}
+
+/// `Cache-Control` headers of the responses of every route
+#[derive(Clone, Debug)]
+pub struct Caching {
+    default: HeaderValue,
+    routes: Arc<HashMap<(Method, String), HeaderValue>>,
+}
+
+impl Caching {
+    pub fn new(config: &CacheConfig) -> Self {
+        let routes = config
+            .routes
+            .iter()
+            .filter_map(|route| {
+                let value = header_value(&route.policy)?;
+                Some(((route.method.clone(), route.route.clone()), value))
+            })
+            .collect();
+
+        Self {
+            default: header_value(&config.default).unwrap_or(HeaderValue::from_static("no-store")),
+            routes: Arc::new(routes),
+        }
+    }
+}
+
+/// Middleware setting the `Cache-Control` header of responses without one
+/// and adding the headers responses vary by. Routes only use their own
+/// policy for successful responses, so errors are never cached longer than
+/// the default allows
+pub async fn cache_headers(State(caching): State<Caching>, req: Request, next: Next) -> Response {
+    let policy = req
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|route| {
+            let key = (req.method().clone(), route.as_str().to_owned());
+            caching.routes.get(&key)
+        })
+        .cloned();
+
+    let mut res = next.run(req).await;
+    let policy = match policy {
+        Some(policy) if res.status().is_success() => policy,
+        _ => caching.default,
+    };
+
+    let headers = res.headers_mut();
+    if !headers.contains_key(header::CACHE_CONTROL) {
+        headers.insert(header::CACHE_CONTROL, policy);
+    }
+    for name in VARY {
+        headers.append(header::VARY, HeaderValue::from_static(name));
+    }
+    res
+}
+
+fn header_value(policy: &CachePolicy) -> Option<HeaderValue> {
+    HeaderValue::from_str(&policy.to_string()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::{middleware, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn policy(directives: &[&str]) -> CachePolicy {
+        CachePolicy {
+            directives: directives.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn app() -> Router {
+        let config = CacheConfig {
+            default: policy(&["private", "no-cache"]),
+            routes: vec![RouteCachePolicy {
+                method: Method::GET,
+                route: String::from("/todos/:id"),
+                policy: policy(&["private", "max-age=60"]),
+            }],
+        };
+
+        Router::new()
+            .route("/todos", get(|| async {}))
+            .route(
+                "/todos/:id",
+                get(
+                    |axum::extract::Path(id): axum::extract::Path<u32>| async move {
+                        match id {
+                            1 => StatusCode::OK,
+                            _ => StatusCode::NOT_FOUND,
+                        }
+                    },
+                ),
+            )
+            .route(
+                "/todos/events",
+                get(|| async { ([(header::CACHE_CONTROL, "no-store")], "") }),
+            )
+            .layer(middleware::from_fn_with_state(
+                Caching::new(&config),
+                cache_headers,
+            ))
+    }
+
+    async fn cache_control(uri: &str) -> String {
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let res = app().oneshot(req).await.unwrap();
+        let vary = res
+            .headers()
+            .get_all(header::VARY)
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(vary, ["accept", "authorization", "x-tenant-id"]);
+
+        res.headers()[header::CACHE_CONTROL]
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[tokio::test]
+    async fn routes_have_their_own_policy() {
+        assert_eq!(cache_control("/todos").await, "private, no-cache");
+        assert_eq!(cache_control("/todos/1").await, "private, max-age=60");
+        // errors use the default, and responses with their own header keep it
+        assert_eq!(cache_control("/todos/2").await, "private, no-cache");
+        assert_eq!(cache_control("/todos/events").await, "no-store");
+    }
+}
@@ -0,0 +1,198 @@
+use axum::http::HeaderValue;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer, ExposeHeaders};
+
+use crate::framework::config::CorsConfig;
+
+/// Values of a CORS setting, either every value or only the listed ones
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Allowed<T> {
+    Any,
+    List(Vec<T>),
+}
+
+impl<T> Allowed<T> {
+    pub fn is_any(&self) -> bool {
+        matches!(self, Self::Any)
+    }
+}
+
+/// Origin allowed to call the API, either exactly such as
+/// `https://todo.example.com` or every subdomain of a domain such as
+/// `https://*.example.com`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OriginPattern {
+    Exact(HeaderValue),
+    Subdomains {
+        /// Scheme with its separator, such as `https://`
+        scheme: String,
+        /// Domain with its leading dot and the port when there is one, such
+        /// as `.example.com:8443`
+        suffix: String,
+    },
+}
+
+impl OriginPattern {
+    pub fn matches(&self, origin: &HeaderValue) -> bool {
+        match self {
+            Self::Exact(allowed) => allowed == origin,
+            Self::Subdomains { scheme, suffix } => origin
+                .to_str()
+                .ok()
+                .and_then(|origin| origin.strip_prefix(scheme.as_str()))
+                .and_then(|host| host.strip_suffix(suffix.as_str()))
+                .is_some_and(|subdomain| {
+                    !subdomain.is_empty()
+                        && subdomain
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                }),
+        }
+    }
+}
+
+/// Create the CORS layer of the policy, which must have been validated so
+/// credentials are never allowed along with any origin, method or header
+pub fn create_layer(config: &CorsConfig) -> CorsLayer {
+    let origins = match &config.origins {
+        Allowed::Any => AllowOrigin::any(),
+        Allowed::List(patterns) => {
+            let patterns = patterns.clone();
+            AllowOrigin::predicate(move |origin, _| {
+                patterns.iter().any(|pattern| pattern.matches(origin))
+            })
+        }
+    };
+    let methods = match &config.methods {
+        Allowed::Any => AllowMethods::any(),
+        Allowed::List(methods) => AllowMethods::list(methods.iter().cloned()),
+    };
+    let headers = match &config.headers {
+        Allowed::Any => AllowHeaders::any(),
+        Allowed::List(headers) => AllowHeaders::list(headers.iter().cloned()),
+    };
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(headers)
+        .expose_headers(ExposeHeaders::list(config.expose_headers.iter().cloned()))
+        .max_age(config.max_age)
+        .allow_credentials(config.allow_credentials)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::body::Body;
+    use axum::extract::Request;
+    use axum::http::{header, HeaderName, Method, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn header_names(names: &[&'static str]) -> Vec<HeaderName> {
+        names
+            .iter()
+            .map(|name| HeaderName::from_static(name))
+            .collect()
+    }
+
+    fn subdomains() -> OriginPattern {
+        OriginPattern::Subdomains {
+            scheme: String::from("https://"),
+            suffix: String::from(".example.com"),
+        }
+    }
+
+    #[test]
+    fn subdomains_of_the_domain_match() {
+        let pattern = subdomains();
+        for origin in ["https://todo.example.com", "https://eu.todo.example.com"] {
+            assert!(
+                pattern.matches(&HeaderValue::from_static(origin)),
+                "{origin}"
+            );
+        }
+        for origin in [
+            "https://example.com",
+            "http://todo.example.com",
+            "https://todo.example.com.evil.com",
+            "https://evil.com/.example.com",
+            "https://todo.example.com:8443",
+        ] {
+            assert!(
+                !pattern.matches(&HeaderValue::from_static(origin)),
+                "{origin}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn only_allowed_origins_are_echoed() {
+        let config = CorsConfig {
+            origins: Allowed::List(vec![
+                OriginPattern::Exact(HeaderValue::from_static("http://localhost:3000")),
+                subdomains(),
+            ]),
+            methods: Allowed::List(vec![Method::GET, Method::POST, Method::PUT, Method::DELETE]),
+            headers: Allowed::List(header_names(&["content-type"])),
+            expose_headers: header_names(&["etag", "location"]),
+            max_age: Duration::from_secs(600),
+            allow_credentials: true,
+        };
+        let app = Router::new()
+            .route("/todos", get(|| async {}))
+            .layer(create_layer(&config));
+
+        let preflight = |origin: &'static str| {
+            Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/todos")
+                .header(header::ORIGIN, origin)
+                .header(header::ACCESS_CONTROL_REQUEST_METHOD, "PUT")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let res = app
+            .clone()
+            .oneshot(preflight("https://todo.example.com"))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let headers = res.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://todo.example.com"
+        );
+        assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+        assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_METHODS],
+            "GET,POST,PUT,DELETE"
+        );
+
+        let res = app
+            .clone()
+            .oneshot(preflight("https://evil.com"))
+            .await
+            .unwrap();
+        assert!(!res
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let req = Request::builder()
+            .uri("/todos")
+            .header(header::ORIGIN, "http://localhost:3000")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.oneshot(req).await.unwrap();
+        assert_eq!(
+            res.headers()[header::ACCESS_CONTROL_EXPOSE_HEADERS],
+            "etag,location"
+        );
+    }
+}
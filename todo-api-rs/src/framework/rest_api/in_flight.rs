@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use axum::body::{Body, Bytes};
+use axum::extract::{Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::Response;
+use http_body::{Frame, SizeHint};
+
+/// Requests the server is handling, until their response body is fully sent,
+/// so a shutdown can tell which ones it interrupts
+#[derive(Clone, Debug, Default)]
+pub struct InFlight {
+    shared: Arc<Mutex<Requests>>,
+}
+
+#[derive(Debug, Default)]
+struct Requests {
+    next_id: u64,
+    running: HashMap<u64, RunningRequest>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RunningRequest {
+    pub method: Method,
+    pub path: String,
+    pub started_at: Instant,
+}
+
+impl RunningRequest {
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+}
+
+impl InFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests still running, the oldest first
+    pub fn running(&self) -> Vec<RunningRequest> {
+        let requests = self.shared.lock().unwrap_or_else(|err| err.into_inner());
+        let mut running = requests.running.values().cloned().collect::<Vec<_>>();
+        running.sort_by_key(|request| request.started_at);
+        running
+    }
+
+    fn begin(&self, req: &Request) -> Guard {
+        let mut requests = self.shared.lock().unwrap_or_else(|err| err.into_inner());
+        let id = requests.next_id;
+        requests.next_id += 1;
+        requests.running.insert(
+            id,
+            RunningRequest {
+                method: req.method().clone(),
+                path: req.uri().path().to_owned(),
+                started_at: Instant::now(),
+            },
+        );
+
+        Guard {
+            in_flight: self.clone(),
+            id,
+        }
+    }
+}
+
+/// Removes its request from the running ones when dropped, whether the
+/// request completed or was cancelled
+#[derive(Debug)]
+struct Guard {
+    in_flight: InFlight,
+    id: u64,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let mut requests = self
+            .in_flight
+            .shared
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        requests.running.remove(&self.id);
+    }
+}
+
+/// Middleware tracking the request until its response body is dropped, which
+/// covers streamed bodies such as exports and event streams
+pub async fn track(State(in_flight): State<InFlight>, req: Request, next: Next) -> Response {
+    let guard = in_flight.begin(&req);
+    next.run(req).await.map(|body| {
+        Body::new(TrackedBody {
+            body,
+            _guard: guard,
+        })
+    })
+}
+
+/// Body that holds the guard of its request
+struct TrackedBody {
+    body: Body,
+    _guard: Guard,
+}
+
+impl http_body::Body for TrackedBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Pin::new(&mut self.body).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::middleware;
+    use axum::routing::get;
+    use axum::Router;
+    use futures_util::StreamExt;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_run_until_their_body_is_sent() {
+        let in_flight = InFlight::new();
+        let app = Router::new()
+            .route("/todos.csv", get(|| async { "id,title\r\n" }))
+            .layer(middleware::from_fn_with_state(in_flight.clone(), track));
+
+        let req = Request::builder()
+            .uri("/todos.csv?title=milk")
+            .body(Body::empty())
+            .unwrap();
+        let res = app.oneshot(req).await.unwrap();
+
+        let running = in_flight.running();
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].method, Method::GET);
+        assert_eq!(running[0].path, "/todos.csv");
+        // the length of bodies is kept
+        assert_eq!(http_body::Body::size_hint(res.body()).exact(), Some(10));
+
+        let chunks = res.into_body().into_data_stream().count().await;
+        assert_eq!(chunks, 1);
+        assert!(in_flight.running().is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{header, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::adapters::presenters::json::error::Content;
+use crate::framework::rest_api::negotiation::{error_format, rejection};
+
+/// Seconds shed clients are told to wait before retrying
+const RETRY_AFTER: u32 = 1;
+
+/// Routes reading a whole archive or file before responding, which get the
+/// bulk timeout rather than the one of other requests
+const BULK_ROUTES: [&str; 4] = [
+    "/backup/restore",
+    "/todos/import/csv",
+    "/todos/import/ics",
+    "/todos/import/txt",
+];
+
+/// Requests the server handles at once, past which new ones are shed rather
+/// than queued behind them
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimit {
+    permits: Arc<Semaphore>,
+}
+
+impl ConcurrencyLimit {
+    pub fn new(max_requests: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_requests)),
+        }
+    }
+
+    /// Count a request until the permit is dropped, none while the limit is
+    /// reached
+    pub fn try_acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.permits.try_acquire().ok()
+    }
+}
+
+/// Time a request has to produce its response, longer for restores and
+/// imports
+#[derive(Clone, Copy, Debug)]
+pub struct RequestTimeout {
+    request: Duration,
+    bulk: Duration,
+}
+
+impl RequestTimeout {
+    pub fn new(request: Duration, bulk: Duration) -> Self {
+        Self { request, bulk }
+    }
+
+    /// Time of requests other than restores and imports
+    pub fn request(&self) -> Duration {
+        self.request
+    }
+
+    fn of(&self, req: &Request) -> Duration {
+        let bulk = req
+            .extensions()
+            .get::<MatchedPath>()
+            .is_some_and(|route| BULK_ROUTES.contains(&route.as_str()));
+        match bulk {
+            true => self.bulk,
+            false => self.request,
+        }
+    }
+}
+
+/// Error of the requests shed while the server is overloaded
+pub fn overloaded() -> Content {
+    Content::new("Overloaded", "Server is overloaded, retry later")
+}
+
+/// Error of the requests that took longer than their timeout
+pub fn timed_out() -> Content {
+    Content::new("Timeout", "Request took too long to complete")
+}
+
+/// Middleware rejecting requests with an `Overloaded` error while the limit
+/// of concurrent requests is reached. A request counts until its response
+/// starts, so streamed bodies such as event streams do not hold the limit.
+/// Sockets find the limit in the extensions of their request, to count each
+/// of their commands
+pub async fn shed(State(limit): State<ConcurrencyLimit>, mut req: Request, next: Next) -> Response {
+    let Some(_permit) = limit.try_acquire() else {
+        tracing::warn!("Shed request, too many requests in flight");
+
+        let mut res = rejection(error_format(req.headers(), req.uri()).error(503, overloaded()));
+        res.headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER));
+        return res;
+    };
+
+    req.extensions_mut().insert(limit.clone());
+    next.run(req).await
+}
+
+/// Middleware rejecting requests with a `Timeout` error when their response
+/// does not start in time, dropping the handler so its queries are cancelled.
+/// Sockets find the timeouts in the extensions of their request, to time each
+/// of their commands
+pub async fn timeout(
+    State(timeouts): State<RequestTimeout>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let duration = timeouts.of(&req);
+    req.extensions_mut().insert(timeouts);
+    let errors = error_format(req.headers(), req.uri());
+    match tokio::time::timeout(duration, next.run(req)).await {
+        Ok(res) => res,
+        Err(_) => {
+            tracing::warn!("Request timed out after {duration:?}");
+
+            rejection(errors.error(504, timed_out()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::{get, post};
+    use axum::{middleware, Router};
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn code(res: Response) -> String {
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        body["code"].as_str().unwrap().to_owned()
+    }
+
+    fn request() -> Request {
+        Request::builder()
+            .uri("/todos")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn slow_requests_time_out() {
+        let app = Router::new()
+            .route("/todos", get(|| tokio::time::sleep(Duration::from_secs(5))))
+            .layer(middleware::from_fn_with_state(
+                RequestTimeout::new(Duration::from_millis(10), Duration::from_secs(60)),
+                timeout,
+            ));
+
+        let res = app.oneshot(request()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(code(res).await, "Timeout");
+    }
+
+    #[tokio::test]
+    async fn imports_and_restores_get_the_bulk_timeout() {
+        let slow = || tokio::time::sleep(Duration::from_millis(50));
+        let app = Router::new()
+            .route("/todos/import/csv", post(slow))
+            .route("/backup/restore", post(slow))
+            .route("/todos", post(slow))
+            .layer(middleware::from_fn_with_state(
+                RequestTimeout::new(Duration::from_millis(10), Duration::from_secs(60)),
+                timeout,
+            ));
+
+        for (uri, status) in [
+            ("/todos/import/csv", StatusCode::OK),
+            ("/backup/restore", StatusCode::OK),
+            ("/todos", StatusCode::GATEWAY_TIMEOUT),
+        ] {
+            let req = Request::post(uri).body(Body::empty()).unwrap();
+            let res = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(res.status(), status, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn requests_over_the_limit_are_shed() {
+        let release = Arc::new(Notify::new());
+        let limit = ConcurrencyLimit::new(1);
+        let app = Router::new()
+            .route(
+                "/todos",
+                get({
+                    let release = release.clone();
+                    || async move { release.notified().await }
+                }),
+            )
+            .layer(middleware::from_fn_with_state(limit.clone(), shed));
+
+        let running = tokio::spawn(app.clone().oneshot(request()));
+        tokio::task::yield_now().await;
+
+        let res = app.oneshot(request()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers()["retry-after"], "1");
+        assert_eq!(code(res).await, "Overloaded");
+
+        release.notify_one();
+        assert_eq!(running.await.unwrap().unwrap().status(), StatusCode::OK);
+        assert_eq!(limit.permits.available_permits(), 1);
+    }
+}
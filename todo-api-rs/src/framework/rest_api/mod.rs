@@ -1 +1,10 @@
+pub mod caching;
+pub mod cors;
+pub mod in_flight;
+pub mod load;
+pub mod negotiation;
+pub mod openapi;
+pub mod redaction;
+pub mod request_id;
 pub mod routes;
+pub mod tenant;
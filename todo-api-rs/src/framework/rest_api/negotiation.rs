@@ -0,0 +1,398 @@
+use std::convert::Infallible;
+use std::error;
+
+use axum::async_trait;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, FromRequestParts, Request};
+use axum::http::request::Parts;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::adapters::presenters::json::error::{
+    Content, ErrorFormat, JsonError, JSON_CONTENT_TYPE, PROBLEM_CONTENT_TYPE,
+};
+
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+pub const CBOR_CONTENT_TYPE: &str = "application/cbor";
+
+/// Wire format of request and response bodies
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl Format {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => JSON_CONTENT_TYPE,
+            Self::MessagePack => MSGPACK_CONTENT_TYPE,
+            Self::Cbor => CBOR_CONTENT_TYPE,
+        }
+    }
+
+    /// Find the format of a media type without parameters, such as `application/cbor`
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type.trim().to_ascii_lowercase().as_str() {
+            JSON_CONTENT_TYPE | PROBLEM_CONTENT_TYPE => Some(Self::Json),
+            MSGPACK_CONTENT_TYPE | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(Self::MessagePack)
+            }
+            CBOR_CONTENT_TYPE => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, Box<dyn error::Error>> {
+        match self {
+            Self::Json => Ok(serde_json::to_vec(value)?),
+            Self::MessagePack => Ok(rmp_serde::to_vec_named(value)?),
+            Self::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes)?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, Box<dyn error::Error>> {
+        match self {
+            Self::Json => Ok(serde_json::from_slice(bytes)?),
+            Self::MessagePack => Ok(rmp_serde::from_slice(bytes)?),
+            Self::Cbor => Ok(ciborium::from_reader(bytes)?),
+        }
+    }
+}
+
+/// Response body format negotiated through the `Accept` header of the request,
+/// rejecting with `406 Not Acceptable` when no supported format is accepted
+#[derive(Clone, Copy, Debug)]
+pub struct Accepted(pub Format);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Accepted {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        accepted_format(&parts.headers).map(Self).ok_or_else(|| {
+            let message = format!(
+                "Accept header must allow one of {}, {} or {}",
+                Format::Json.content_type(),
+                Format::MessagePack.content_type(),
+                Format::Cbor.content_type(),
+            );
+            let content = Content::new("NotAcceptable", message);
+            let err = error_format(&parts.headers, &parts.uri).error(406, content);
+            rejection(err)
+        })
+    }
+}
+
+/// Request body decoded according to its `Content-Type` header, rejecting with
+/// `415 Unsupported Media Type` when the format is not supported
+#[derive(Clone, Debug)]
+pub struct Decoded<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for Decoded<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let errors = error_format(req.headers(), req.uri());
+        let format = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .and_then(Format::from_media_type);
+
+        let Some(format) = format else {
+            let message = format!(
+                "Content-Type header must be one of {}, {} or {}",
+                Format::Json.content_type(),
+                Format::MessagePack.content_type(),
+                Format::Cbor.content_type(),
+            );
+            let content = Content::new("UnsupportedMediaType", message);
+            return Err(rejection(errors.error(415, content)));
+        };
+
+        let bytes = Bytes::from_request(req, state).await.map_err(|err| {
+            match err.status() {
+                StatusCode::PAYLOAD_TOO_LARGE => {
+                    let content = Content::new("PayloadTooLarge", "Request body is too large");
+                    rejection(errors.error(413, content))
+                }
+                _ => err.into_response(),
+            }
+        })?;
+
+        format.decode(&bytes).map(Self).map_err(|err| {
+            let content = Content::new("InvalidBody", format!("Invalid request body: {err}"));
+            rejection(errors.error(400, content))
+        })
+    }
+}
+
+/// Serializable value encoded with the negotiated [`Format`] when turned into a response
+#[derive(Clone, Debug)]
+pub struct Encoded<T> {
+    format: Format,
+    content_type: &'static str,
+    value: T,
+}
+
+impl<T> Encoded<T> {
+    pub fn new(format: Format, value: T) -> Self {
+        Self {
+            format,
+            content_type: format.content_type(),
+            value,
+        }
+    }
+}
+
+impl Encoded<Value> {
+    /// Encode the content of `err`, keeping its media type when encoded as JSON
+    pub fn error(format: Format, err: JsonError) -> Self {
+        let content_type = match format {
+            Format::Json => err.content_type(),
+            _ => format.content_type(),
+        };
+
+        Self {
+            format,
+            content_type,
+            value: err.content,
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Encoded<T> {
+    fn into_response(self) -> Response {
+        match self.format.encode(&self.value) {
+            Ok(bytes) => {
+                let content_type = HeaderValue::from_static(self.content_type);
+                ([(header::CONTENT_TYPE, content_type)], bytes).into_response()
+            }
+            Err(err) => {
+                tracing::error!("Failed encoding response body: {err}");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+/// Error body format negotiated through the `Accept` header of the request
+#[derive(Clone, Debug)]
+pub struct AcceptedErrorFormat(pub ErrorFormat);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AcceptedErrorFormat {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(error_format(&parts.headers, &parts.uri)))
+    }
+}
+
+/// Problem details are only used when explicitly accepted by the client with
+/// a quality at least as high as the one of `application/json`, otherwise
+/// errors keep the legacy `{ code, message }` shape
+pub fn error_format(headers: &HeaderMap, uri: &Uri) -> ErrorFormat {
+    let mut problem_quality = 0.0;
+    let mut json_quality = 0.0;
+    for (media_type, quality) in media_ranges(headers) {
+        if media_type.eq_ignore_ascii_case(PROBLEM_CONTENT_TYPE) {
+            problem_quality = f32::max(problem_quality, quality);
+        } else if media_type.eq_ignore_ascii_case(JSON_CONTENT_TYPE) {
+            json_quality = f32::max(json_quality, quality);
+        }
+    }
+
+    if problem_quality > 0.0 && problem_quality >= json_quality {
+        ErrorFormat::Problem {
+            instance: uri.path().to_string(),
+        }
+    } else {
+        ErrorFormat::Content
+    }
+}
+
+/// Pick the supported [`Format`] with the highest quality in the `Accept` header.
+/// Missing header and wildcards default to JSON
+fn accepted_format(headers: &HeaderMap) -> Option<Format> {
+    let mut ranges = media_ranges(headers).peekable();
+    if ranges.peek().is_none() {
+        return Some(Format::Json);
+    }
+
+    let mut accepted: Option<(Format, f32)> = None;
+    for (media_type, quality) in ranges {
+        let format = match media_type {
+            "*/*" | "application/*" => Some(Format::Json),
+            _ => Format::from_media_type(media_type),
+        };
+
+        match (format, accepted) {
+            (Some(_), _) if quality <= 0.0 => {}
+            (Some(format), None) => accepted = Some((format, quality)),
+            (Some(format), Some((_, best))) if quality > best => accepted = Some((format, quality)),
+            _ => {}
+        }
+    }
+
+    accepted.map(|(format, _)| format)
+}
+
+/// Media types of the `Accept` header with their quality, which defaults to 1
+fn media_ranges(headers: &HeaderMap) -> impl Iterator<Item = (&str, f32)> {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|range| {
+            let mut params = range.split(';');
+            let media_type = params.next()?.trim();
+            let quality = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            Some((media_type, quality)).filter(|(media_type, _)| !media_type.is_empty())
+        })
+}
+
+/// Turn an error of a request extractor into a JSON response
+pub fn rejection(err: JsonError) -> Response {
+    let status = StatusCode::from_u16(err.status()).unwrap_or(StatusCode::BAD_REQUEST);
+    let content_type = [(header::CONTENT_TYPE, err.content_type())];
+    (status, content_type, Json(err.content)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn legacy_format_is_default() {
+        let uri = Uri::from_static("/todos");
+        assert_eq!(error_format(&HeaderMap::new(), &uri), ErrorFormat::Content);
+
+        let headers = accept("application/json");
+        assert_eq!(error_format(&headers, &uri), ErrorFormat::Content);
+    }
+
+    #[test]
+    fn problem_format_is_negotiated() {
+        let uri = Uri::from_static("/todos/1?page=2");
+        let expected = ErrorFormat::Problem {
+            instance: String::from("/todos/1"),
+        };
+
+        for value in [
+            "application/problem+json",
+            "application/json;q=0.9, application/problem+json",
+            "application/json, application/problem+json",
+        ] {
+            assert_eq!(error_format(&accept(value), &uri), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn problem_format_respects_quality() {
+        let uri = Uri::from_static("/todos");
+        for value in [
+            "application/problem+json;q=0",
+            "application/json, application/problem+json;q=0.9",
+        ] {
+            assert_eq!(
+                error_format(&accept(value), &uri),
+                ErrorFormat::Content,
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn accepted_format_defaults_to_json() {
+        assert_eq!(accepted_format(&HeaderMap::new()), Some(Format::Json));
+        assert_eq!(accepted_format(&accept("*/*")), Some(Format::Json));
+    }
+
+    #[test]
+    fn accepted_format_respects_quality() {
+        let headers = accept("application/json;q=0.5, application/cbor");
+        assert_eq!(accepted_format(&headers), Some(Format::Cbor));
+
+        let headers = accept("application/msgpack;q=0.8, application/cbor;q=0.2");
+        assert_eq!(accepted_format(&headers), Some(Format::MessagePack));
+
+        let headers = accept("application/cbor;q=0, application/xml");
+        assert_eq!(accepted_format(&headers), None);
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        title: String,
+        #[serde(rename = "todoAt")]
+        todo_at: Option<String>,
+    }
+
+    #[test]
+    fn formats_round_trip() {
+        let sample = Sample {
+            title: String::from("Title"),
+            todo_at: None,
+        };
+
+        for format in [Format::Json, Format::MessagePack, Format::Cbor] {
+            let bytes = format.encode(&sample).unwrap();
+            assert_eq!(format.decode::<Sample>(&bytes).unwrap(), sample);
+        }
+    }
+
+    #[tokio::test]
+    async fn large_bodies_are_rejected() {
+        use axum::extract::DefaultBodyLimit;
+        use axum::routing::post;
+        use axum::Router;
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route("/todos", post(|_: Decoded<Sample>| async {}))
+            .layer(DefaultBodyLimit::max(16));
+        let req = Request::builder()
+            .method("POST")
+            .uri("/todos")
+            .header(header::CONTENT_TYPE, JSON_CONTENT_TYPE)
+            .body(axum::body::Body::from(r#"{"title":"Buy groceries"}"#))
+            .unwrap();
+
+        let res = app.oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], "PayloadTooLarge");
+    }
+}
@@ -0,0 +1,163 @@
+use axum::Router;
+use utoipa::openapi::OpenApi as OpenApiDoc;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::framework::rest_api::routes::backup::BackupApi;
+use crate::framework::rest_api::routes::health::HealthApi;
+use crate::framework::rest_api::routes::todo::TodoApi;
+use crate::framework::rest_api::routes::webhook::WebhookApi;
+
+#[derive(OpenApi)]
+#[openapi(info(title = "Todo API", description = "REST API to manage todos"))]
+struct ApiDoc;
+
+/// Create the OpenAPI document of every documented route
+pub fn create_openapi() -> OpenApiDoc {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(TodoApi::openapi());
+    doc.merge(WebhookApi::openapi());
+    doc.merge(BackupApi::openapi());
+    doc.merge(HealthApi::openapi());
+    doc
+}
+
+/// Serve the OpenAPI document at `/openapi.json` and a Swagger UI at `/docs`
+pub fn create_router() -> Router {
+    // swagger ui is built against an older `utoipa`, so it receives the already serialized document
+    let doc = serde_json::to_value(create_openapi()).expect("Failed serializing OpenAPI document");
+
+    SwaggerUi::new("/docs")
+        .external_url_unchecked("/openapi.json", doc)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use sqlx::postgres::PgPoolOptions;
+    use tokio_util::sync::CancellationToken;
+    use tower::ServiceExt;
+    use utoipa::openapi::HttpMethod;
+
+    use super::*;
+    use crate::framework::events::todo::TodoBroadcaster;
+    use crate::framework::health::{HealthChecker, Readiness};
+    use crate::framework::rest_api::redaction::Redaction;
+    use crate::framework::rest_api::routes::{backup, health, todo, webhook};
+
+    /// Routes served without being part of the document, which are the ones
+    /// of the document itself
+    const UNDOCUMENTED: [(Method, &str); 2] =
+        [(Method::GET, "/openapi.json"), (Method::GET, "/docs/")];
+
+    const METHODS: [(HttpMethod, Method); 5] = [
+        (HttpMethod::Get, Method::GET),
+        (HttpMethod::Post, Method::POST),
+        (HttpMethod::Put, Method::PUT),
+        (HttpMethod::Patch, Method::PATCH),
+        (HttpMethod::Delete, Method::DELETE),
+    ];
+
+    fn create_app() -> Router {
+        // requests are rejected for missing a tenant before touching the database
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+
+        Router::new()
+            .merge(todo::create_router(
+                pool.clone(),
+                TodoBroadcaster::new(1),
+                CancellationToken::new(),
+                Redaction::new(true),
+                usize::MAX,
+            ))
+            .merge(webhook::create_router(pool.clone()))
+            .merge(backup::create_router(pool.clone(), usize::MAX))
+            .merge(health::create_router(HealthChecker::new(
+                pool,
+                Readiness::new(),
+                Duration::from_millis(100),
+            )))
+            .merge(create_router())
+    }
+
+    /// Replace path templates such as `{id}` with a concrete value, keeping
+    /// what follows them in the segment such as `{id}.ics`
+    fn concrete_path(path: &str) -> String {
+        path.split('/')
+            .map(|segment| match segment.starts_with('{') {
+                true => {
+                    let suffix = segment.split_once('}').map_or("", |(_, suffix)| suffix);
+                    format!("00000000-0000-0000-0000-000000000000{suffix}")
+                }
+                false => segment.to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Whether the app routes `method` to `path`, rather than responding it
+    /// has no such route
+    async fn is_routed(method: Method, path: &str) -> bool {
+        let req = Request::builder()
+            .method(method)
+            .uri(concrete_path(path))
+            .body(Body::empty())
+            .unwrap();
+
+        let status = create_app().oneshot(req).await.unwrap().status();
+        status != StatusCode::NOT_FOUND && status != StatusCode::METHOD_NOT_ALLOWED
+    }
+
+    #[tokio::test]
+    async fn spec_matches_routes() {
+        let doc = create_openapi();
+        assert!(!doc.paths.paths.is_empty());
+
+        for (path, item) in doc.paths.paths.iter() {
+            for (http_method, method) in METHODS {
+                let documented = match http_method {
+                    HttpMethod::Get => item.get.is_some(),
+                    HttpMethod::Post => item.post.is_some(),
+                    HttpMethod::Put => item.put.is_some(),
+                    HttpMethod::Patch => item.patch.is_some(),
+                    HttpMethod::Delete => item.delete.is_some(),
+                    _ => false,
+                };
+                let routed = is_routed(method.clone(), path).await;
+
+                assert_eq!(
+                    documented, routed,
+                    "{method} {path} is documented: {documented}, but routed: {routed}"
+                );
+            }
+        }
+
+        for (method, path) in UNDOCUMENTED {
+            assert!(
+                !doc.paths.paths.contains_key(path),
+                "{path} is documented but listed as undocumented"
+            );
+            assert!(
+                is_routed(method.clone(), path).await,
+                "{method} {path} is not routed"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn openapi_json_is_served() {
+        let req = Request::builder()
+            .uri("/openapi.json")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = create_router().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,38 @@
+/// Replaces the value of redacted fields in logs
+const REDACTED: &str = "[redacted]";
+
+/// Fields of requests left out of logs, as they may hold personal data
+#[derive(Clone, Copy, Debug)]
+pub struct Redaction {
+    descriptions: bool,
+}
+
+impl Redaction {
+    pub fn new(descriptions: bool) -> Self {
+        Self { descriptions }
+    }
+
+    /// Description of a todo as logged, which still tells whether there is one
+    /// when descriptions are redacted
+    pub fn description<'a>(&self, description: Option<&'a str>) -> Option<&'a str> {
+        match description {
+            Some(_) if self.descriptions => Some(REDACTED),
+            description => description,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_present_descriptions_are_redacted() {
+        let redaction = Redaction::new(true);
+        assert_eq!(redaction.description(Some("Call Ana")), Some(REDACTED));
+        assert_eq!(redaction.description(None), None);
+
+        let redaction = Redaction::new(false);
+        assert_eq!(redaction.description(Some("Call Ana")), Some("Call Ana"));
+    }
+}
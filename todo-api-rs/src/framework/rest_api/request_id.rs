@@ -0,0 +1,109 @@
+use std::fmt;
+
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest request id accepted from clients, longer ones are replaced
+const MAX_LEN: usize = 128;
+
+/// Id correlating the logs and spans of a request, taken from its
+/// `X-Request-Id` header or generated when it has none
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RequestId(HeaderValue);
+
+impl RequestId {
+    /// Id of the `X-Request-Id` header of `req`, when it is printable ASCII
+    /// and not too long, so it is safe to log
+    fn from_request(req: &Request) -> Option<Self> {
+        req.headers()
+            .get(REQUEST_ID_HEADER)
+            .filter(|value| {
+                (1..=MAX_LEN).contains(&value.len())
+                    && value.as_bytes().iter().all(u8::is_ascii_graphic)
+            })
+            .cloned()
+            .map(Self)
+    }
+
+    fn generate() -> Self {
+        Self(HeaderValue::from_str(&Uuid::new_v4().to_string()).expect("Uuids are valid headers"))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.to_str().expect("Request ids are ASCII")
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Middleware giving every request an id, which the span of the request
+/// records and the response echoes in its `X-Request-Id` header. It must run
+/// before the tracing layer for the span to see the id
+pub async fn assign(mut req: Request, next: Next) -> Response {
+    let id = RequestId::from_request(&req).unwrap_or_else(RequestId::generate);
+    req.extensions_mut().insert(id.clone());
+
+    let mut res = next.run(req).await;
+    res.headers_mut().insert(REQUEST_ID_HEADER, id.0);
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::{middleware, Extension, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn request_id(header: Option<&str>) -> (String, String) {
+        let app = Router::new()
+            .route(
+                "/",
+                get(|Extension(id): Extension<RequestId>| async move { id.to_string() }),
+            )
+            .layer(middleware::from_fn(assign));
+
+        let mut req = Request::builder().uri("/");
+        if let Some(header) = header {
+            req = req.header(REQUEST_ID_HEADER, header);
+        }
+        let res = app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+
+        let echoed = res.headers()[REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (echoed, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn ids_of_clients_are_kept() {
+        let (echoed, seen) = request_id(Some("checkout-42")).await;
+        assert_eq!(echoed, "checkout-42");
+        assert_eq!(seen, "checkout-42");
+    }
+
+    #[tokio::test]
+    async fn missing_or_unsafe_ids_are_generated() {
+        let long = "a".repeat(MAX_LEN + 1);
+        for header in [None, Some(""), Some("two words"), Some(long.as_str())] {
+            let (echoed, seen) = request_id(header).await;
+            assert_eq!(echoed, seen);
+            assert!(Uuid::parse_str(&echoed).is_ok(), "{header:?} was kept");
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use futures_util::TryStreamExt;
+
+use super::BackupState;
+use crate::adapters::controllers::backup::export::ExportBackupController;
+use crate::adapters::presenters::json::backup::{BackupArchiveView, JsonBackupPresenter};
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::application::use_cases::backup::export::ExportBackupUseCase;
+use crate::framework::rest_api::tenant::Tenant;
+
+#[utoipa::path(
+    get,
+    path = "/backup",
+    tag = "backup",
+    params(Tenant),
+    responses(
+        (status = 200, description = "Archive of every todo, with their ids and timestamps, and of the entries they were imported from", body = BackupArchiveView),
+        (status = 400, description = "`InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn export_backup(State(state): State<BackupState>, tenant: Tenant) -> Response {
+    tracing::info!(tenant.id = %tenant.id(), "Export backup request");
+
+    let interactor = ExportBackupUseCase::new(state.backup_repository.with_tenant(tenant.id()));
+    let controller = ExportBackupController::new(interactor, JsonBackupPresenter::new());
+    let content = controller.run().await;
+
+    // the status is already sent when a later batch fails, so the error can
+    // only cut the archive short, which then fails to restore
+    let body = Body::from_stream(content.map_err(|err| {
+        tracing::error!("Export backup internal error: {err}");
+        err.to_string()
+    }));
+    let headers = [
+        (header::CONTENT_TYPE, "application/json"),
+        (
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"backup.json\"",
+        ),
+    ];
+
+    (StatusCode::OK, headers, body).into_response()
+}
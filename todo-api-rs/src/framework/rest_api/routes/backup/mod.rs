@@ -0,0 +1,61 @@
+mod export;
+mod restore;
+
+use axum::extract::DefaultBodyLimit;
+use axum::routing::{get, post};
+use axum::Router;
+use sqlx::{Pool, Postgres};
+use tower_http::decompression::RequestDecompressionLayer;
+use utoipa::OpenApi;
+
+use crate::adapters::presenters::json::backup::{
+    BackupArchiveView, BackupImportView, RestoreReportView, TableSummaryView,
+};
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::{FieldError, Problem};
+use crate::framework::storage::repositories::backup::PgBackupRepository;
+
+use export::export_backup;
+use restore::restore_backup;
+
+/// Create the routes of backups. Restores accept bodies up to
+/// `restore_body_limit` bytes, which is usually above the limit of other
+/// routes since an archive has every todo of the tenant. Archives may be
+/// compressed, the limit applying to the decompressed archive
+pub fn create_router(pool: Pool<Postgres>, restore_body_limit: usize) -> Router {
+    let state = BackupState {
+        backup_repository: PgBackupRepository::new(pool),
+    };
+
+    Router::new()
+        .route("/backup", get(export_backup))
+        .route(
+            "/backup/restore",
+            post(restore_backup).layer((
+                RequestDecompressionLayer::new(),
+                DefaultBodyLimit::max(restore_body_limit),
+            )),
+        )
+        .with_state(state)
+}
+
+#[derive(Clone)]
+struct BackupState {
+    backup_repository: PgBackupRepository,
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(export::export_backup, restore::restore_backup),
+    components(schemas(
+        BackupArchiveView,
+        BackupImportView,
+        RestoreReportView,
+        TableSummaryView,
+        Content,
+        Problem,
+        FieldError
+    )),
+    tags((name = "backup", description = "Export and restore the todos of a tenant and the entries they were imported from, without its webhooks"))
+)]
+pub struct BackupApi;
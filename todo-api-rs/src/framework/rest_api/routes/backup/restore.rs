@@ -0,0 +1,115 @@
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::BackupState;
+use crate::adapters::controllers::backup::restore::RestoreBackupController;
+use crate::adapters::dtos::backup::restore::RestoreRequest;
+use crate::adapters::presenters::json::backup::{
+    BackupArchiveView, JsonBackupPresenter, RestoreReportView,
+};
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::application::use_cases::backup::restore::RestoreBackupUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
+
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(super) struct RestoreParams {
+    /// Either `merge`, which is the default and keeps the todos missing from
+    /// the archive, or `replace`, which deletes them
+    #[param(example = "replace")]
+    mode: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/backup/restore",
+    tag = "backup",
+    params(Tenant, RestoreParams),
+    request_body(
+        description = "Archive written by a backup. Todos are restored with their ids and \
+            timestamps, overwriting the todos with the same id, and so are the entries they \
+            were imported from",
+        content = BackupArchiveView,
+        content_type = "application/json",
+    ),
+    responses(
+        (status = 200, description = "Todos and imports changed by the restore", content(
+            (RestoreReportView = "application/json"),
+            (RestoreReportView = "application/msgpack"),
+            (RestoreReportView = "application/cbor"),
+        )),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 409, description = "`DuplicatedTitle` or `IdTaken` by another tenant, nothing is restored", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 413, description = "Archive is larger than the restore body limit, 64 MiB by default"),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn restore_backup(
+    State(state): State<BackupState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Query(query): Query<RestoreParams>,
+    body: Bytes,
+) -> Response {
+    let req = RestoreRequest {
+        mode: query.mode,
+        archive: body.into(),
+    };
+
+    tracing::info!(
+        tenant.id = %tenant.id(),
+        backup.bytes = req.archive.len(),
+        backup.mode = req.mode.as_deref(),
+        "Restore backup request"
+    );
+
+    let presenter = JsonBackupPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "restore_backup",
+        RestoreBackupUseCase::new(state.backup_repository.with_tenant(tenant.id())),
+    );
+    let controller = RestoreBackupController::new(interactor, presenter);
+    let output = match controller.run(req).await {
+        Ok(output) => output,
+        Err(err) => {
+            if let Some(src) = err.src() {
+                tracing::error!("Restore backup internal error: {src}");
+            } else {
+                tracing::error!("Restore backup error: {err:?}");
+            }
+
+            let status = match StatusCode::from_u16(err.status()) {
+                Ok(status) => status,
+                Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            return (status, Encoded::error(format, err)).into_response();
+        }
+    };
+
+    (StatusCode::OK, Encoded::new(format, output)).into_response()
+}
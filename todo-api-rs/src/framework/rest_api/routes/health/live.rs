@@ -0,0 +1,24 @@
+use axum::Json;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct LiveView {
+    /// Always `up`
+    #[schema(example = "up")]
+    pub status: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses(
+        (status = 200, description = "Server is running, whatever the state of its dependencies", body = LiveView),
+    )
+)]
+pub(super) async fn live() -> Json<LiveView> {
+    Json(LiveView {
+        status: String::from("up"),
+    })
+}
@@ -0,0 +1,27 @@
+mod live;
+mod ready;
+
+use axum::routing::get;
+use axum::Router;
+use utoipa::OpenApi;
+
+use crate::framework::health::HealthChecker;
+
+use live::{live, LiveView};
+use ready::{ready, DatabaseView, MigrationsView, PoolView, ReadinessView};
+
+/// Create the probes of the server, which need no tenant
+pub fn create_router(checker: HealthChecker) -> Router {
+    Router::new()
+        .route("/health/live", get(live))
+        .route("/health/ready", get(ready))
+        .with_state(checker)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(live::live, ready::ready),
+    components(schemas(LiveView, ReadinessView, DatabaseView, MigrationsView, PoolView)),
+    tags((name = "health", description = "Probe whether the server is alive and ready for requests"))
+)]
+pub struct HealthApi;
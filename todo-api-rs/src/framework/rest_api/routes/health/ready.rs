@@ -0,0 +1,190 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::framework::health::{HealthChecker, ReadinessReport};
+
+/// State of every dependency of the server
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct ReadinessView {
+    /// Either `ready` or `degraded`
+    #[schema(example = "ready")]
+    pub status: String,
+    /// Whether the server is shutting down, which makes it degraded
+    pub draining: bool,
+    pub database: DatabaseView,
+    pub migrations: MigrationsView,
+    pub pool: PoolView,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct DatabaseView {
+    /// Either `up` or `down`
+    #[schema(example = "up")]
+    pub status: String,
+    /// Time `SELECT 1` took, when it answered
+    #[serde(rename = "latencyMs")]
+    pub latency_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct MigrationsView {
+    /// Either `up`, or `down` when some are pending or they cannot be checked
+    #[schema(example = "up")]
+    pub status: String,
+    /// Versions of the migrations that are not applied
+    pub pending: Vec<i64>,
+    pub error: Option<String>,
+}
+
+/// Connections of the pool, which does not make the server degraded when
+/// saturated since requests still wait for a connection to be released
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct PoolView {
+    /// Either `up` or `saturated` when every connection is in use
+    #[schema(example = "up")]
+    pub status: String,
+    pub size: u32,
+    pub idle: usize,
+    pub max: u32,
+}
+
+impl From<ReadinessReport> for ReadinessView {
+    fn from(report: ReadinessReport) -> Self {
+        let status = match report.is_ready() {
+            true => "ready",
+            false => "degraded",
+        };
+
+        let database = match report.database {
+            Ok(latency) => DatabaseView {
+                status: String::from("up"),
+                latency_ms: Some(latency.as_secs_f64() * 1000.0),
+                error: None,
+            },
+            Err(err) => DatabaseView {
+                status: String::from("down"),
+                latency_ms: None,
+                error: Some(err.to_string()),
+            },
+        };
+
+        let migrations = match report.pending_migrations {
+            Ok(pending) => MigrationsView {
+                status: String::from(if pending.is_empty() { "up" } else { "down" }),
+                pending,
+                error: None,
+            },
+            Err(err) => MigrationsView {
+                status: String::from("down"),
+                pending: Vec::new(),
+                error: Some(err.to_string()),
+            },
+        };
+
+        let pool = PoolView {
+            status: String::from(if report.pool.is_saturated() {
+                "saturated"
+            } else {
+                "up"
+            }),
+            size: report.pool.size,
+            idle: report.pool.idle,
+            max: report.pool.max,
+        };
+
+        Self {
+            status: status.to_owned(),
+            draining: report.draining,
+            database,
+            migrations,
+            pool,
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Every dependency is up", body = ReadinessView),
+        (status = 503, description = "A dependency is down, or the server is shutting down", body = ReadinessView),
+    )
+)]
+pub(super) async fn ready(
+    State(checker): State<HealthChecker>,
+) -> (StatusCode, Json<ReadinessView>) {
+    let report = checker.check().await;
+    let status = match report.is_ready() {
+        true => StatusCode::OK,
+        false => {
+            tracing::warn!("Readiness check failed: {report:?}");
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    };
+
+    (status, Json(ReadinessView::from(report)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use sqlx::PgPool;
+    use tower::ServiceExt;
+
+    use super::super::create_router;
+    use super::*;
+    use crate::framework::health::Readiness;
+
+    async fn get_ready(checker: HealthChecker) -> (StatusCode, serde_json::Value) {
+        let req = Request::builder()
+            .uri("/health/ready")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = create_router(checker).oneshot(req).await.unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn readiness_fails_while_draining(pool: PgPool) {
+        let readiness = Readiness::new();
+        let checker = HealthChecker::new(pool, readiness.clone(), Duration::from_secs(2));
+
+        let (status, body) = get_ready(checker.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+        assert_eq!(body["database"]["status"], "up");
+        assert_eq!(body["migrations"]["pending"], serde_json::json!([]));
+
+        readiness.drain();
+        let (status, body) = get_ready(checker).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["draining"], true);
+    }
+
+    #[tokio::test]
+    async fn unreachable_databases_are_down() {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost:1/unused")
+            .unwrap();
+        let checker = HealthChecker::new(pool, Readiness::new(), Duration::from_millis(200));
+
+        let (status, body) = get_ready(checker).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["database"]["status"], "down");
+        assert_eq!(body["migrations"]["status"], "down");
+        assert_eq!(body["draining"], false);
+    }
+}
@@ -0,0 +1,30 @@
+mod scrape;
+
+use std::time::Duration;
+
+use axum::routing::get;
+use axum::Router;
+use sqlx::{Pool, Postgres};
+
+use scrape::scrape;
+
+/// Create the route scraped by Prometheus, which needs no tenant so it is
+/// served on the admin address rather than next to the API. Counting todos
+/// takes at most `count_timeout`, so a slow database does not make the scrape
+/// itself time out
+pub fn create_router(pool: Pool<Postgres>, count_timeout: Duration) -> Router {
+    let state = MetricsState {
+        pool,
+        count_timeout,
+    };
+
+    Router::new()
+        .route("/metrics", get(scrape))
+        .with_state(state)
+}
+
+#[derive(Clone)]
+struct MetricsState {
+    pool: Pool<Postgres>,
+    count_timeout: Duration,
+}
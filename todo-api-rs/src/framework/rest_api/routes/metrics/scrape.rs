@@ -0,0 +1,24 @@
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use tokio::time::timeout;
+
+use super::MetricsState;
+use crate::framework::metrics::METRICS;
+use crate::framework::storage::metrics::count_todos;
+
+/// Content type of the Prometheus text format
+const TEXT_FORMAT: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub(super) async fn scrape(State(state): State<MetricsState>) -> impl IntoResponse {
+    METRICS.observe_pool(&state.pool);
+    // gauges keep the last counts when counting fails, so a failed count is
+    // not mistaken for every todo being deleted
+    match timeout(state.count_timeout, count_todos(&state.pool)).await {
+        Ok(Ok(counts)) => METRICS.observe_todos(&counts),
+        Ok(Err(err)) => tracing::error!("Failed counting todos for metrics: {err}"),
+        Err(_) => tracing::error!("Counting todos for metrics timed out"),
+    }
+
+    ([(header::CONTENT_TYPE, TEXT_FORMAT)], METRICS.render())
+}
@@ -1 +1,5 @@
+pub mod backup;
+pub mod health;
+pub mod metrics;
 pub mod todo;
+pub mod webhook;
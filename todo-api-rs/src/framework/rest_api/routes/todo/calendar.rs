@@ -0,0 +1,146 @@
+use axum::extract::{Path, Query, Request, State};
+use axum::handler::Handler;
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::export::{export, ExportFile, ExportParams};
+use super::find::FindPathParams;
+use super::TodoState;
+use crate::adapters::controllers::todo::find::FindTodoController;
+use crate::adapters::dtos::todo::find::FindRequest;
+use crate::adapters::presenters::ical::{IcalTodoPresenter, CALENDAR_CONTENT_TYPE};
+use crate::adapters::presenters::json::error::{Content, JsonError};
+use crate::adapters::presenters::json::problem::Problem;
+use crate::application::use_cases::todo::find::FindTodoUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{AcceptedErrorFormat, Encoded, Format};
+use crate::framework::rest_api::tenant::Tenant;
+
+const EXTENSION: &str = ".ics";
+
+#[utoipa::path(
+    get,
+    path = "/todos.ics",
+    tag = "todos",
+    params(Tenant, ExportParams),
+    responses(
+        (status = 200, description = "iCalendar feed of every todo, newest first", body = String, content_type = "text/calendar"),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn list_todo_calendar(
+    State(state): State<TodoState>,
+    tenant: Tenant,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Query(query): Query<ExportParams>,
+) -> Response {
+    let presenter = IcalTodoPresenter::new().with_error_format(errors);
+    let file = ExportFile {
+        content_type: CALENDAR_CONTENT_TYPE,
+        disposition: "inline; filename=\"todos.ics\"",
+    };
+    export(state, tenant, presenter, file, query).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/todos/{id}.ics",
+    tag = "todos",
+    params(Tenant, FindPathParams),
+    responses(
+        (status = 200, description = "iCalendar document of the todo", body = String, content_type = "text/calendar"),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 404, description = "`NotFound`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn find_todo_calendar(
+    State(state): State<TodoState>,
+    tenant: Tenant,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Path(path): Path<FindPathParams>,
+) -> Response {
+    let id = path
+        .id
+        .map(|id| id.strip_suffix(EXTENSION).map(str::to_owned).unwrap_or(id));
+    let req = FindRequest { id };
+
+    tracing::info!(tenant.id = %tenant.id(), todo.id = req.id.as_deref(), "Find todo calendar request");
+
+    let presenter = IcalTodoPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "find_todo",
+        FindTodoUseCase::new(state.todo_repository.with_tenant(tenant.id())),
+    );
+    let controller = FindTodoController::new(interactor, presenter);
+    match controller.run(req).await {
+        Ok(calendar) => calendar_response(calendar),
+        Err(err) => {
+            if let Some(src) = err.src() {
+                tracing::error!("Find todo calendar internal error: {src}");
+            } else {
+                tracing::error!("Find todo calendar error: {err:?}");
+            }
+
+            error_response(err)
+        }
+    }
+}
+
+/// Path parameters span whole segments, so `/todos/:id.ics` is matched by the
+/// `/todos/:id` route, and this middleware takes over the requests ending with
+/// `.ics` before they reach the handlers of that route
+pub(super) async fn serve_todo_calendar(
+    State(state): State<TodoState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !req.uri().path().ends_with(EXTENSION) {
+        return next.run(req).await;
+    }
+
+    if req.method() != Method::GET {
+        let allow = [(header::ALLOW, HeaderValue::from_static("GET"))];
+        return (StatusCode::METHOD_NOT_ALLOWED, allow).into_response();
+    }
+
+    find_todo_calendar.call(req, state).await
+}
+
+fn calendar_response(calendar: String) -> Response {
+    let content_type = [(header::CONTENT_TYPE, CALENDAR_CONTENT_TYPE)];
+    (StatusCode::OK, content_type, calendar).into_response()
+}
+
+pub(super) fn error_response(err: JsonError) -> Response {
+    let status = match StatusCode::from_u16(err.status()) {
+        Ok(status) => status,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Encoded::error(Format::Json, err)).into_response()
+}
@@ -1,27 +1,88 @@
 use axum::extract::State;
 use axum::http::{header, StatusCode};
 use axum::response::IntoResponse;
-use axum::Json;
 use serde::Deserialize;
+use utoipa::ToSchema;
 
 use super::TodoState;
 use crate::adapters::controllers::todo::create::CreateTodoController;
 use crate::adapters::dtos::todo::create::CreateRequest;
-use crate::adapters::presenters::json::todo::JsonTodoPresenter;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::todo::{JsonTodoPresenter, TodoView};
 use crate::application::use_cases::todo::create::CreateTodoUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Decoded, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, ToSchema)]
 pub(super) struct CreateBody {
-    title: Option<String>,
-    description: Option<String>,
-    #[serde(rename(deserialize = "todoAt"))]
-    todo_at: Option<String>,
-    status: Option<String>,
+    #[schema(required = true, example = "Buy groceries")]
+    pub(super) title: Option<String>,
+    pub(super) description: Option<String>,
+    /// Date in YYYY-MM-DD UTC format
+    #[serde(rename = "todoAt")]
+    #[schema(format = Date)]
+    pub(super) todo_at: Option<String>,
+    /// One of `todo`, `in_progress` or `done`
+    #[schema(required = true, example = "todo")]
+    pub(super) status: Option<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/todos",
+    tag = "todos",
+    params(Tenant),
+    request_body(content(
+        (CreateBody = "application/json"),
+        (CreateBody = "application/msgpack"),
+        (CreateBody = "application/cbor"),
+    )),
+    responses(
+        (status = 201, description = "Todo created", content(
+            (TodoView = "application/json"),
+            (TodoView = "application/msgpack"),
+            (TodoView = "application/cbor"),
+        ), headers(
+            ("location" = String, description = "Path of the created todo")
+        )),
+        (status = 400, description = "`ParseError`, `InvalidBody` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 409, description = "`DuplicatedTitle`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 413, description = "`PayloadTooLarge`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 415, description = "`UnsupportedMediaType`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
 pub(super) async fn create_todo(
     State(state): State<TodoState>,
-    Json(body): Json<CreateBody>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Decoded(body): Decoded<CreateBody>,
 ) -> impl IntoResponse {
     let req = CreateRequest {
         title: body.title,
@@ -30,10 +91,23 @@ pub(super) async fn create_todo(
         status: body.status,
     };
 
-    tracing::info!("Create todo request: {req:?}");
+    tracing::info!(
+        tenant.id = %tenant.id(),
+        todo.title = req.title.as_deref(),
+        todo.description = state.redaction.description(req.description.as_deref()),
+        todo.todo_at = req.todo_at.as_deref(),
+        todo.status = req.status.as_deref(),
+        "Create todo request"
+    );
 
-    let presenter = JsonTodoPresenter::new();
-    let interactor = CreateTodoUseCase::new(state.todo_repository);
+    let presenter = JsonTodoPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "create_todo",
+        CreateTodoUseCase::new(
+            state.todo_repository.with_tenant(tenant.id()),
+            state.todo_dispatcher.with_tenant(tenant.id()),
+        ),
+    );
     let controller = CreateTodoController::new(interactor, presenter);
     let output = match controller.run(req).await {
         Ok(output) => output,
@@ -48,7 +122,7 @@ pub(super) async fn create_todo(
                 Ok(status) => status,
                 Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
             };
-            return (status, Json(err.content)).into_response();
+            return (status, Encoded::error(format, err)).into_response();
         }
     };
 
@@ -57,5 +131,5 @@ pub(super) async fn create_todo(
         headers.insert(header::LOCATION, location);
     }
 
-    (StatusCode::CREATED, headers, Json(output)).into_response()
+    (StatusCode::CREATED, headers, Encoded::new(format, output)).into_response()
 }
@@ -1,30 +1,76 @@
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::Json;
 use serde::Deserialize;
+use utoipa::IntoParams;
 
 use super::TodoState;
 use crate::adapters::controllers::todo::delete::DeleteTodoController;
 use crate::adapters::dtos::todo::delete::DeleteRequest;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
 use crate::adapters::presenters::json::todo::JsonTodoPresenter;
 use crate::application::use_cases::todo::delete::DeleteTodoUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
 pub(super) struct DeletePathParams {
+    /// Id of the todo
+    #[param(required = true, format = Uuid)]
     id: Option<String>,
 }
 
+#[utoipa::path(
+    delete,
+    path = "/todos/{id}",
+    tag = "todos",
+    params(Tenant, DeletePathParams),
+    responses(
+        (status = 204, description = "Todo deleted"),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 404, description = "`NotFound`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
 pub(super) async fn delete_todo(
     State(state): State<TodoState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
     Path(path): Path<DeletePathParams>,
 ) -> impl IntoResponse {
     let req = DeleteRequest { id: path.id };
 
-    tracing::info!("Delete todo request {req:?}");
+    tracing::info!(tenant.id = %tenant.id(), todo.id = req.id.as_deref(), "Delete todo request");
 
-    let presenter = JsonTodoPresenter::new();
-    let interactor = DeleteTodoUseCase::new(state.todo_repository);
+    let presenter = JsonTodoPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "delete_todo",
+        DeleteTodoUseCase::new(
+            state.todo_repository.with_tenant(tenant.id()),
+            state.todo_dispatcher.with_tenant(tenant.id()),
+        ),
+    );
     let controller = DeleteTodoController::new(interactor, presenter);
     if let Err(err) = controller.run(req).await {
         if let Some(src) = err.src() {
@@ -37,8 +83,7 @@ pub(super) async fn delete_todo(
             Ok(status) => status,
             Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
         };
-
-        return (status, Json(err.content)).into_response();
+        return (status, Encoded::error(format, err)).into_response();
     }
 
     (StatusCode::NO_CONTENT).into_response()
@@ -0,0 +1,72 @@
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::stream::{Stream, StreamExt};
+
+use super::TodoState;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::todo::TodoView;
+use crate::framework::events::todo::Received;
+use crate::framework::rest_api::tenant::Tenant;
+
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+#[utoipa::path(
+    get,
+    path = "/todos/events",
+    tag = "todos",
+    params(
+        Tenant,
+        ("Last-Event-ID" = Option<u64>, Header, description = "Id of the last received event, to resume the stream after it on any instance"),
+    ),
+    responses(
+        (status = 200, description = "Stream of `todo.created`, `todo.updated` and `todo.deleted` events, each carrying a `TodoView` as data. A `reset` event without data is sent first when the events after `Last-Event-ID` are no longer kept, such as after a restart, and todos must then be reloaded", content(
+            (TodoView = "text/event-stream"),
+        )),
+        (status = 400, description = "`InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn todo_events(
+    State(state): State<TodoState>,
+    tenant: Tenant,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    // an invalid id is ignored, as if the client never received an event
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+
+    tracing::info!(
+        tenant.id = %tenant.id(),
+        last_event_id,
+        "Todo events subscription"
+    );
+
+    let events = state
+        .todo_events
+        .with_tenant(tenant.id())
+        .subscribe(last_event_id)
+        .take_until(state.shutdown.cancelled_owned())
+        .map(into_sse_event);
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+fn into_sse_event(received: Received) -> Result<Event, axum::Error> {
+    match received {
+        Received::Reset => Ok(Event::default().event("reset").data("")),
+        Received::Event(event) => Event::default()
+            .id(event.id.to_string())
+            .event(event.name())
+            .json_data(TodoView::from(event.change.todo().clone())),
+    }
+}
@@ -0,0 +1,147 @@
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use futures_util::TryStreamExt;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::calendar::error_response;
+use super::TodoState;
+use crate::adapters::controllers::todo::export::ExportTodosController;
+use crate::adapters::dtos::todo::export::{ExportPresenter, ExportRequest, ExportStream};
+use crate::adapters::presenters::csv::{CsvTodoPresenter, CSV_CONTENT_TYPE};
+use crate::adapters::presenters::json::error::{Content, JsonError};
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::txt::{TxtTodoPresenter, TODO_TXT_CONTENT_TYPE};
+use crate::application::use_cases::todo::export::ExportTodosUseCase;
+use crate::framework::rest_api::negotiation::AcceptedErrorFormat;
+use crate::framework::rest_api::tenant::Tenant;
+
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(super) struct ExportParams {
+    /// Only export todos whose title contains this text
+    pub(super) title: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/todos.csv",
+    tag = "todos",
+    params(Tenant, ExportParams),
+    responses(
+        (status = 200, description = "Every todo as a CSV record, newest first", body = String, content_type = "text/csv"),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn export_csv(
+    State(state): State<TodoState>,
+    tenant: Tenant,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Query(query): Query<ExportParams>,
+) -> Response {
+    let presenter = CsvTodoPresenter::new().with_error_format(errors);
+    let file = ExportFile {
+        content_type: CSV_CONTENT_TYPE,
+        disposition: "attachment; filename=\"todos.csv\"",
+    };
+    export(state, tenant, presenter, file, query).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/todos.txt",
+    tag = "todos",
+    params(Tenant, ExportParams),
+    responses(
+        (status = 200, description = "Every todo as a todo.txt line, newest first. Todos in progress have the `(A)` priority and done todos are completed", body = String, content_type = "text/plain"),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn export_txt(
+    State(state): State<TodoState>,
+    tenant: Tenant,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Query(query): Query<ExportParams>,
+) -> Response {
+    let presenter = TxtTodoPresenter::new().with_error_format(errors);
+    let file = ExportFile {
+        content_type: TODO_TXT_CONTENT_TYPE,
+        disposition: "attachment; filename=\"todo.txt\"",
+    };
+    export(state, tenant, presenter, file, query).await
+}
+
+/// Headers of an exported file
+pub(super) struct ExportFile {
+    pub(super) content_type: &'static str,
+    pub(super) disposition: &'static str,
+}
+
+/// Stream every todo of the tenant matching `query` as a file presented by
+/// `presenter`
+pub(super) async fn export<P>(
+    state: TodoState,
+    tenant: Tenant,
+    presenter: P,
+    file: ExportFile,
+    query: ExportParams,
+) -> Response
+where
+    P: ExportPresenter<View = Result<ExportStream, JsonError>>,
+{
+    let req = ExportRequest { title: query.title };
+
+    tracing::info!(
+        tenant.id = %tenant.id(),
+        title = req.title.as_deref(),
+        content_type = file.content_type,
+        "Export todos request"
+    );
+
+    let interactor = ExportTodosUseCase::new(state.todo_repository.with_tenant(tenant.id()));
+    let controller = ExportTodosController::new(interactor, presenter);
+    let content = match controller.run(req).await {
+        Ok(content) => content,
+        Err(err) => {
+            tracing::error!("Export todos error: {err:?}");
+            return error_response(err);
+        }
+    };
+
+    // the status is already sent when a later batch fails, so the error can
+    // only cut the body short
+    let body = Body::from_stream(content.map_err(|err| {
+        tracing::error!("Export todos internal error: {err}");
+        err.to_string()
+    }));
+    let headers = [
+        (header::CONTENT_TYPE, file.content_type),
+        (header::CONTENT_DISPOSITION, file.disposition),
+    ];
+
+    (StatusCode::OK, headers, body).into_response()
+}
@@ -1,30 +1,77 @@
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::Json;
 use serde::Deserialize;
+use utoipa::IntoParams;
 
 use super::TodoState;
 use crate::adapters::controllers::todo::find::FindTodoController;
 use crate::adapters::dtos::todo::find::FindRequest;
-use crate::adapters::presenters::json::todo::JsonTodoPresenter;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::todo::{JsonTodoPresenter, TodoView};
 use crate::application::use_cases::todo::find::FindTodoUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
 pub(super) struct FindPathParams {
-    id: Option<String>,
+    /// Id of the todo
+    #[param(required = true, format = Uuid)]
+    pub(super) id: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/todos/{id}",
+    tag = "todos",
+    params(Tenant, FindPathParams),
+    responses(
+        (status = 200, description = "Todo found", content(
+            (TodoView = "application/json"),
+            (TodoView = "application/msgpack"),
+            (TodoView = "application/cbor"),
+        )),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 404, description = "`NotFound`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
 pub(super) async fn find_todo(
     State(state): State<TodoState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
     Path(path): Path<FindPathParams>,
 ) -> impl IntoResponse {
     let req = FindRequest { id: path.id };
 
-    tracing::info!("Find todo request: {req:?}");
+    tracing::info!(tenant.id = %tenant.id(), todo.id = req.id.as_deref(), "Find todo request");
 
-    let presenter = JsonTodoPresenter::new();
-    let interactor = FindTodoUseCase::new(state.todo_repository);
+    let presenter = JsonTodoPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "find_todo",
+        FindTodoUseCase::new(state.todo_repository.with_tenant(tenant.id())),
+    );
     let controller = FindTodoController::new(interactor, presenter);
     let output = match controller.run(req).await {
         Ok(output) => output,
@@ -39,10 +86,9 @@ pub(super) async fn find_todo(
                 Ok(status) => status,
                 Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
             };
-
-            return (status, Json(err.content)).into_response();
+            return (status, Encoded::error(format, err)).into_response();
         }
     };
 
-    (StatusCode::OK, Json(output)).into_response()
+    (StatusCode::OK, Encoded::new(format, output)).into_response()
 }
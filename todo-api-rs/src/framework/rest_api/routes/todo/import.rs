@@ -0,0 +1,263 @@
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::TodoState;
+use crate::adapters::controllers::todo::import::ImportTodosController;
+use crate::adapters::dtos::todo::import::{ImportFormat, ImportRequest};
+use crate::adapters::presenters::json::error::{Content, ErrorFormat};
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::todo::{ImportReportView, JsonTodoPresenter};
+use crate::application::use_cases::todo::import::ImportTodosUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Encoded, Format};
+use crate::framework::rest_api::tenant::Tenant;
+
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(super) struct ImportParams {
+    /// What to do with titles and descriptions longer than allowed, either
+    /// `reject` the entry, which is the default, or `truncate` the text
+    #[param(example = "truncate")]
+    policy: Option<String>,
+    /// Report what importing would do without creating anything, defaults
+    /// to false
+    #[serde(rename = "dryRun")]
+    dry_run: Option<bool>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/todos/import/csv",
+    tag = "todos",
+    params(Tenant, ImportParams),
+    request_body(
+        description = "CSV file whose header names the columns of its records, among \
+            `title`, `description`, `status`, `todoAt` and `id`. Records with an `id` are \
+            skipped when imported again, other columns are ignored",
+        content = String,
+        content_type = "text/csv",
+    ),
+    responses(
+        (status = 200, description = "Outcome of every imported record, by line", content(
+            (ImportReportView = "application/json"),
+            (ImportReportView = "application/msgpack"),
+            (ImportReportView = "application/cbor"),
+        )),
+        (status = 400, description = "`ParseError`, `InvalidBody` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn import_csv(
+    State(state): State<TodoState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Query(query): Query<ImportParams>,
+    body: Bytes,
+) -> Response {
+    import(
+        state,
+        tenant,
+        format,
+        errors,
+        ImportFormat::Csv,
+        query,
+        body,
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/todos/import/ics",
+    tag = "todos",
+    params(Tenant, ImportParams),
+    request_body(
+        description = "iCalendar file whose `VTODO` components are imported",
+        content = String,
+        content_type = "text/calendar",
+    ),
+    responses(
+        (status = 200, description = "Outcome of every imported entry, by UID", content(
+            (ImportReportView = "application/json"),
+            (ImportReportView = "application/msgpack"),
+            (ImportReportView = "application/cbor"),
+        )),
+        (status = 400, description = "`ParseError`, `InvalidBody` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn import_ics(
+    State(state): State<TodoState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Query(query): Query<ImportParams>,
+    body: Bytes,
+) -> Response {
+    import(
+        state,
+        tenant,
+        format,
+        errors,
+        ImportFormat::Ics,
+        query,
+        body,
+    )
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/todos/import/txt",
+    tag = "todos",
+    params(Tenant, ImportParams),
+    request_body(
+        description = "todo.txt file whose lines are imported. Lines with an `id:` key are skipped \
+            when imported again. Completed lines are done, lines with the `(A)` priority are in \
+            progress and other lines are todo. The last `due:` key is the date of the todo, \
+            an empty one meaning it has none",
+        content = String,
+        content_type = "text/plain",
+    ),
+    responses(
+        (status = 200, description = "Outcome of every imported line", content(
+            (ImportReportView = "application/json"),
+            (ImportReportView = "application/msgpack"),
+            (ImportReportView = "application/cbor"),
+        )),
+        (status = 400, description = "`ParseError`, `InvalidBody` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn import_txt(
+    State(state): State<TodoState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Query(query): Query<ImportParams>,
+    body: Bytes,
+) -> Response {
+    import(
+        state,
+        tenant,
+        format,
+        errors,
+        ImportFormat::Txt,
+        query,
+        body,
+    )
+    .await
+}
+
+async fn import(
+    state: TodoState,
+    tenant: Tenant,
+    format: Format,
+    errors: ErrorFormat,
+    file_format: ImportFormat,
+    query: ImportParams,
+    body: Bytes,
+) -> Response {
+    let content = match String::from_utf8(body.into()) {
+        Ok(content) => content,
+        Err(_) => {
+            let content = Content::new("InvalidBody", "Imported file must be encoded in UTF-8");
+            return (
+                StatusCode::BAD_REQUEST,
+                Encoded::error(format, errors.error(400, content)),
+            )
+                .into_response();
+        }
+    };
+
+    let req = ImportRequest {
+        format: file_format,
+        content,
+        policy: query.policy,
+        dry_run: query.dry_run.unwrap_or_default(),
+    };
+
+    tracing::info!(
+        tenant.id = %tenant.id(),
+        bytes = req.content.len(),
+        format = file_format.name(),
+        policy = req.policy.as_deref(),
+        dry_run = req.dry_run,
+        "Import todos request"
+    );
+
+    let presenter = JsonTodoPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "import_todos",
+        ImportTodosUseCase::new(
+            state.todo_repository.with_tenant(tenant.id()),
+            state.todo_dispatcher.with_tenant(tenant.id()),
+        ),
+    );
+    let controller = ImportTodosController::new(interactor, presenter);
+    let output = match controller.run(req).await {
+        Ok(output) => output,
+        Err(err) => {
+            if let Some(src) = err.src() {
+                tracing::error!("Import todos internal error: {src}");
+            } else {
+                tracing::error!("Import todos error: {err:?}");
+            }
+
+            let status = match StatusCode::from_u16(err.status()) {
+                Ok(status) => status,
+                Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            return (status, Encoded::error(format, err)).into_response();
+        }
+    };
+
+    (StatusCode::OK, Encoded::new(format, output)).into_response()
+}
@@ -1,25 +1,68 @@
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::Json;
 use serde::Deserialize;
+use utoipa::IntoParams;
 
 use super::TodoState;
 use crate::adapters::controllers::todo::list::ListTodosController;
 use crate::adapters::dtos::todo::list::ListRequest;
-use crate::adapters::presenters::json::todo::JsonTodoPresenter;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::todo::{JsonTodoPresenter, TodosListView};
 use crate::application::use_cases::todo::list::ListTodosUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub(super) struct QueryParams {
-    page: Option<u32>,
-    #[serde(rename(deserialize = "perPage"))]
-    per_page: Option<u32>,
-    title: Option<String>,
+    /// Page starting from 1, defaults to 1
+    #[param(minimum = 1)]
+    pub(super) page: Option<u32>,
+    /// Items per page, defaults to 10
+    #[serde(rename = "perPage")]
+    #[param(minimum = 1)]
+    pub(super) per_page: Option<u32>,
+    /// Only list todos whose title contains this text
+    pub(super) title: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/todos",
+    tag = "todos",
+    params(Tenant, QueryParams),
+    responses(
+        (status = 200, description = "Paginated list of todos", content(
+            (TodosListView = "application/json"),
+            (TodosListView = "application/msgpack"),
+            (TodosListView = "application/cbor"),
+        )),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
 pub(super) async fn list_todo(
     State(state): State<TodoState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
     Query(query): Query<QueryParams>,
 ) -> impl IntoResponse {
     let req = ListRequest {
@@ -28,10 +71,19 @@ pub(super) async fn list_todo(
         title: query.title,
     };
 
-    tracing::info!("List todos request: {req:?}");
+    tracing::info!(
+        tenant.id = %tenant.id(),
+        page = req.page,
+        per_page = req.per_page,
+        title = req.title.as_deref(),
+        "List todos request"
+    );
 
-    let presenter = JsonTodoPresenter::new();
-    let interactor = ListTodosUseCase::new(state.todo_repository);
+    let presenter = JsonTodoPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "list_todos",
+        ListTodosUseCase::new(state.todo_repository.with_tenant(tenant.id())),
+    );
     let controller = ListTodosController::new(interactor, presenter);
     let output = match controller.run(req).await {
         Ok(output) => output,
@@ -46,10 +98,9 @@ pub(super) async fn list_todo(
                 Ok(status) => status,
                 Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
             };
-
-            return (status, Json(err.content)).into_response();
+            return (status, Encoded::error(format, err)).into_response();
         }
     };
 
-    (StatusCode::OK, Json(output)).into_response()
+    (StatusCode::OK, Encoded::new(format, output)).into_response()
 }
@@ -1,32 +1,99 @@
+mod calendar;
 mod create;
 mod delete;
+mod events;
+mod export;
 mod find;
+mod import;
 mod list;
 mod update;
+mod ws;
 
-use axum::extract::FromRef;
+use axum::extract::{DefaultBodyLimit, FromRef};
+use axum::middleware;
 use axum::routing::{get, post};
 use axum::Router;
 use sqlx::{Pool, Postgres};
+use tokio_util::sync::CancellationToken;
+use tower_http::decompression::RequestDecompressionLayer;
+use utoipa::OpenApi;
 
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::{FieldError, Problem};
+use crate::adapters::presenters::json::todo::{
+    ImportReportView, ImportedEntryView, InvalidEntryView, SkippedEntryView, TodoView,
+    TodosListView,
+};
+use crate::framework::events::dispatcher::LogDispatcher;
+use crate::framework::events::todo::TodoBroadcaster;
+use crate::framework::rest_api::redaction::Redaction;
 use crate::framework::storage::repositories::todo::PgTodoRepository;
 
-use create::create_todo;
+use calendar::{list_todo_calendar, serve_todo_calendar};
+use create::{create_todo, CreateBody};
 use delete::delete_todo;
+use events::todo_events;
+use export::{export_csv, export_txt};
 use find::find_todo;
+use import::{import_csv, import_ics, import_txt};
 use list::list_todo;
-use update::update_todo;
+use update::{update_todo, UpdateBody};
+use ws::todo_socket;
 
-pub fn create_router(pool: Pool<Postgres>) -> Router {
+/// Create the routes of todos. Event streams and sockets end once `shutdown`
+/// is cancelled, so they do not hold up the shutdown of the server. Created
+/// and updated todos accept bodies up to `body_limit` bytes, while imports
+/// keep the limit of other routes and may be compressed with gzip, brotli or
+/// zstd, the limit applying to the decompressed body
+pub fn create_router(
+    pool: Pool<Postgres>,
+    events: TodoBroadcaster,
+    shutdown: CancellationToken,
+    redaction: Redaction,
+    body_limit: usize,
+) -> Router {
     let state = TodoState {
         todo_repository: PgTodoRepository::new(pool.clone()),
+        todo_events: events,
+        todo_dispatcher: LogDispatcher::new(),
+        shutdown,
+        redaction,
     };
 
     Router::new()
-        .route("/todos", post(create_todo).get(list_todo))
+        .route(
+            "/todos",
+            post(create_todo)
+                .layer(DefaultBodyLimit::max(body_limit))
+                .get(list_todo),
+        )
+        .route("/todos.csv", get(export_csv))
+        .route("/todos.ics", get(list_todo_calendar))
+        .route("/todos.txt", get(export_txt))
+        .route("/todos/events", get(todo_events))
+        .route(
+            "/todos/import/csv",
+            post(import_csv).layer(RequestDecompressionLayer::new()),
+        )
+        .route(
+            "/todos/import/ics",
+            post(import_ics).layer(RequestDecompressionLayer::new()),
+        )
+        .route(
+            "/todos/import/txt",
+            post(import_txt).layer(RequestDecompressionLayer::new()),
+        )
+        .route("/ws", get(todo_socket))
         .route(
             "/todos/:id",
-            get(find_todo).delete(delete_todo).put(update_todo),
+            get(find_todo)
+                .delete(delete_todo)
+                .put(update_todo)
+                .layer(DefaultBodyLimit::max(body_limit))
+                .layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    serve_todo_calendar,
+                )),
         )
         .with_state(state)
 }
@@ -34,4 +101,43 @@ pub fn create_router(pool: Pool<Postgres>) -> Router {
 #[derive(FromRef, Clone)]
 struct TodoState {
     todo_repository: PgTodoRepository,
+    todo_events: TodoBroadcaster,
+    todo_dispatcher: LogDispatcher,
+    shutdown: CancellationToken,
+    redaction: Redaction,
 }
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create::create_todo,
+        list::list_todo,
+        find::find_todo,
+        export::export_csv,
+        export::export_txt,
+        calendar::list_todo_calendar,
+        calendar::find_todo_calendar,
+        import::import_csv,
+        import::import_ics,
+        import::import_txt,
+        update::update_todo,
+        delete::delete_todo,
+        events::todo_events,
+        ws::todo_socket,
+    ),
+    components(schemas(
+        CreateBody,
+        UpdateBody,
+        TodoView,
+        TodosListView,
+        ImportReportView,
+        ImportedEntryView,
+        SkippedEntryView,
+        InvalidEntryView,
+        Content,
+        Problem,
+        FieldError
+    )),
+    tags((name = "todos", description = "Create, read, update and delete todos"))
+)]
+pub struct TodoApi;
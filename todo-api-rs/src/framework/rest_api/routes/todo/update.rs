@@ -1,33 +1,95 @@
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::Json;
 use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
 
 use super::TodoState;
 use crate::adapters::controllers::todo::update::UpdateTodoController;
 use crate::adapters::dtos::todo::update::UpdateRequest;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
 use crate::adapters::presenters::json::todo::JsonTodoPresenter;
 use crate::application::use_cases::todo::update::UpdateTodoUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Decoded, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
 pub(super) struct UpdatePathParams {
+    /// Id of the todo
+    #[param(required = true, format = Uuid)]
     id: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, ToSchema)]
 pub(super) struct UpdateBody {
-    title: Option<String>,
-    description: Option<String>,
-    #[serde(rename(deserialize = "todoAt"))]
-    todo_at: Option<String>,
-    status: Option<String>,
+    #[schema(required = true, example = "Buy groceries")]
+    pub(super) title: Option<String>,
+    pub(super) description: Option<String>,
+    /// Date in YYYY-MM-DD UTC format
+    #[serde(rename = "todoAt")]
+    #[schema(format = Date)]
+    pub(super) todo_at: Option<String>,
+    /// One of `todo`, `in_progress` or `done`
+    #[schema(required = true, example = "in_progress")]
+    pub(super) status: Option<String>,
 }
 
+#[utoipa::path(
+    put,
+    path = "/todos/{id}",
+    tag = "todos",
+    params(Tenant, UpdatePathParams),
+    request_body(content(
+        (UpdateBody = "application/json"),
+        (UpdateBody = "application/msgpack"),
+        (UpdateBody = "application/cbor"),
+    )),
+    responses(
+        (status = 200, description = "Todo updated"),
+        (status = 400, description = "`ParseError`, `InvalidBody` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 404, description = "`NotFound`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 409, description = "`DuplicatedTitle` or `Conflict`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 413, description = "`PayloadTooLarge`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 415, description = "`UnsupportedMediaType`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
 pub(super) async fn update_todo(
     State(state): State<TodoState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
     Path(path): Path<UpdatePathParams>,
-    Json(body): Json<UpdateBody>,
+    Decoded(body): Decoded<UpdateBody>,
 ) -> impl IntoResponse {
     let req = UpdateRequest {
         id: path.id,
@@ -37,10 +99,24 @@ pub(super) async fn update_todo(
         status: body.status,
     };
 
-    tracing::info!("Update todo request: {req:?}");
+    tracing::info!(
+        tenant.id = %tenant.id(),
+        todo.id = req.id.as_deref(),
+        todo.title = req.title.as_deref(),
+        todo.description = state.redaction.description(req.description.as_deref()),
+        todo.todo_at = req.todo_at.as_deref(),
+        todo.status = req.status.as_deref(),
+        "Update todo request"
+    );
 
-    let presenter = JsonTodoPresenter::new();
-    let interactor = UpdateTodoUseCase::new(state.todo_repository);
+    let presenter = JsonTodoPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "update_todo",
+        UpdateTodoUseCase::new(
+            state.todo_repository.with_tenant(tenant.id()),
+            state.todo_dispatcher.with_tenant(tenant.id()),
+        ),
+    );
     let controller = UpdateTodoController::new(interactor, presenter);
     if let Err(err) = controller.run(req).await {
         if let Some(src) = err.src() {
@@ -53,8 +129,7 @@ pub(super) async fn update_todo(
             Ok(status) => status,
             Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
         };
-
-        (status, Json(err.content)).into_response()
+        (status, Encoded::error(format, err)).into_response()
     } else {
         (StatusCode::OK).into_response()
     }
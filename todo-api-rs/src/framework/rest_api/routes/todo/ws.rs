@@ -0,0 +1,873 @@
+use std::borrow::Cow;
+use std::pin::Pin;
+use std::time::Duration;
+
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::http::Method;
+use axum::response::Response;
+use axum::Extension;
+use futures_util::stream::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::time::{self, Instant};
+
+use super::create::CreateBody;
+use super::update::UpdateBody;
+use super::TodoState;
+use crate::adapters::controllers::todo::create::CreateTodoController;
+use crate::adapters::controllers::todo::delete::DeleteTodoController;
+use crate::adapters::controllers::todo::update::UpdateTodoController;
+use crate::adapters::dtos::todo::create::CreateRequest;
+use crate::adapters::dtos::todo::delete::DeleteRequest;
+use crate::adapters::dtos::todo::update::UpdateRequest;
+use crate::adapters::presenters::json::error::{Content, ErrorFormat, JsonError};
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::todo::{JsonTodoPresenter, TodoView};
+use crate::application::use_cases::todo::create::CreateTodoUseCase;
+use crate::application::use_cases::todo::delete::DeleteTodoUseCase;
+use crate::application::use_cases::todo::update::UpdateTodoUseCase;
+use crate::domain::entities::todo::{Status, TodoEntity};
+use crate::framework::events::todo::Received;
+use crate::framework::metrics::Measured;
+use crate::framework::rate_limit::{self, RateLimiter};
+use crate::framework::rest_api::load::{self, ConcurrencyLimit, RequestTimeout};
+use crate::framework::rest_api::negotiation::AcceptedErrorFormat;
+use crate::framework::rest_api::tenant::{Tenant, BEARER_PROTOCOL};
+
+/// How often the server pings the client
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// How long the client may stay silent, pongs included, before being disconnected
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(75);
+/// How long sending a single message may take before the client is considered too slow
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+type EventStream = Pin<Box<dyn Stream<Item = Received> + Send>>;
+
+/// Message sent by the client as a JSON text frame
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    /// Receive changes of todos matching `filter`, replacing any previous
+    /// subscription. Events after `lastEventId` that are still kept are replayed
+    Subscribe {
+        #[serde(default)]
+        filter: SubscribeFilter,
+        #[serde(rename = "lastEventId")]
+        last_event_id: Option<u64>,
+    },
+    Unsubscribe,
+    Create(CreateCommand),
+    Update(UpdateCommand),
+    Delete(DeleteCommand),
+}
+
+/// Message of the client run through the same controllers and use cases as REST
+#[derive(Debug)]
+enum Command {
+    Create(CreateCommand),
+    Update(UpdateCommand),
+    Delete(DeleteCommand),
+}
+
+impl Command {
+    fn request_id(&self) -> Option<&String> {
+        match self {
+            Command::Create(command) => command.request_id.as_ref(),
+            Command::Update(command) => command.request_id.as_ref(),
+            Command::Delete(command) => command.request_id.as_ref(),
+        }
+    }
+
+    /// REST route of the command, whose limits the command shares
+    fn route(&self) -> (Method, &'static str) {
+        match self {
+            Command::Create(_) => (Method::POST, "/todos"),
+            Command::Update(_) => (Method::PUT, "/todos/:id"),
+            Command::Delete(_) => (Method::DELETE, "/todos/:id"),
+        }
+    }
+}
+
+/// Limits of the server, which the middlewares only apply to the upgrade of
+/// the socket so they are applied to each command instead. A limit missing
+/// from the request is not applied
+#[derive(Clone)]
+struct Limits {
+    rate: Option<RateLimiter>,
+    timeout: Option<RequestTimeout>,
+    concurrency: Option<ConcurrencyLimit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateCommand {
+    #[serde(rename = "requestId")]
+    request_id: Option<String>,
+    body: CreateBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateCommand {
+    #[serde(rename = "requestId")]
+    request_id: Option<String>,
+    id: Option<String>,
+    body: UpdateBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeleteCommand {
+    #[serde(rename = "requestId")]
+    request_id: Option<String>,
+    id: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+struct SubscribeFilter {
+    /// Only todos whose title contains this text, ignoring case
+    title: Option<String>,
+    /// Only todos with this status
+    status: Option<String>,
+}
+
+/// Message sent by the server as a JSON text frame
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Subscribed,
+    Unsubscribed,
+    /// Events after `lastEventId` are no longer kept, so todos must be
+    /// reloaded before applying the following events
+    Reset,
+    Event {
+        id: u64,
+        event: &'static str,
+        todo: TodoView,
+    },
+    /// Outcome of a command, with the same status and body the REST route would respond with
+    Result {
+        #[serde(rename = "requestId", skip_serializing_if = "Option::is_none")]
+        request_id: Option<String>,
+        status: u16,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        todo: Option<TodoView>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<Value>,
+    },
+    /// Message from the client that could not be handled
+    Error {
+        status: u16,
+        error: Value,
+    },
+}
+
+/// Live channel to receive todo changes and send commands through JSON text frames.
+///
+/// Clients send `subscribe` (with optional `filter` and `lastEventId`), `unsubscribe`,
+/// `create`, `update` and `delete` messages. Commands are answered with a `result`
+/// message echoing their `requestId`, and changes are pushed as `event` messages,
+/// preceded by a `reset` message when the events after `lastEventId` are no longer kept.
+/// Events have the same id on every instance, so subscriptions may resume on any of them.
+/// Every message is scoped to the tenant that opened the connection, and every
+/// command has the rate limit, timeout and concurrency limit of its REST route,
+/// failing with the same error when they are exceeded. Browsers,
+/// which cannot set headers on the handshake, offer the `bearer` subprotocol
+/// followed by their token instead
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "todos",
+    params(Tenant),
+    responses(
+        (status = 101, description = "Switching to the websocket protocol"),
+        (status = 400, description = "`InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn todo_socket(
+    State(state): State<TodoState>,
+    tenant: Tenant,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    rate: Option<Extension<RateLimiter>>,
+    timeout: Option<Extension<RequestTimeout>>,
+    concurrency: Option<Extension<ConcurrencyLimit>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    tracing::info!(tenant.id = %tenant.id(), "Todo socket connection");
+
+    let limits = Limits {
+        rate: rate.map(|Extension(rate)| rate),
+        timeout: timeout.map(|Extension(timeout)| timeout),
+        concurrency: concurrency.map(|Extension(concurrency)| concurrency),
+    };
+
+    // the tenant is authorized once on upgrade and every command and
+    // subscription of the connection is bound to it
+    ws.max_message_size(MAX_MESSAGE_SIZE)
+        .protocols([BEARER_PROTOCOL])
+        .on_upgrade(move |socket| handle_socket(socket, state, tenant, errors, limits))
+}
+
+struct Subscription {
+    events: EventStream,
+    filter: Filter,
+}
+
+async fn handle_socket(
+    mut socket: WebSocket,
+    state: TodoState,
+    tenant: Tenant,
+    errors: ErrorFormat,
+    limits: Limits,
+) {
+    let mut subscription: Option<Subscription> = None;
+    let mut heartbeat = time::interval(HEARTBEAT_INTERVAL);
+    let mut last_seen = Instant::now();
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let message = match message {
+                    Some(Ok(message)) => message,
+                    Some(Err(err)) => {
+                        tracing::info!("Todo socket receive error: {err}");
+                        break;
+                    }
+                    None => break,
+                };
+
+                last_seen = Instant::now();
+                let reply = match message {
+                    Message::Text(text) => match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Subscribe { filter, last_event_id }) => {
+                            match Filter::parse(filter) {
+                                Ok(filter) => {
+                                    let events = state
+                                        .todo_events
+                                        .clone()
+                                        .with_tenant(tenant.id())
+                                        .subscribe(last_event_id);
+                                    subscription = Some(Subscription {
+                                        events: Box::pin(events),
+                                        filter,
+                                    });
+                                    ServerMessage::Subscribed
+                                }
+                                Err(err) => invalid_message(&errors, err),
+                            }
+                        }
+                        Ok(ClientMessage::Unsubscribe) => {
+                            subscription = None;
+                            ServerMessage::Unsubscribed
+                        }
+                        Ok(ClientMessage::Create(command)) => {
+                            run_command(&state, tenant, &errors, &limits, Command::Create(command)).await
+                        }
+                        Ok(ClientMessage::Update(command)) => {
+                            run_command(&state, tenant, &errors, &limits, Command::Update(command)).await
+                        }
+                        Ok(ClientMessage::Delete(command)) => {
+                            run_command(&state, tenant, &errors, &limits, Command::Delete(command)).await
+                        }
+                        Err(err) => invalid_message(&errors, format!("Invalid message: {err}")),
+                    },
+                    Message::Binary(_) => {
+                        invalid_message(&errors, "Messages must be sent as JSON text frames")
+                    }
+                    // pings are answered automatically
+                    Message::Ping(_) | Message::Pong(_) => continue,
+                    Message::Close(_) => break,
+                };
+
+                if !send(&mut socket, &reply).await {
+                    break;
+                }
+            }
+            received = next_event(&mut subscription) => {
+                let event = match received {
+                    Some(Received::Event(event)) => event,
+                    Some(Received::Reset) => {
+                        if !send(&mut socket, &ServerMessage::Reset).await {
+                            break;
+                        }
+                        continue;
+                    }
+                    None => {
+                        // the subscriber fell too far behind to keep up with the events
+                        let reason = "Subscription fell behind, subscribe again from the last event id";
+                        close(socket, close_code::AGAIN, reason).await;
+                        return;
+                    }
+                };
+
+                let Some(subscription) = subscription.as_ref() else {
+                    continue;
+                };
+                if !subscription.filter.matches(event.change.todo()) {
+                    continue;
+                }
+
+                let message = ServerMessage::Event {
+                    id: event.id,
+                    event: event.name(),
+                    todo: TodoView::from(event.change.todo().clone()),
+                };
+                if !send(&mut socket, &message).await {
+                    break;
+                }
+            }
+            _ = state.shutdown.cancelled() => {
+                close(socket, close_code::AWAY, "Server is shutting down").await;
+                return;
+            }
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > HEARTBEAT_TIMEOUT {
+                    tracing::info!(tenant.id = %tenant.id(), "Todo socket timed out");
+                    close(socket, close_code::AWAY, "Heartbeat timed out").await;
+                    return;
+                }
+
+                if !send_message(&mut socket, Message::Ping(Vec::new())).await {
+                    break;
+                }
+            }
+        }
+    }
+
+    tracing::info!(tenant.id = %tenant.id(), "Todo socket disconnected");
+}
+
+/// Wait for the next event of the subscription, or forever without one
+async fn next_event(subscription: &mut Option<Subscription>) -> Option<Received> {
+    match subscription {
+        Some(subscription) => subscription.events.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Run a create, update or delete command within the limits of its REST route
+async fn run_command(
+    state: &TodoState,
+    tenant: Tenant,
+    errors: &ErrorFormat,
+    limits: &Limits,
+    command: Command,
+) -> ServerMessage {
+    let request_id = command.request_id().cloned();
+    let (method, route) = command.route();
+
+    if let Some(rate) = &limits.rate {
+        let client = rate_limit::tenant_key(&tenant);
+        if let Some(decision) = rate.take(&client, &method, route).await {
+            if !decision.allowed {
+                return rejected(request_id, errors.error(429, decision.rejection()));
+            }
+        }
+    }
+
+    // the permit counts the command until it ran, like requests until they respond
+    let permit = limits
+        .concurrency
+        .as_ref()
+        .map(ConcurrencyLimit::try_acquire);
+    if let Some(None) = permit {
+        tracing::warn!("Shed todo socket command, too many requests in flight");
+        return rejected(request_id, errors.error(503, load::overloaded()));
+    }
+
+    let run = execute_command(state, tenant, errors, command);
+    let Some(duration) = limits.timeout.as_ref().map(RequestTimeout::request) else {
+        return run.await;
+    };
+    match time::timeout(duration, run).await {
+        Ok(reply) => reply,
+        Err(_) => {
+            tracing::warn!("Todo socket command timed out after {duration:?}");
+            rejected(request_id, errors.error(504, load::timed_out()))
+        }
+    }
+}
+
+/// Result of a command rejected before it ran
+fn rejected(request_id: Option<String>, err: JsonError) -> ServerMessage {
+    ServerMessage::Result {
+        request_id,
+        status: err.status(),
+        todo: None,
+        error: Some(err.content),
+    }
+}
+
+/// Run a create, update or delete command through its controller
+async fn execute_command(
+    state: &TodoState,
+    tenant: Tenant,
+    errors: &ErrorFormat,
+    command: Command,
+) -> ServerMessage {
+    let presenter = JsonTodoPresenter::new().with_error_format(errors.clone());
+    let repository = state.todo_repository.clone().with_tenant(tenant.id());
+    let dispatcher = state.todo_dispatcher.with_tenant(tenant.id());
+
+    let (request_id, result) = match command {
+        Command::Create(CreateCommand { request_id, body }) => {
+            let req = CreateRequest {
+                title: body.title,
+                description: body.description,
+                todo_at: body.todo_at,
+                status: body.status,
+            };
+
+            tracing::info!(
+                tenant.id = %tenant.id(),
+                todo.title = req.title.as_deref(),
+                todo.description = state.redaction.description(req.description.as_deref()),
+                todo.todo_at = req.todo_at.as_deref(),
+                todo.status = req.status.as_deref(),
+                "Create todo socket request"
+            );
+
+            let interactor = Measured::new(
+                "create_todo",
+                CreateTodoUseCase::new(repository, dispatcher),
+            );
+            let controller = CreateTodoController::new(interactor, presenter);
+            let result = controller.run(req).await.map(|todo| (201, Some(todo)));
+            (request_id, result)
+        }
+        Command::Update(UpdateCommand {
+            request_id,
+            id,
+            body,
+        }) => {
+            let req = UpdateRequest {
+                id,
+                title: body.title,
+                description: body.description,
+                todo_at: body.todo_at,
+                status: body.status,
+            };
+
+            tracing::info!(
+                tenant.id = %tenant.id(),
+                todo.id = req.id.as_deref(),
+                todo.title = req.title.as_deref(),
+                todo.description = state.redaction.description(req.description.as_deref()),
+                todo.todo_at = req.todo_at.as_deref(),
+                todo.status = req.status.as_deref(),
+                "Update todo socket request"
+            );
+
+            let interactor = Measured::new(
+                "update_todo",
+                UpdateTodoUseCase::new(repository, dispatcher),
+            );
+            let controller = UpdateTodoController::new(interactor, presenter);
+            let result = controller.run(req).await.map(|_| (200, None));
+            (request_id, result)
+        }
+        Command::Delete(DeleteCommand { request_id, id }) => {
+            let req = DeleteRequest { id };
+
+            tracing::info!(tenant.id = %tenant.id(), todo.id = req.id.as_deref(), "Delete todo socket request");
+
+            let interactor = Measured::new(
+                "delete_todo",
+                DeleteTodoUseCase::new(repository, dispatcher),
+            );
+            let controller = DeleteTodoController::new(interactor, presenter);
+            let result = controller.run(req).await.map(|_| (204, None));
+            (request_id, result)
+        }
+    };
+
+    match result {
+        Ok((status, todo)) => ServerMessage::Result {
+            request_id,
+            status,
+            todo,
+            error: None,
+        },
+        Err(err) => {
+            log_error(&err);
+            ServerMessage::Result {
+                request_id,
+                status: err.status(),
+                todo: None,
+                error: Some(err.content),
+            }
+        }
+    }
+}
+
+fn log_error(err: &JsonError) {
+    if let Some(src) = err.src() {
+        tracing::error!("Todo socket command internal error: {src}");
+    } else {
+        tracing::error!("Todo socket command error: {err:?}");
+    }
+}
+
+fn invalid_message(errors: &ErrorFormat, message: impl Into<String>) -> ServerMessage {
+    let err = errors.error(400, Content::new("InvalidMessage", message.into()));
+    ServerMessage::Error {
+        status: err.status(),
+        error: err.content,
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> bool {
+    match serde_json::to_string(message) {
+        Ok(text) => send_message(socket, Message::Text(text)).await,
+        Err(err) => {
+            tracing::error!("Failed encoding todo socket message: {err}");
+            true
+        }
+    }
+}
+
+/// Send `message`, giving up on clients that take too long to receive it
+async fn send_message(socket: &mut WebSocket, message: Message) -> bool {
+    match time::timeout(SEND_TIMEOUT, socket.send(message)).await {
+        Ok(Ok(())) => true,
+        Ok(Err(err)) => {
+            tracing::info!("Todo socket send error: {err}");
+            false
+        }
+        Err(_) => {
+            tracing::info!("Todo socket send timed out");
+            false
+        }
+    }
+}
+
+async fn close(mut socket: WebSocket, code: u16, reason: &'static str) {
+    let frame = CloseFrame {
+        code,
+        reason: Cow::Borrowed(reason),
+    };
+    _ = send_message(&mut socket, Message::Close(Some(frame))).await;
+}
+
+/// Parsed [`SubscribeFilter`]
+#[derive(Clone, Debug, Default)]
+struct Filter {
+    title: Option<String>,
+    status: Option<Status>,
+}
+
+impl Filter {
+    fn parse(filter: SubscribeFilter) -> Result<Self, String> {
+        let status = filter
+            .status
+            .map(|status| Status::parse_str(&status))
+            .transpose()
+            .map_err(|err| err.to_string())?;
+
+        Ok(Self {
+            title: filter.title.map(|title| title.to_lowercase()),
+            status,
+        })
+    }
+
+    fn matches(&self, todo: &TodoEntity) -> bool {
+        let title_matches = self
+            .title
+            .as_ref()
+            .is_none_or(|title| todo.title().as_str().to_lowercase().contains(title));
+        let status_matches = self
+            .status
+            .as_ref()
+            .is_none_or(|status| todo.status() == status);
+
+        title_matches && status_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    use axum::{middleware, Router};
+    use sqlx::postgres::PgPoolOptions;
+    use sqlx::PgPool;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::tungstenite::Message as ClientFrame;
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::application::publishers::todo::TodoChange;
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::types::Id;
+    use crate::framework::config::{RateLimitBackend, RateLimitConfig};
+    use crate::framework::events::listener;
+    use crate::framework::events::todo::{TodoBroadcaster, TodoEvent};
+    use crate::framework::outbox::relay::{self, RelayConfig};
+    use crate::framework::outbox::sinks::BroadcastSink;
+    use crate::framework::rate_limit::memory::MemoryStore;
+    use crate::framework::rate_limit::{Quota, RouteQuota};
+    use crate::framework::rest_api::redaction::Redaction;
+    use crate::framework::rest_api::routes::todo;
+    use crate::framework::rest_api::tenant::{self, TENANT_HEADER};
+
+    async fn serve(events: TodoBroadcaster) -> SocketAddr {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        serve_with_pool(pool, events).await
+    }
+
+    async fn serve_with_pool(pool: PgPool, events: TodoBroadcaster) -> SocketAddr {
+        let sink = Arc::new(BroadcastSink::new(pool.clone()));
+        let shutdown = CancellationToken::new();
+        relay::start(
+            pool.clone(),
+            RelayConfig::default(),
+            vec![sink],
+            shutdown.clone(),
+        );
+        listener::start(pool.clone(), events.clone(), shutdown.clone());
+        let app = Router::new()
+            .merge(todo::create_router(
+                pool,
+                events,
+                shutdown,
+                Redaction::new(true),
+                64 << 10,
+            ))
+            .layer(middleware::from_fn_with_state(
+                tenant::tests::trusting_header(),
+                tenant::authenticate,
+            ));
+        serve_app(app).await
+    }
+
+    async fn serve_app(app: Router) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app.into_make_service()).await });
+        addr
+    }
+
+    async fn next_text<S>(socket: &mut S) -> Value
+    where
+        S: Stream<Item = Result<ClientFrame, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        loop {
+            match socket.next().await.unwrap().unwrap() {
+                ClientFrame::Text(text) => return serde_json::from_str(&text).unwrap(),
+                ClientFrame::Ping(_) | ClientFrame::Pong(_) => continue,
+                frame => panic!("Unexpected frame {frame:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn filter_matches_title_and_status() {
+        let filter = Filter::parse(SubscribeFilter {
+            title: Some(String::from("GROCER")),
+            status: Some(String::from("todo")),
+        })
+        .unwrap();
+
+        assert!(filter.matches(&todo("Buy groceries", Status::Todo)));
+        assert!(!filter.matches(&todo("Buy groceries", Status::Done)));
+        assert!(!filter.matches(&todo("Walk the dog", Status::Todo)));
+        assert!(Filter::default().matches(&todo("Walk the dog", Status::Done)));
+
+        let invalid = SubscribeFilter {
+            title: None,
+            status: Some(String::from("unknown")),
+        };
+        assert!(Filter::parse(invalid).is_err());
+    }
+
+    #[tokio::test]
+    async fn subscriber_receives_tenant_events() {
+        use futures_util::SinkExt;
+
+        let events = TodoBroadcaster::new(8);
+        let addr = serve(events.clone()).await;
+        let tenant_id = Id::new();
+
+        let mut req = format!("ws://{addr}/ws").into_client_request().unwrap();
+        req.headers_mut()
+            .insert(TENANT_HEADER, tenant_id.to_string().parse().unwrap());
+        let (mut socket, _) = tokio_tungstenite::connect_async(req).await.unwrap();
+
+        let subscribe = r#"{ "type": "subscribe", "filter": { "status": "todo" } }"#;
+        socket
+            .send(ClientFrame::Text(subscribe.into()))
+            .await
+            .unwrap();
+        assert_eq!(next_text(&mut socket).await["type"], "subscribed");
+
+        let tenant = events.clone().with_tenant(tenant_id);
+        let published = [
+            (events.with_tenant(Id::new()), todo("Other", Status::Todo)),
+            (tenant.clone(), todo("Filtered", Status::Done)),
+            (tenant, todo("Matching", Status::Todo)),
+        ];
+        for (id, (tenant, todo)) in (1..).zip(published) {
+            tenant.publish(TodoEvent {
+                id,
+                change: TodoChange::Created(todo),
+            });
+        }
+
+        let event = next_text(&mut socket).await;
+        assert_eq!(event["type"], "event");
+        assert!(event["id"].is_u64());
+        assert_eq!(event["event"], "todo.created");
+        assert_eq!(event["todo"]["title"], "Matching");
+
+        socket.send(ClientFrame::Binary(vec![1])).await.unwrap();
+        let error = next_text(&mut socket).await;
+        assert_eq!(error["type"], "error");
+        assert_eq!(error["error"]["code"], "InvalidMessage");
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn commands_run_through_use_cases(pool: PgPool) {
+        use futures_util::SinkExt;
+
+        let addr = serve_with_pool(pool, TodoBroadcaster::new(8)).await;
+        let mut req = format!("ws://{addr}/ws").into_client_request().unwrap();
+        req.headers_mut()
+            .insert(TENANT_HEADER, Id::new().to_string().parse().unwrap());
+        let (mut socket, _) = tokio_tungstenite::connect_async(req).await.unwrap();
+
+        let subscribe = r#"{ "type": "subscribe" }"#;
+        socket
+            .send(ClientFrame::Text(subscribe.into()))
+            .await
+            .unwrap();
+        assert_eq!(next_text(&mut socket).await["type"], "subscribed");
+
+        let create = r#"{ "type": "create", "requestId": "1", "body": { "title": "Socket", "status": "todo" } }"#;
+        socket.send(ClientFrame::Text(create.into())).await.unwrap();
+        let created = next_text(&mut socket).await;
+        assert_eq!(created["type"], "result");
+        assert_eq!(created["requestId"], "1");
+        assert_eq!(created["status"], 201);
+        let id = created["todo"]["id"].as_str().unwrap().to_string();
+
+        let event = next_text(&mut socket).await;
+        assert_eq!(event["event"], "todo.created");
+        assert_eq!(event["todo"]["id"], id.as_str());
+
+        let update = format!(
+            r#"{{ "type": "update", "requestId": "2", "id": "{id}", "body": {{ "title": "", "status": "done" }} }}"#
+        );
+        socket.send(ClientFrame::Text(update)).await.unwrap();
+        let invalid = next_text(&mut socket).await;
+        assert_eq!(invalid["status"], 400);
+        assert_eq!(invalid["error"]["code"], "ParseError");
+
+        let delete = format!(r#"{{ "type": "delete", "requestId": "3", "id": "{id}" }}"#);
+        socket.send(ClientFrame::Text(delete)).await.unwrap();
+        assert_eq!(next_text(&mut socket).await["status"], 204);
+        assert_eq!(next_text(&mut socket).await["event"], "todo.deleted");
+    }
+
+    #[tokio::test]
+    async fn commands_share_the_rate_limit_of_their_route() {
+        use futures_util::SinkExt;
+
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        let config = RateLimitConfig {
+            backend: RateLimitBackend::Memory,
+            default_quota: None,
+            routes: vec![RouteQuota {
+                method: Method::POST,
+                route: String::from("/todos"),
+                quota: Some(Quota {
+                    limit: 1,
+                    period: Duration::from_secs(60),
+                }),
+            }],
+        };
+        let limiter = RateLimiter::new(Arc::new(MemoryStore::new()), &config);
+        let app = todo::create_router(
+            pool,
+            TodoBroadcaster::new(1),
+            CancellationToken::new(),
+            Redaction::new(true),
+            64 << 10,
+        )
+        .layer(middleware::from_fn_with_state(limiter, rate_limit::limit))
+        .layer(middleware::from_fn_with_state(
+            tenant::tests::trusting_header(),
+            tenant::authenticate,
+        ));
+        let addr = serve_app(app).await;
+
+        let mut req = format!("ws://{addr}/ws").into_client_request().unwrap();
+        req.headers_mut()
+            .insert(TENANT_HEADER, Id::new().to_string().parse().unwrap());
+        let (mut socket, _) = tokio_tungstenite::connect_async(req).await.unwrap();
+
+        // invalid commands are rejected before reaching the database
+        let create = r#"{ "type": "create", "requestId": "1", "body": { "title": "" } }"#;
+        let update = r#"{ "type": "update", "requestId": "2", "id": "1", "body": {} }"#;
+        for (command, status, code) in [
+            (create, 400, "ParseError"),
+            (create, 429, "RateLimited"),
+            (update, 400, "ParseError"),
+        ] {
+            socket
+                .send(ClientFrame::Text(command.into()))
+                .await
+                .unwrap();
+            let result = next_text(&mut socket).await;
+            assert_eq!(result["type"], "result");
+            assert_eq!(result["status"], status, "{command}");
+            assert_eq!(result["error"]["code"], code, "{command}");
+        }
+    }
+
+    #[tokio::test]
+    async fn browsers_offer_their_token_as_a_subprotocol() {
+        use futures_util::SinkExt;
+
+        let addr = serve(TodoBroadcaster::new(1)).await;
+        let mut req = format!("ws://{addr}/ws").into_client_request().unwrap();
+        let protocols = format!("bearer, {}", tenant::tests::token_of(Id::new()));
+        req.headers_mut()
+            .insert("sec-websocket-protocol", protocols.parse().unwrap());
+        let (mut socket, res) = tokio_tungstenite::connect_async(req).await.unwrap();
+        assert_eq!(res.headers()["sec-websocket-protocol"], "bearer");
+
+        let unknown = r#"{ "type": "publish" }"#;
+        socket
+            .send(ClientFrame::Text(unknown.into()))
+            .await
+            .unwrap();
+        let error = next_text(&mut socket).await;
+        assert_eq!(error["type"], "error");
+        assert_eq!(error["error"]["code"], "InvalidMessage");
+    }
+
+    #[tokio::test]
+    async fn socket_requires_tenant() {
+        let addr = serve(TodoBroadcaster::new(1)).await;
+        let req = format!("ws://{addr}/ws").into_client_request().unwrap();
+
+        let err = tokio_tungstenite::connect_async(req).await.unwrap_err();
+        assert!(matches!(
+            err,
+            tokio_tungstenite::tungstenite::Error::Http(res) if res.status() == 401
+        ));
+    }
+}
@@ -0,0 +1,117 @@
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use super::WebhookState;
+use crate::adapters::controllers::webhook::create::CreateWebhookController;
+use crate::adapters::dtos::webhook::create::CreateRequest;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::webhook::{JsonWebhookPresenter, WebhookView};
+use crate::application::use_cases::webhook::create::CreateWebhookUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Decoded, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
+
+#[derive(Clone, Debug, Deserialize, ToSchema)]
+pub(super) struct CreateBody {
+    /// Absolute `https` url that receives the deliveries, which must resolve
+    /// to a public address
+    #[schema(required = true, example = "https://example.com/hooks/todos")]
+    pub(super) url: Option<String>,
+    /// Any of `todo.created`, `todo.updated` or `todo.deleted`, every event when empty
+    #[schema(example = json!(["todo.created"]))]
+    pub(super) events: Option<Vec<String>>,
+    /// Between 16 and 256 characters used to sign the deliveries, never returned back
+    #[schema(required = true, example = "whsec_0123456789abcdef")]
+    pub(super) secret: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = "webhooks",
+    params(Tenant),
+    request_body(content(
+        (CreateBody = "application/json"),
+        (CreateBody = "application/msgpack"),
+        (CreateBody = "application/cbor"),
+    )),
+    responses(
+        (status = 201, description = "Webhook created", content(
+            (WebhookView = "application/json"),
+            (WebhookView = "application/msgpack"),
+            (WebhookView = "application/cbor"),
+        ), headers(
+            ("location" = String, description = "Path of the webhook deliveries")
+        )),
+        (status = 400, description = "`ParseError`, `InvalidBody` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 415, description = "`UnsupportedMediaType`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn create_webhook(
+    State(state): State<WebhookState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Decoded(body): Decoded<CreateBody>,
+) -> impl IntoResponse {
+    let req = CreateRequest {
+        url: body.url,
+        events: body.events,
+        secret: body.secret,
+    };
+
+    tracing::info!(tenant.id = %tenant.id(), webhook.url = req.url.as_deref(), "Create webhook request");
+
+    let presenter = JsonWebhookPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "create_webhook",
+        CreateWebhookUseCase::new(state.webhook_repository.with_tenant(tenant.id())),
+    );
+    let controller = CreateWebhookController::new(interactor, presenter);
+    let output = match controller.run(req).await {
+        Ok(output) => output,
+        Err(err) => {
+            if let Some(src) = err.src() {
+                tracing::error!("Create webhook internal error: {src}");
+            } else {
+                tracing::error!("Create webhook error: {err:?}");
+            }
+
+            let status = match StatusCode::from_u16(err.status()) {
+                Ok(status) => status,
+                Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            return (status, Encoded::error(format, err)).into_response();
+        }
+    };
+
+    let mut headers = header::HeaderMap::new();
+    let location = format!("/webhooks/{}/deliveries", output.id);
+    if let Ok(location) = location.parse::<header::HeaderValue>() {
+        headers.insert(header::LOCATION, location);
+    }
+
+    (StatusCode::CREATED, headers, Encoded::new(format, output)).into_response()
+}
@@ -0,0 +1,87 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::WebhookState;
+use crate::adapters::controllers::webhook::delete::DeleteWebhookController;
+use crate::adapters::dtos::webhook::delete::DeleteRequest;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::webhook::JsonWebhookPresenter;
+use crate::application::use_cases::webhook::delete::DeleteWebhookUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
+
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub(super) struct DeletePathParams {
+    /// Id of the webhook
+    #[param(required = true, format = Uuid)]
+    id: Option<String>,
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(Tenant, DeletePathParams),
+    responses(
+        (status = 204, description = "Webhook and its deliveries deleted"),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 404, description = "`NotFound`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn delete_webhook(
+    State(state): State<WebhookState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Path(path): Path<DeletePathParams>,
+) -> impl IntoResponse {
+    let req = DeleteRequest { id: path.id };
+
+    tracing::info!(tenant.id = %tenant.id(), webhook.id = req.id.as_deref(), "Delete webhook request");
+
+    let presenter = JsonWebhookPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "delete_webhook",
+        DeleteWebhookUseCase::new(state.webhook_repository.with_tenant(tenant.id())),
+    );
+    let controller = DeleteWebhookController::new(interactor, presenter);
+    if let Err(err) = controller.run(req).await {
+        if let Some(src) = err.src() {
+            tracing::error!("Delete webhook internal error: {src}");
+        } else {
+            tracing::error!("Delete webhook error: {err:?}");
+        }
+
+        let status = match StatusCode::from_u16(err.status()) {
+            Ok(status) => status,
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        return (status, Encoded::error(format, err)).into_response();
+    }
+
+    (StatusCode::NO_CONTENT).into_response()
+}
@@ -0,0 +1,110 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::WebhookState;
+use crate::adapters::controllers::webhook::deliveries::ListDeliveriesController;
+use crate::adapters::dtos::webhook::deliveries::DeliveriesRequest;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::webhook::{DeliveriesListView, JsonWebhookPresenter};
+use crate::application::use_cases::webhook::deliveries::ListDeliveriesUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
+
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub(super) struct DeliveriesPathParams {
+    /// Id of the webhook
+    #[param(required = true, format = Uuid)]
+    id: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub(super) struct QueryParams {
+    /// Only list deliveries that are `pending`, `delivered` or `dead`
+    status: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(Tenant, DeliveriesPathParams, QueryParams),
+    responses(
+        (status = 200, description = "Latest 100 deliveries of the webhook with their attempts", content(
+            (DeliveriesListView = "application/json"),
+            (DeliveriesListView = "application/msgpack"),
+            (DeliveriesListView = "application/cbor"),
+        )),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 404, description = "`NotFound`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn list_deliveries(
+    State(state): State<WebhookState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Path(path): Path<DeliveriesPathParams>,
+    Query(query): Query<QueryParams>,
+) -> impl IntoResponse {
+    let req = DeliveriesRequest {
+        webhook_id: path.id,
+        status: query.status,
+    };
+
+    tracing::info!(
+        tenant.id = %tenant.id(),
+        webhook.id = req.webhook_id.as_deref(),
+        status = req.status.as_deref(),
+        "List webhook deliveries request"
+    );
+
+    let presenter = JsonWebhookPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "list_deliveries",
+        ListDeliveriesUseCase::new(state.webhook_repository.with_tenant(tenant.id())),
+    );
+    let controller = ListDeliveriesController::new(interactor, presenter);
+    let output = match controller.run(req).await {
+        Ok(output) => output,
+        Err(err) => {
+            if let Some(src) = err.src() {
+                tracing::error!("List webhook deliveries internal error: {src}");
+            } else {
+                tracing::error!("List webhook deliveries error: {err:?}");
+            }
+
+            let status = match StatusCode::from_u16(err.status()) {
+                Ok(status) => status,
+                Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            return (status, Encoded::error(format, err)).into_response();
+        }
+    };
+
+    (StatusCode::OK, Encoded::new(format, output)).into_response()
+}
@@ -0,0 +1,76 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+
+use super::WebhookState;
+use crate::adapters::controllers::webhook::list::ListWebhooksController;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::webhook::{JsonWebhookPresenter, WebhooksListView};
+use crate::application::use_cases::webhook::list::ListWebhooksUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "webhooks",
+    params(Tenant),
+    responses(
+        (status = 200, description = "Webhooks of the tenant, without their secrets", content(
+            (WebhooksListView = "application/json"),
+            (WebhooksListView = "application/msgpack"),
+            (WebhooksListView = "application/cbor"),
+        )),
+        (status = 400, description = "`InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn list_webhooks(
+    State(state): State<WebhookState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+) -> impl IntoResponse {
+    tracing::info!(tenant.id = %tenant.id(), "List webhooks request");
+
+    let presenter = JsonWebhookPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "list_webhooks",
+        ListWebhooksUseCase::new(state.webhook_repository.with_tenant(tenant.id())),
+    );
+    let controller = ListWebhooksController::new(interactor, presenter);
+    let output = match controller.run().await {
+        Ok(output) => output,
+        Err(err) => {
+            if let Some(src) = err.src() {
+                tracing::error!("List webhooks internal error: {src}");
+            } else {
+                tracing::error!("List webhooks error: {err:?}");
+            }
+
+            let status = match StatusCode::from_u16(err.status()) {
+                Ok(status) => status,
+                Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            return (status, Encoded::error(format, err)).into_response();
+        }
+    };
+
+    (StatusCode::OK, Encoded::new(format, output)).into_response()
+}
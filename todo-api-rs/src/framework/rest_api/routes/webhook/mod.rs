@@ -0,0 +1,69 @@
+mod create;
+mod delete;
+mod deliveries;
+mod list;
+mod replay;
+mod update;
+
+use axum::routing::{delete, get, post};
+use axum::Router;
+use sqlx::{Pool, Postgres};
+use utoipa::OpenApi;
+
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::{FieldError, Problem};
+use crate::adapters::presenters::json::webhook::{
+    DeliveriesListView, DeliveryAttemptView, DeliveryView, WebhookView, WebhooksListView,
+};
+use crate::framework::storage::repositories::webhook::PgWebhookRepository;
+
+use create::{create_webhook, CreateBody};
+use delete::delete_webhook;
+use deliveries::list_deliveries;
+use list::list_webhooks;
+use replay::replay_delivery;
+use update::{update_webhook, UpdateBody};
+
+pub fn create_router(pool: Pool<Postgres>) -> Router {
+    let state = WebhookState {
+        webhook_repository: PgWebhookRepository::new(pool),
+    };
+
+    Router::new()
+        .route("/webhooks", post(create_webhook).get(list_webhooks))
+        .route("/webhooks/:id", delete(delete_webhook).put(update_webhook))
+        .route("/webhooks/:id/deliveries", get(list_deliveries))
+        .route("/webhooks/deliveries/:id/replay", post(replay_delivery))
+        .with_state(state)
+}
+
+#[derive(Clone)]
+struct WebhookState {
+    webhook_repository: PgWebhookRepository,
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create::create_webhook,
+        list::list_webhooks,
+        update::update_webhook,
+        delete::delete_webhook,
+        deliveries::list_deliveries,
+        replay::replay_delivery,
+    ),
+    components(schemas(
+        CreateBody,
+        UpdateBody,
+        WebhookView,
+        WebhooksListView,
+        DeliveryView,
+        DeliveryAttemptView,
+        DeliveriesListView,
+        Content,
+        Problem,
+        FieldError
+    )),
+    tags((name = "webhooks", description = "Subscribe to todo changes pushed to your own endpoints"))
+)]
+pub struct WebhookApi;
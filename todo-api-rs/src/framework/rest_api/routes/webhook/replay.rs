@@ -0,0 +1,87 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::WebhookState;
+use crate::adapters::controllers::webhook::replay::ReplayDeliveryController;
+use crate::adapters::dtos::webhook::replay::ReplayRequest;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::webhook::JsonWebhookPresenter;
+use crate::application::use_cases::webhook::replay::ReplayDeliveryUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
+
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub(super) struct ReplayPathParams {
+    /// Id of the delivery
+    #[param(required = true, format = Uuid)]
+    id: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/webhooks/deliveries/{id}/replay",
+    tag = "webhooks",
+    params(Tenant, ReplayPathParams),
+    responses(
+        (status = 202, description = "Delivery is pending again, with a fresh set of attempts"),
+        (status = 400, description = "`ParseError` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 404, description = "`NotFound`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn replay_delivery(
+    State(state): State<WebhookState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Path(path): Path<ReplayPathParams>,
+) -> impl IntoResponse {
+    let req = ReplayRequest { id: path.id };
+
+    tracing::info!(tenant.id = %tenant.id(), delivery.id = req.id.as_deref(), "Replay webhook delivery request");
+
+    let presenter = JsonWebhookPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "replay_delivery",
+        ReplayDeliveryUseCase::new(state.webhook_repository.with_tenant(tenant.id())),
+    );
+    let controller = ReplayDeliveryController::new(interactor, presenter);
+    if let Err(err) = controller.run(req).await {
+        if let Some(src) = err.src() {
+            tracing::error!("Replay webhook delivery internal error: {src}");
+        } else {
+            tracing::error!("Replay webhook delivery error: {err:?}");
+        }
+
+        let status = match StatusCode::from_u16(err.status()) {
+            Ok(status) => status,
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        return (status, Encoded::error(format, err)).into_response();
+    }
+
+    (StatusCode::ACCEPTED).into_response()
+}
@@ -0,0 +1,121 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use super::WebhookState;
+use crate::adapters::controllers::webhook::update::UpdateWebhookController;
+use crate::adapters::dtos::webhook::update::UpdateRequest;
+use crate::adapters::presenters::json::error::Content;
+use crate::adapters::presenters::json::problem::Problem;
+use crate::adapters::presenters::json::webhook::{JsonWebhookPresenter, WebhookView};
+use crate::application::use_cases::webhook::update::UpdateWebhookUseCase;
+use crate::framework::metrics::Measured;
+use crate::framework::rest_api::negotiation::{Accepted, AcceptedErrorFormat, Decoded, Encoded};
+use crate::framework::rest_api::tenant::Tenant;
+
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Path)]
+pub(super) struct UpdatePathParams {
+    /// Id of the webhook
+    #[param(required = true, format = Uuid)]
+    id: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, ToSchema)]
+pub(super) struct UpdateBody {
+    /// Absolute `https` url that receives the deliveries, which must resolve
+    /// to a public address
+    #[schema(required = true, example = "https://example.com/hooks/todos")]
+    pub(super) url: Option<String>,
+    /// Any of `todo.created`, `todo.updated` or `todo.deleted`, every event when empty
+    #[schema(example = json!(["todo.created"]))]
+    pub(super) events: Option<Vec<String>>,
+    /// Between 16 and 256 characters used to sign the deliveries, never returned back
+    #[schema(required = true, example = "whsec_0123456789abcdef")]
+    pub(super) secret: Option<String>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(Tenant, UpdatePathParams),
+    request_body(content(
+        (UpdateBody = "application/json"),
+        (UpdateBody = "application/msgpack"),
+        (UpdateBody = "application/cbor"),
+    )),
+    responses(
+        (status = 200, description = "Webhook updated, and enabled again when it was disabled", content(
+            (WebhookView = "application/json"),
+            (WebhookView = "application/msgpack"),
+            (WebhookView = "application/cbor"),
+        )),
+        (status = 400, description = "`ParseError`, `InvalidBody` or `InvalidTenant`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 401, description = "`Unauthenticated` or `InvalidToken`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 404, description = "`NotFound`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 406, description = "`NotAcceptable`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 415, description = "`UnsupportedMediaType`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+        (status = 500, description = "`InternalError`", content(
+            (Content = "application/json"),
+            (Problem = "application/problem+json"),
+        )),
+    )
+)]
+pub(super) async fn update_webhook(
+    State(state): State<WebhookState>,
+    tenant: Tenant,
+    Accepted(format): Accepted,
+    AcceptedErrorFormat(errors): AcceptedErrorFormat,
+    Path(path): Path<UpdatePathParams>,
+    Decoded(body): Decoded<UpdateBody>,
+) -> impl IntoResponse {
+    let req = UpdateRequest {
+        id: path.id,
+        url: body.url,
+        events: body.events,
+        secret: body.secret,
+    };
+
+    tracing::info!(tenant.id = %tenant.id(), webhook.id = req.id.as_deref(), webhook.url = req.url.as_deref(), "Update webhook request");
+
+    let presenter = JsonWebhookPresenter::new().with_error_format(errors);
+    let interactor = Measured::new(
+        "update_webhook",
+        UpdateWebhookUseCase::new(state.webhook_repository.with_tenant(tenant.id())),
+    );
+    let controller = UpdateWebhookController::new(interactor, presenter);
+    match controller.run(req).await {
+        Ok(output) => (StatusCode::OK, Encoded::new(format, output)).into_response(),
+        Err(err) => {
+            if let Some(src) = err.src() {
+                tracing::error!("Update webhook internal error: {src}");
+            } else {
+                tracing::error!("Update webhook error: {err:?}");
+            }
+
+            let status = match StatusCode::from_u16(err.status()) {
+                Ok(status) => status,
+                Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Encoded::error(format, err)).into_response()
+        }
+    }
+}
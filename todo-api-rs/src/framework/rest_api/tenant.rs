@@ -0,0 +1,346 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+use axum::extract::{FromRequestParts, Request, State};
+use axum::http::request::Parts;
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde_json::{Map, Value};
+use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
+use utoipa::openapi::{KnownFormat, ObjectBuilder, Required, SchemaFormat, Type};
+use utoipa::IntoParams;
+
+use crate::adapters::presenters::json::error::{Content, JsonError};
+use crate::domain::types::Id;
+use crate::framework::config::AuthConfig;
+use crate::framework::rest_api::negotiation::{error_format, rejection};
+
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// Websocket subprotocol offered along with the bearer token by clients that
+/// cannot set headers on the handshake, such as browsers, which offer
+/// `bearer, <token>` as their subprotocols
+pub const BEARER_PROTOCOL: &str = "bearer";
+
+/// Tenant that owns the request, resolved by [`authenticate`] from the claim
+/// of a verified bearer token, or from the `X-Tenant-Id` header when the
+/// server trusts the gateway in front of it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tenant(Id);
+
+impl Tenant {
+    pub fn id(&self) -> Id {
+        self.0
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Tenant {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Self>().copied().ok_or_else(|| {
+            let message = "Request must have a bearer token identifying its tenant";
+            let content = Content::new("Unauthenticated", message);
+            unauthorized(error_format(&parts.headers, &parts.uri).error(401, content))
+        })
+    }
+}
+
+impl IntoParams for Tenant {
+    fn into_params(_parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        let schema = ObjectBuilder::new()
+            .schema_type(Type::String)
+            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Uuid)));
+
+        let header = ParameterBuilder::new()
+            .name(TENANT_HEADER)
+            .parameter_in(ParameterIn::Header)
+            .required(Required::False)
+            .description(Some(
+                "Id of the tenant that owns the todos, only read when the server trusts the gateway in front of it. Clients otherwise send a bearer token with the id in its tenant claim",
+            ))
+            .schema(Some(schema))
+            .build();
+
+        vec![header]
+    }
+}
+
+/// Verifies the credentials of requests to resolve their tenant
+#[derive(Clone)]
+pub struct TenantAuth {
+    shared: Arc<Verifier>,
+}
+
+struct Verifier {
+    key: Option<DecodingKey>,
+    validation: Validation,
+    claim: String,
+    trust_header: bool,
+}
+
+/// Credentials of a request that failed to identify a tenant
+#[derive(Debug, PartialEq, Eq)]
+enum Credentials {
+    /// Bearer token that is malformed, expired or not signed with the secret
+    InvalidToken(String),
+    /// Trusted `X-Tenant-Id` header which is not an id
+    InvalidHeader,
+}
+
+impl TenantAuth {
+    pub fn new(config: &AuthConfig) -> Self {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_required_spec_claims(&["exp"]);
+
+        Self {
+            shared: Arc::new(Verifier {
+                key: config
+                    .jwt_secret
+                    .as_ref()
+                    .map(|secret| DecodingKey::from_secret(&secret.0)),
+                validation,
+                claim: config.tenant_claim.clone(),
+                trust_header: config.trust_tenant_header,
+            }),
+        }
+    }
+
+    /// Tenant of a request, none when it has no credentials
+    fn resolve(&self, headers: &HeaderMap) -> Result<Option<Tenant>, Credentials> {
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map(|(_, token)| token.trim())
+            .or_else(|| protocol_token(headers));
+        if let Some(token) = bearer {
+            return self.verify(token).map(Some);
+        }
+
+        let verifier = &self.shared;
+        match headers.get(TENANT_HEADER) {
+            Some(value) if verifier.trust_header => value
+                .to_str()
+                .ok()
+                .and_then(|value| Id::parse_str(value).ok())
+                .map(|id| Some(Tenant(id)))
+                .ok_or(Credentials::InvalidHeader),
+            _ => Ok(None),
+        }
+    }
+
+    /// Tenant in the claim of a bearer token signed with the secret
+    fn verify(&self, token: &str) -> Result<Tenant, Credentials> {
+        let verifier = &self.shared;
+        let Some(key) = &verifier.key else {
+            return Err(Credentials::InvalidToken(String::from(
+                "bearer tokens are not accepted by this server",
+            )));
+        };
+
+        let claims = jsonwebtoken::decode::<Map<String, Value>>(token, key, &verifier.validation)
+            .map_err(|err| Credentials::InvalidToken(err.to_string()))?
+            .claims;
+
+        claims
+            .get(&verifier.claim)
+            .and_then(Value::as_str)
+            .and_then(|id| Id::parse_str(id).ok())
+            .map(Tenant)
+            .ok_or_else(|| {
+                Credentials::InvalidToken(format!("claim {} must be a tenant id", verifier.claim))
+            })
+    }
+}
+
+/// Bearer token offered after [`BEARER_PROTOCOL`] in the subprotocols of a
+/// websocket handshake
+fn protocol_token(headers: &HeaderMap) -> Option<&str> {
+    let mut protocols = headers
+        .get(header::SEC_WEBSOCKET_PROTOCOL)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .map(str::trim);
+
+    protocols.find(|protocol| *protocol == BEARER_PROTOCOL)?;
+    protocols.next()
+}
+
+/// Middleware resolving the [`Tenant`] of requests with credentials, and
+/// rejecting the ones with invalid credentials. Requests without any are let
+/// through, and rejected by the routes that need a tenant
+pub async fn authenticate(
+    State(auth): State<TenantAuth>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let errors = error_format(req.headers(), req.uri());
+    match auth.resolve(req.headers()) {
+        Ok(Some(tenant)) => {
+            req.extensions_mut().insert(tenant);
+        }
+        Ok(None) => {}
+        Err(Credentials::InvalidToken(reason)) => {
+            tracing::info!(reason, "Rejected invalid bearer token");
+
+            let content = Content::new("InvalidToken", "Bearer token is invalid or expired");
+            return unauthorized(errors.error(401, content));
+        }
+        Err(Credentials::InvalidHeader) => {
+            let message = format!("Header {TENANT_HEADER} must contain a valid tenant id");
+            let content = Content::new("InvalidTenant", message);
+            return rejection(errors.error(400, content));
+        }
+    }
+
+    next.run(req).await
+}
+
+fn unauthorized(err: JsonError) -> Response {
+    let mut res = rejection(err);
+    res.headers_mut()
+        .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    res
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::{middleware, Router};
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::framework::config::JwtSecret;
+
+    const SECRET: &[u8] = b"a secret of at least thirty two bytes";
+
+    /// Auth of routers under test, which trusts the `X-Tenant-Id` header and
+    /// verifies the tokens of [`token_of`]
+    pub(crate) fn trusting_header() -> TenantAuth {
+        TenantAuth::new(&AuthConfig {
+            jwt_secret: Some(JwtSecret(SECRET.to_vec())),
+            tenant_claim: String::from("tenant_id"),
+            trust_tenant_header: true,
+        })
+    }
+
+    /// Valid bearer token of a tenant
+    pub(crate) fn token_of(tenant_id: Id) -> String {
+        token(
+            json!({ "tenant_id": tenant_id.to_string(), "exp": expiring_in(60) }),
+            SECRET,
+        )
+    }
+
+    fn token(claims: Value, secret: &[u8]) -> String {
+        let key = EncodingKey::from_secret(secret);
+        jsonwebtoken::encode(&Header::default(), &claims, &key).unwrap()
+    }
+
+    fn expiring_in(seconds: i64) -> i64 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        now.as_secs() as i64 + seconds
+    }
+
+    async fn tenant_of(trust_header: bool, headers: &[(&str, String)]) -> (StatusCode, String) {
+        let auth = TenantAuth::new(&AuthConfig {
+            jwt_secret: Some(JwtSecret(SECRET.to_vec())),
+            tenant_claim: String::from("tenant_id"),
+            trust_tenant_header: trust_header,
+        });
+        let app = Router::new()
+            .route(
+                "/todos",
+                get(|tenant: Tenant| async move { tenant.id().to_string() }),
+            )
+            .layer(middleware::from_fn_with_state(auth, authenticate));
+
+        let mut req = Request::builder().uri("/todos");
+        for (name, value) in headers {
+            req = req.header(*name, value);
+        }
+        let res = app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+
+        let status = res.status();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn tenant_is_the_claim_of_a_verified_token() {
+        let tenant_id = Id::new().to_string();
+        let valid = token(
+            json!({ "tenant_id": tenant_id, "exp": expiring_in(60) }),
+            SECRET,
+        );
+        let (status, body) =
+            tenant_of(false, &[("authorization", format!("Bearer {valid}"))]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, tenant_id);
+
+        let forged = token(
+            json!({ "tenant_id": tenant_id, "exp": expiring_in(60) }),
+            b"another secret of thirty two bytes!",
+        );
+        let expired = token(
+            json!({ "tenant_id": tenant_id, "exp": expiring_in(-3600) }),
+            SECRET,
+        );
+        let without_claim = token(json!({ "sub": tenant_id, "exp": expiring_in(60) }), SECRET);
+        for token in [forged, expired, without_claim] {
+            let (status, body) =
+                tenant_of(false, &[("authorization", format!("Bearer {token}"))]).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert!(body.contains("InvalidToken"), "{body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn token_may_be_offered_as_a_subprotocol() {
+        let tenant_id = Id::new().to_string();
+        let valid = token(
+            json!({ "tenant_id": tenant_id, "exp": expiring_in(60) }),
+            SECRET,
+        );
+
+        let protocols = format!("{BEARER_PROTOCOL}, {valid}");
+        let (status, body) = tenant_of(false, &[("sec-websocket-protocol", protocols)]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, tenant_id);
+
+        let without_token = String::from(BEARER_PROTOCOL);
+        let (status, _) = tenant_of(false, &[("sec-websocket-protocol", without_token)]).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn header_is_only_read_when_trusted() {
+        let tenant_id = Id::new().to_string();
+        let header = [(TENANT_HEADER, tenant_id.clone())];
+
+        let (status, body) = tenant_of(false, &header).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains("Unauthenticated"), "{body}");
+
+        let (status, body) = tenant_of(true, &header).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, tenant_id);
+
+        let (status, _) = tenant_of(true, &[(TENANT_HEADER, String::from("1"))]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
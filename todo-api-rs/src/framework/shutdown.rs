@@ -0,0 +1,24 @@
+use tokio::signal;
+
+/// Wait for SIGINT or, on unix, SIGTERM and return the name of the one
+/// received
+pub async fn signal() -> &'static str {
+    let interrupt = async {
+        signal::ctrl_c().await.expect("Failed listening for SIGINT");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed listening for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => "SIGINT",
+        _ = terminate => "SIGTERM",
+    }
+}
@@ -0,0 +1,30 @@
+use std::collections::HashSet;
+
+use sqlx::{Error as SqlxError, PgPool};
+
+use super::MIGRATOR;
+
+/// Run the cheapest query there is, to check the database answers
+pub async fn ping(pool: &PgPool) -> Result<(), SqlxError> {
+    sqlx::query("SELECT 1").execute(pool).await?;
+    Ok(())
+}
+
+/// Versions of the migrations of the server that are not applied yet, or
+/// whose last run failed
+pub async fn pending_migrations(pool: &PgPool) -> Result<Vec<i64>, SqlxError> {
+    const APPLIED_Q: &str = "SELECT version FROM _sqlx_migrations WHERE success";
+
+    let applied = sqlx::query_scalar::<_, i64>(APPLIED_Q)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| migration.version)
+        .filter(|version| !applied.contains(version))
+        .collect())
+}
@@ -0,0 +1,71 @@
+use sqlx::{Error as SqlxError, PgPool};
+use tracing::Instrument;
+
+use super::models::todo::Status;
+use super::statement_span;
+use crate::domain::entities::todo::Status as EntityStatus;
+
+/// Count the todos of every tenant by status, through a function owned by a
+/// role that bypasses row level security
+pub async fn count_todos(pool: &PgPool) -> Result<Vec<(EntityStatus, i64)>, SqlxError> {
+    const COUNT_Q: &str = "SELECT status, count FROM count_todos_by_status()";
+
+    let counts = sqlx::query_as::<_, (Status, i64)>(COUNT_Q)
+        .fetch_all(pool)
+        .instrument(statement_span(COUNT_Q))
+        .await?;
+
+    Ok(counts
+        .into_iter()
+        .map(|(status, count)| (status.into_entity(), count))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::repositories::todo::{FindError, TodoRepository};
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::types::Id;
+    use crate::framework::storage::repositories::tenant_pool;
+    use crate::framework::storage::repositories::todo::PgTodoRepository;
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn todos_of_every_tenant_are_counted(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let mut tenant_a = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let mut tenant_b = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+
+        let eggs = todo("Buy eggs", EntityStatus::Todo);
+        tenant_a.create(eggs.clone()).await.unwrap();
+        tenant_a
+            .create(todo("Buy milk", EntityStatus::Done))
+            .await
+            .unwrap();
+        tenant_b
+            .create(todo("Buy milk", EntityStatus::Done))
+            .await
+            .unwrap();
+
+        let mut counts = count_todos(&pool).await.unwrap();
+        counts.sort_by_key(|(status, _)| status.to_string());
+        assert_eq!(counts, [(EntityStatus::Done, 2), (EntityStatus::Todo, 1)]);
+
+        // tenants still only see their own todos, whatever they set
+        assert!(matches!(
+            tenant_b.find(eggs.id()).await,
+            Err(FindError::NotFound)
+        ));
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query("SELECT set_config('app.metrics', 'on', true)")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let (visible,) = sqlx::query_as::<_, (i64,)>("SELECT count(*) FROM todo")
+            .fetch_one(&mut *tx)
+            .await
+            .unwrap();
+        assert_eq!(visible, 0);
+    }
+}
@@ -1,3 +1,55 @@
+pub mod health;
+pub mod metrics;
+pub mod rate_limit;
 pub(crate) mod repositories;
 
 mod models;
+
+use std::fmt;
+use std::time::Instant;
+
+use sqlx::migrate::Migrator;
+use sqlx::{Error as SqlxError, PgPool, Postgres, Transaction};
+use tracing::Span;
+
+use crate::framework::metrics::METRICS;
+
+/// Migrations of the database, embedded in the server
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Begin a transaction, recording how long it waited for a connection of the
+/// pool
+async fn begin(pool: &PgPool) -> Result<Transaction<'static, Postgres>, SqlxError> {
+    let started_at = Instant::now();
+    let tx = pool.begin().await?;
+    METRICS.observe_pool_acquire(started_at.elapsed());
+    Ok(tx)
+}
+
+/// Span of a SQL statement, named after its operation such as `SELECT`. It
+/// has the statement with its placeholders, never the values bound to them
+fn statement_span(sql: &str) -> Span {
+    tracing::debug_span!(
+        "statement",
+        otel.name = sql.split_whitespace().next(),
+        otel.kind = "client",
+        db.system = "postgresql",
+        db.statement = %Statement(sql),
+    )
+}
+
+/// SQL statement on a single line, as the indentation of queries is noise
+struct Statement<'a>(&'a str);
+
+impl fmt::Display for Statement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, word) in self.0.split_whitespace().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            f.write_str(word)?;
+        }
+
+        Ok(())
+    }
+}
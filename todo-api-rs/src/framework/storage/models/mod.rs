@@ -1 +1,3 @@
-pub(super) mod todo;
\ No newline at end of file
+pub(super) mod outbox;
+pub(super) mod todo;
+pub(super) mod webhook;
\ No newline at end of file
@@ -0,0 +1,69 @@
+use std::error;
+
+use serde::Deserialize;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::types::Uuid;
+use sqlx::FromRow;
+
+use super::todo::TodoModel;
+use crate::application::publishers::todo::TodoChange;
+use crate::domain::types::Id;
+use crate::framework::events::todo::TodoEvent;
+use crate::framework::outbox::OutboxEvent;
+
+#[derive(Clone, Debug, FromRow)]
+pub struct OutboxModel {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub event: String,
+    /// [`TodoModel`] serialized as JSON
+    pub payload: String,
+    pub attempt_count: i32,
+    pub created_at: OffsetDateTime,
+}
+
+impl OutboxModel {
+    pub fn try_into_event(self) -> Result<OutboxEvent, Box<dyn error::Error>> {
+        let todo = serde_json::from_str::<TodoModel>(&self.payload)?;
+
+        Ok(OutboxEvent {
+            id: self.id.into(),
+            tenant_id: self.tenant_id.into(),
+            change: into_change(&self.event, todo)?,
+            attempt_count: u32::try_from(self.attempt_count).unwrap_or(0),
+            occurred_at: self.created_at.into(),
+        })
+    }
+}
+
+/// Outbox event notified to every instance once relayed
+#[derive(Clone, Debug, Deserialize)]
+pub struct BroadcastModel {
+    pub seq: i64,
+    pub tenant_id: Uuid,
+    pub event: String,
+    pub payload: TodoModel,
+}
+
+impl BroadcastModel {
+    /// Event of the tenant with the returned id, identified by the order it
+    /// was written to the outbox in
+    pub fn try_into_event(self) -> Result<(Id, TodoEvent), Box<dyn error::Error>> {
+        let event = TodoEvent {
+            id: u64::try_from(self.seq)?,
+            change: into_change(&self.event, self.payload)?,
+        };
+
+        Ok((self.tenant_id.into(), event))
+    }
+}
+
+fn into_change(event: &str, todo: TodoModel) -> Result<TodoChange, Box<dyn error::Error>> {
+    let todo = todo.try_into_entity()?;
+    match event {
+        "todo.created" => Ok(TodoChange::Created(todo)),
+        "todo.updated" => Ok(TodoChange::Updated(todo)),
+        "todo.deleted" => Ok(TodoChange::Deleted(todo)),
+        event => Err(format!("Unknown outbox event {event}").into()),
+    }
+}
@@ -1,6 +1,6 @@
 use std::error;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use sqlx::types::time::{Date as TimeDate, OffsetDateTime};
 use sqlx::types::Uuid;
 use sqlx::{FromRow, Type};
@@ -10,7 +10,7 @@ use crate::domain::entities::todo::{
 };
 use crate::domain::types::Date;
 
-#[derive(Clone, Debug, FromRow, Deserialize)]
+#[derive(Clone, Debug, FromRow, Serialize, Deserialize)]
 pub struct TodoModel {
     pub id: Uuid,
     pub title: String,
@@ -40,8 +40,23 @@ impl TodoModel {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Type, Deserialize)]
+impl From<&TodoEntity> for TodoModel {
+    fn from(todo: &TodoEntity) -> Self {
+        Self {
+            id: todo.id().uuid(),
+            title: todo.title().as_str().to_owned(),
+            description: todo.description().map(|d| d.as_str().to_owned()),
+            todo_at: todo.todo_at().map(|at| at.time()),
+            status: Status::from(todo.status()),
+            created_at: todo.created_at().time(),
+            updated_at: todo.updated_at().time(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Type, Serialize, Deserialize)]
 #[sqlx(type_name = "todo_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum Status {
     Todo,
     InProgress,
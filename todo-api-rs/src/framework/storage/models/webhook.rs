@@ -0,0 +1,129 @@
+use std::error;
+
+use serde::Deserialize;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::types::Uuid;
+use sqlx::{FromRow, Type};
+
+use crate::domain::entities::webhook::{
+    DeliveryAttempt, DeliveryEntity, DeliveryStatus as EntityDeliveryStatus, InitProps,
+    WebhookEntity, WebhookEvent, WebhookSecret, WebhookUrl,
+};
+
+#[derive(Clone, Debug, FromRow, Deserialize)]
+pub struct WebhookModel {
+    pub id: Uuid,
+    pub url: String,
+    pub events: Vec<String>,
+    pub secret: String,
+    pub disabled_reason: Option<String>,
+    pub created_at: OffsetDateTime,
+}
+
+impl WebhookModel {
+    pub fn try_into_entity(self) -> Result<WebhookEntity, Box<dyn error::Error>> {
+        let events = self
+            .events
+            .iter()
+            .map(|event| WebhookEvent::parse_str(event))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // disabled webhooks keep the url they were disabled for
+        let url = match self.disabled_reason {
+            Some(_) => WebhookUrl::init(self.url),
+            None => WebhookUrl::new(self.url)?,
+        };
+
+        let entity = WebhookEntity::init(InitProps {
+            id: self.id.into(),
+            url,
+            events,
+            secret: WebhookSecret::new(self.secret)?,
+            disabled_reason: self.disabled_reason,
+            created_at: self.created_at.into(),
+        });
+
+        Ok(entity)
+    }
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct DeliveryModel {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub status: DeliveryStatus,
+    pub attempt_count: i32,
+    pub next_attempt_at: Option<OffsetDateTime>,
+    pub last_error: Option<String>,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+impl DeliveryModel {
+    pub fn try_into_entity(
+        self,
+        attempts: Vec<DeliveryAttempt>,
+    ) -> Result<DeliveryEntity, Box<dyn error::Error>> {
+        Ok(DeliveryEntity {
+            id: self.id.into(),
+            webhook_id: self.webhook_id.into(),
+            event: WebhookEvent::parse_str(&self.event)?,
+            status: self.status.into_entity(),
+            attempt_count: self.attempt_count.try_into()?,
+            next_attempt_at: self.next_attempt_at.map(Into::into),
+            last_error: self.last_error,
+            attempts,
+            created_at: self.created_at.into(),
+            updated_at: self.updated_at.into(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct AttemptModel {
+    pub delivery_id: Uuid,
+    pub attempted_at: OffsetDateTime,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub duration_ms: i32,
+}
+
+impl AttemptModel {
+    pub fn try_into_entity(self) -> Result<DeliveryAttempt, Box<dyn error::Error>> {
+        Ok(DeliveryAttempt {
+            attempted_at: self.attempted_at.into(),
+            status_code: self.status_code.map(u16::try_from).transpose()?,
+            error: self.error,
+            duration_ms: self.duration_ms.try_into()?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Type)]
+#[sqlx(type_name = "webhook_delivery_status", rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Dead,
+}
+
+impl From<EntityDeliveryStatus> for DeliveryStatus {
+    fn from(value: EntityDeliveryStatus) -> Self {
+        match value {
+            EntityDeliveryStatus::Pending => Self::Pending,
+            EntityDeliveryStatus::Delivered => Self::Delivered,
+            EntityDeliveryStatus::Dead => Self::Dead,
+        }
+    }
+}
+
+impl DeliveryStatus {
+    pub fn into_entity(self) -> EntityDeliveryStatus {
+        match self {
+            Self::Pending => EntityDeliveryStatus::Pending,
+            Self::Delivered => EntityDeliveryStatus::Delivered,
+            Self::Dead => EntityDeliveryStatus::Dead,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use sqlx::{Error as SqlxError, PgPool};
+use tracing::Instrument;
+
+use super::statement_span;
+use crate::framework::rate_limit::{Decision, Quota, RateLimitStore, StoreError};
+
+/// Time between deletions of the buckets that are full again
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Buckets shared by every instance of the server, refilled by the clock of
+/// the database so instances agree on it
+#[derive(Debug)]
+pub struct PgRateLimitStore {
+    pool: PgPool,
+    swept_at: Mutex<Instant>,
+}
+
+impl PgRateLimitStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            swept_at: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn take_token(&self, key: &str, quota: Quota) -> Result<Decision, SqlxError> {
+        // the bucket is refilled and a token taken in a single statement, so
+        // concurrent requests of a client wait on the lock of its row
+        const TAKE_Q: &str = r#"
+            INSERT INTO rate_limit_bucket AS bucket (key, tokens, allowed, updated_at)
+            VALUES ($1, $2 - 1, true, now())
+            ON CONFLICT (key) DO UPDATE SET (tokens, allowed, updated_at) = (
+                SELECT
+                    CASE WHEN refilled >= 1 THEN refilled - 1 ELSE refilled END,
+                    refilled >= 1,
+                    now()
+                FROM (
+                    SELECT LEAST(
+                        $2,
+                        bucket.tokens + EXTRACT(EPOCH FROM now() - bucket.updated_at)::float8 * $3
+                    ) AS refilled
+                ) AS refill
+            )
+            RETURNING tokens, allowed
+        "#;
+
+        self.sweep().await?;
+
+        let (tokens, allowed) = sqlx::query_as::<_, (f64, bool)>(TAKE_Q)
+            .bind(key)
+            .bind(f64::from(quota.limit))
+            .bind(quota.rate())
+            .fetch_one(&self.pool)
+            .instrument(statement_span(TAKE_Q))
+            .await?;
+
+        Ok(Decision {
+            quota,
+            allowed,
+            tokens,
+        })
+    }
+
+    /// Delete the buckets unused for the longest period of a quota, which are
+    /// full as if they were new, at most once per interval
+    async fn sweep(&self) -> Result<(), SqlxError> {
+        const SWEEP_Q: &str = r#"
+            DELETE FROM rate_limit_bucket
+            WHERE updated_at < now() - make_interval(secs => $1)
+        "#;
+
+        {
+            let mut swept_at = self.swept_at.lock().unwrap_or_else(|err| err.into_inner());
+            if swept_at.elapsed() < SWEEP_INTERVAL {
+                return Ok(());
+            }
+            *swept_at = Instant::now();
+        }
+
+        sqlx::query(SWEEP_Q)
+            .bind(Quota::MAX_PERIOD.as_secs_f64())
+            .execute(&self.pool)
+            .instrument(statement_span(SWEEP_Q))
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl RateLimitStore for PgRateLimitStore {
+    fn take<'a>(
+        &'a self,
+        key: &'a str,
+        quota: Quota,
+    ) -> BoxFuture<'a, Result<Decision, StoreError>> {
+        async move { Ok(self.take_token(key, quota).await?) }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn buckets_are_shared_by_stores(pool: PgPool) {
+        let quota = Quota {
+            limit: 2,
+            period: Duration::from_secs(3600),
+        };
+        let instance_a = PgRateLimitStore::new(pool.clone());
+        let instance_b = PgRateLimitStore::new(pool);
+
+        let first = instance_a.take("client", quota).await.unwrap();
+        assert!(first.allowed);
+        assert!((first.tokens - 1.0).abs() < 0.01);
+
+        assert!(instance_b.take("client", quota).await.unwrap().allowed);
+        let limited = instance_a.take("client", quota).await.unwrap();
+        assert!(!limited.allowed);
+        assert!(limited.tokens < 1.0);
+
+        assert!(
+            instance_b
+                .take("other client", quota)
+                .await
+                .unwrap()
+                .allowed
+        );
+    }
+}
@@ -0,0 +1,481 @@
+use futures_util::{stream, StreamExt, TryStreamExt};
+use sqlx::types::time::OffsetDateTime;
+use sqlx::types::Uuid;
+use sqlx::{Error as SqlxError, PgPool, Postgres, Transaction};
+use tracing::Instrument;
+
+use super::todo::PgTodoRepository;
+use super::{begin_tenant, outbox};
+use crate::application::publishers::todo::TodoChange;
+use crate::application::repositories::backup::{
+    BackupRepository, ImportRecord, ImportStream, RestoreError, RestoreMode, RestoreSummary,
+    Snapshot, TableSummary,
+};
+use crate::application::repositories::todo::{
+    ImportOrigin, ListError, StreamQuery, TodoRepository, TodoStream,
+};
+use crate::domain::entities::todo::TodoEntity;
+use crate::domain::types::Id;
+use crate::framework::storage::models::todo::{Status as TodoModelStatus, TodoModel};
+use crate::framework::storage::statement_span;
+
+/// Postgres permission error, raised when row level security rejects a row
+const INSUFFICIENT_PRIVILEGE: &str = "42501";
+
+/// Formats of the imported files entries are remembered for
+const IMPORT_FORMATS: [&str; 3] = ["csv", "ics", "txt"];
+
+/// Row of the `todo_import` table, without its tenant
+type ImportRow = (String, String, Uuid, OffsetDateTime);
+
+#[derive(Clone)]
+pub struct PgBackupRepository {
+    pool: PgPool,
+    tenant_id: Option<Id>,
+}
+
+impl PgBackupRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            tenant_id: None,
+        }
+    }
+
+    /// Scope every query of the repository to the tenant with `tenant_id`.
+    /// Without a tenant, row level security hides every row.
+    pub fn with_tenant(mut self, tenant_id: Id) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+}
+
+impl BackupRepository for PgBackupRepository {
+    fn todos(&self) -> TodoStream {
+        let mut todos = PgTodoRepository::new(self.pool.clone());
+        if let Some(tenant_id) = self.tenant_id {
+            todos = todos.with_tenant(tenant_id);
+        }
+
+        todos.stream(StreamQuery { title: None })
+    }
+
+    /// Imports are fetched at once, as there are at most as many as todos and
+    /// they are much smaller
+    fn imports(&self) -> ImportStream {
+        const IMPORTS_Q: &str = r#"
+            SELECT format, uid, todo_id, created_at FROM todo_import
+            ORDER BY created_at, format, uid
+        "#;
+
+        let pool = self.pool.clone();
+        let tenant_id = self.tenant_id;
+        let rows = async move {
+            let mut tx = begin_tenant(&pool, tenant_id).await?;
+            let rows = sqlx::query_as::<_, ImportRow>(IMPORTS_Q)
+                .fetch_all(&mut *tx)
+                .instrument(statement_span(IMPORTS_Q))
+                .await?;
+            tx.commit().await?;
+
+            Ok::<_, SqlxError>(rows)
+        };
+
+        stream::once(rows)
+            .map_err(|err| ListError::Internal(err.into()))
+            .map_ok(|rows| stream::iter(rows.into_iter().map(import_record)))
+            .try_flatten()
+            .boxed()
+    }
+
+    async fn restore(
+        &mut self,
+        snapshot: Snapshot,
+        mode: RestoreMode,
+    ) -> Result<RestoreSummary, RestoreError> {
+        let mut tx = begin_tenant(&self.pool, self.tenant_id)
+            .await
+            .map_err(|err| RestoreError::Internal(err.into()))?;
+
+        let mut summary = RestoreSummary::default();
+        if mode == RestoreMode::Replace {
+            let ids = snapshot.todos.iter().map(|todo| todo.id().uuid()).collect();
+            summary.todos.deleted = delete_todos_except(&mut tx, self.tenant_id, ids).await?;
+        }
+
+        for todo in snapshot.todos {
+            upsert_todo(&mut tx, self.tenant_id, todo, &mut summary.todos).await?;
+        }
+
+        // imports are restored once their todos are, as they reference them
+        if let Some(imports) = snapshot.imports {
+            if mode == RestoreMode::Replace {
+                let origins = imports
+                    .iter()
+                    .map(|import| &import.origin)
+                    .collect::<Vec<_>>();
+                summary.imports.deleted = delete_imports_except(&mut tx, &origins).await?;
+            }
+
+            for import in imports {
+                upsert_import(&mut tx, self.tenant_id, import, &mut summary.imports).await?;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|err| RestoreError::Internal(err.into()))?;
+
+        Ok(summary)
+    }
+}
+
+/// Delete every todo whose id is not in `ids`, returning how many were
+async fn delete_todos_except(
+    tx: &mut Transaction<'static, Postgres>,
+    tenant_id: Option<Id>,
+    ids: Vec<Uuid>,
+) -> Result<u64, RestoreError> {
+    const DELETE_Q: &str = "DELETE FROM todo WHERE id <> ALL($1) RETURNING *";
+
+    let models = sqlx::query_as::<_, TodoModel>(DELETE_Q)
+        .bind(ids)
+        .fetch_all(&mut **tx)
+        .instrument(statement_span(DELETE_Q))
+        .await
+        .map_err(|err| RestoreError::Internal(err.into()))?;
+
+    let deleted = models.len() as u64;
+    for model in models {
+        let todo = model.try_into_entity().map_err(RestoreError::Internal)?;
+        outbox::record(tx, tenant_id, &TodoChange::Deleted(todo))
+            .await
+            .map_err(RestoreError::Internal)?;
+    }
+
+    Ok(deleted)
+}
+
+/// Create `todo`, or overwrite the todo with its id when they differ
+async fn upsert_todo(
+    tx: &mut Transaction<'static, Postgres>,
+    tenant_id: Option<Id>,
+    todo: TodoEntity,
+    summary: &mut TableSummary,
+) -> Result<(), RestoreError> {
+    // `xmax` is only set on rows that were updated, and nothing is returned
+    // when the todo was left as it is. The tenant is compared as well since
+    // rows of other tenants are only rejected when an update is attempted
+    const UPSERT_Q: &str = r#"
+        INSERT INTO todo (id, tenant_id, title, description, todo_at, status, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (id) DO UPDATE
+        SET title = EXCLUDED.title,
+            description = EXCLUDED.description,
+            todo_at = EXCLUDED.todo_at,
+            status = EXCLUDED.status,
+            created_at = EXCLUDED.created_at,
+            updated_at = EXCLUDED.updated_at
+        WHERE (todo.tenant_id, todo.title, todo.description, todo.todo_at, todo.status, todo.created_at, todo.updated_at)
+            IS DISTINCT FROM
+            (EXCLUDED.tenant_id, EXCLUDED.title, EXCLUDED.description, EXCLUDED.todo_at, EXCLUDED.status, EXCLUDED.created_at, EXCLUDED.updated_at)
+        RETURNING xmax = 0
+    "#;
+
+    let created = sqlx::query_scalar::<_, bool>(UPSERT_Q)
+        .bind(todo.id().uuid())
+        .bind(tenant_id.map(|id| id.uuid()))
+        .bind(todo.title().as_str())
+        .bind(todo.description().map(|d| d.as_str()))
+        .bind(todo.todo_at().map(|at| at.time()))
+        .bind(TodoModelStatus::from(todo.status()))
+        .bind(todo.created_at().time())
+        .bind(todo.updated_at().time())
+        .fetch_optional(&mut **tx)
+        .instrument(statement_span(UPSERT_Q))
+        .await
+        .map_err(|err| match err {
+            SqlxError::Database(db_err) if db_err.is_unique_violation() => {
+                RestoreError::DuplicatedTitle
+            }
+            // the id belongs to a todo of another tenant
+            SqlxError::Database(db_err)
+                if db_err.code().as_deref() == Some(INSUFFICIENT_PRIVILEGE) =>
+            {
+                RestoreError::IdTaken
+            }
+            _ => RestoreError::Internal(err.into()),
+        })?;
+
+    let change = match created {
+        Some(true) => {
+            summary.created += 1;
+            TodoChange::Created(todo)
+        }
+        Some(false) => {
+            summary.updated += 1;
+            TodoChange::Updated(todo)
+        }
+        None => {
+            summary.unchanged += 1;
+            return Ok(());
+        }
+    };
+
+    outbox::record(tx, tenant_id, &change)
+        .await
+        .map_err(RestoreError::Internal)
+}
+
+/// Delete every import whose entry is not one of `origins`, returning how
+/// many were
+async fn delete_imports_except(
+    tx: &mut Transaction<'static, Postgres>,
+    origins: &[&ImportOrigin],
+) -> Result<u64, RestoreError> {
+    const DELETE_Q: &str = r#"
+        DELETE FROM todo_import
+        WHERE (format, uid) NOT IN (SELECT * FROM UNNEST($1::text[], $2::text[]))
+    "#;
+
+    let formats = origins
+        .iter()
+        .map(|origin| origin.format)
+        .collect::<Vec<_>>();
+    let uids = origins
+        .iter()
+        .map(|origin| origin.uid.as_str())
+        .collect::<Vec<_>>();
+    let result = sqlx::query(DELETE_Q)
+        .bind(formats)
+        .bind(uids)
+        .execute(&mut **tx)
+        .instrument(statement_span(DELETE_Q))
+        .await
+        .map_err(|err| RestoreError::Internal(err.into()))?;
+
+    Ok(result.rows_affected())
+}
+
+/// Create `import`, or overwrite the import of its entry when they differ
+async fn upsert_import(
+    tx: &mut Transaction<'static, Postgres>,
+    tenant_id: Option<Id>,
+    import: ImportRecord,
+    summary: &mut TableSummary,
+) -> Result<(), RestoreError> {
+    // same as todos, `xmax` is only set on rows that were updated
+    const UPSERT_Q: &str = r#"
+        INSERT INTO todo_import (tenant_id, format, uid, todo_id, created_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (tenant_id, format, uid) DO UPDATE
+        SET todo_id = EXCLUDED.todo_id,
+            created_at = EXCLUDED.created_at
+        WHERE (todo_import.todo_id, todo_import.created_at)
+            IS DISTINCT FROM
+            (EXCLUDED.todo_id, EXCLUDED.created_at)
+        RETURNING xmax = 0
+    "#;
+
+    let created = sqlx::query_scalar::<_, bool>(UPSERT_Q)
+        .bind(tenant_id.map(|id| id.uuid()))
+        .bind(import.origin.format)
+        .bind(&import.origin.uid)
+        .bind(import.todo_id.uuid())
+        .bind(import.created_at.time())
+        .fetch_optional(&mut **tx)
+        .instrument(statement_span(UPSERT_Q))
+        .await
+        .map_err(|err| RestoreError::Internal(err.into()))?;
+
+    match created {
+        Some(true) => summary.created += 1,
+        Some(false) => summary.updated += 1,
+        None => summary.unchanged += 1,
+    }
+
+    Ok(())
+}
+
+fn import_record(row: ImportRow) -> Result<ImportRecord, ListError> {
+    let (format, uid, todo_id, created_at) = row;
+    let format = IMPORT_FORMATS
+        .into_iter()
+        .find(|name| *name == format)
+        .ok_or_else(|| ListError::Internal(format!("Unknown import format {format}").into()))?;
+
+    Ok(ImportRecord {
+        origin: ImportOrigin { format, uid },
+        todo_id: Id::from(todo_id),
+        created_at: created_at.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+
+    use super::*;
+    use crate::application::repositories::todo::ImportError;
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::entities::todo::{Status, Title};
+    use crate::framework::storage::repositories::tenant_pool;
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn snapshots_merge_or_replace_todos(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let tenant_id = Id::new();
+        let mut todos = PgTodoRepository::new(pool.clone()).with_tenant(tenant_id);
+        let mut backups = PgBackupRepository::new(pool.clone()).with_tenant(tenant_id);
+
+        let kept = todo("Kept", Status::Todo);
+        let mut renamed = todo("Renamed", Status::Todo);
+        let unchanged = todo("Unchanged", Status::Todo);
+        for todo in [&kept, &renamed, &unchanged] {
+            todos.create(todo.clone()).await.unwrap();
+        }
+        // timestamps are stored with microseconds
+        let unchanged = todos.find(unchanged.id()).await.unwrap();
+
+        renamed.rename(Title::new("Renamed in backup").unwrap());
+        let created = todo("Created", Status::Todo);
+        let snapshot = Snapshot {
+            todos: vec![renamed.clone(), unchanged, created.clone()],
+            imports: None,
+        };
+
+        let merged = backups
+            .restore(snapshot.clone(), RestoreMode::Merge)
+            .await
+            .unwrap();
+        let expected = TableSummary {
+            created: 1,
+            updated: 1,
+            unchanged: 1,
+            deleted: 0,
+        };
+        assert_eq!(merged.todos, expected);
+        let found = todos.find(renamed.id()).await.unwrap();
+        assert_eq!(found.title().as_str(), "Renamed in backup");
+
+        let replaced = backups
+            .restore(snapshot, RestoreMode::Replace)
+            .await
+            .unwrap();
+        let expected = TableSummary {
+            created: 0,
+            updated: 0,
+            unchanged: 3,
+            deleted: 1,
+        };
+        assert_eq!(replaced.todos, expected);
+
+        let mut titles = backups
+            .todos()
+            .map_ok(|todo| todo.title().to_string())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        titles.sort();
+        assert_eq!(titles, ["Created", "Renamed in backup", "Unchanged"]);
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn failed_restores_change_nothing(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let tenant_id = Id::new();
+        let mut todos = PgTodoRepository::new(pool.clone()).with_tenant(tenant_id);
+        let mut backups = PgBackupRepository::new(pool.clone()).with_tenant(tenant_id);
+        todos.create(todo("Existing", Status::Todo)).await.unwrap();
+
+        let snapshot = Snapshot {
+            todos: vec![todo("New", Status::Todo), todo("Existing", Status::Todo)],
+            imports: None,
+        };
+        assert!(matches!(
+            backups.restore(snapshot, RestoreMode::Merge).await,
+            Err(RestoreError::DuplicatedTitle)
+        ));
+
+        // ids of other tenants cannot be overwritten, even with the same todo
+        let mut other_tenant = PgBackupRepository::new(pool.clone()).with_tenant(Id::new());
+        let copied = backups.todos().try_collect::<Vec<_>>().await.unwrap();
+        let mut stolen = copied.clone();
+        stolen[0].rename(Title::new("Stolen").unwrap());
+        for todos in [copied, stolen] {
+            let snapshot = Snapshot {
+                todos,
+                imports: None,
+            };
+            assert!(matches!(
+                other_tenant.restore(snapshot, RestoreMode::Merge).await,
+                Err(RestoreError::IdTaken)
+            ));
+        }
+
+        let titles = backups
+            .todos()
+            .map_ok(|todo| todo.title().to_string())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(titles, ["Existing"]);
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn imports_are_restored_with_their_todos(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let tenant_id = Id::new();
+        let mut todos = PgTodoRepository::new(pool.clone()).with_tenant(tenant_id);
+        let mut backups = PgBackupRepository::new(pool.clone()).with_tenant(tenant_id);
+
+        let imported = todo("Imported", Status::Todo);
+        let origin = |uid: &str| ImportOrigin {
+            format: "ics",
+            uid: uid.to_owned(),
+        };
+        todos
+            .import(imported.clone(), Some(origin("stale@example.com")))
+            .await
+            .unwrap();
+
+        let restored = todo("Restored", Status::Todo);
+        let import = ImportRecord {
+            origin: origin("restored@example.com"),
+            todo_id: restored.id(),
+            created_at: restored.created_at(),
+        };
+        let snapshot = Snapshot {
+            todos: vec![imported, restored],
+            imports: Some(vec![import.clone()]),
+        };
+
+        let replaced = backups
+            .restore(snapshot, RestoreMode::Replace)
+            .await
+            .unwrap();
+        let expected = TableSummary {
+            created: 1,
+            updated: 0,
+            unchanged: 0,
+            deleted: 1,
+        };
+        assert_eq!(replaced.imports, expected);
+
+        let imports = backups.imports().try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(imports.len(), 1);
+        assert_eq!(imports[0].origin, import.origin);
+        assert_eq!(imports[0].todo_id, import.todo_id);
+
+        // importing the entry again is skipped
+        assert!(matches!(
+            todos
+                .check_import(&Title::new("Restored").unwrap(), Some(&import.origin))
+                .await,
+            Err(ImportError::AlreadyImported)
+        ));
+    }
+}
@@ -1 +1,63 @@
-pub mod todo;
\ No newline at end of file
+pub mod backup;
+pub mod outbox;
+pub mod todo;
+pub mod webhook;
+
+use sqlx::{Error as SqlxError, PgPool, Postgres, Transaction};
+use tracing::Instrument;
+
+use crate::domain::types::Id;
+use crate::framework::storage::{begin, statement_span};
+
+/// Begin a transaction with `app.tenant_id` set locally, so row level
+/// security policies only expose rows from the tenant with `tenant_id`
+async fn begin_tenant(
+    pool: &PgPool,
+    tenant_id: Option<Id>,
+) -> Result<Transaction<'static, Postgres>, SqlxError> {
+    // `set_config` with `is_local` is the same as `SET LOCAL`, but accepts bind parameters
+    const SET_TENANT_Q: &str = "SELECT set_config('app.tenant_id', $1, true)";
+
+    let mut tx = begin(pool).await?;
+    let tenant_id = tenant_id.map(|id| id.to_string()).unwrap_or_default();
+    sqlx::query(SET_TENANT_Q)
+        .bind(tenant_id)
+        .execute(&mut *tx)
+        .instrument(statement_span(SET_TENANT_Q))
+        .await?;
+
+    Ok(tx)
+}
+
+/// Superusers bypass row level security, so queries of the returned pool run
+/// as a regular role that only has access to the `todo`, `outbox` and
+/// `todo_import` tables
+#[cfg(test)]
+pub(crate) async fn tenant_pool(pool: &PgPool) -> PgPool {
+    use sqlx::postgres::PgPoolOptions;
+    use sqlx::Executor;
+
+    const CREATE_ROLE_Q: &str = r#"
+        DO $$ BEGIN
+            CREATE ROLE todo_tenant_test NOLOGIN NOSUPERUSER NOBYPASSRLS;
+        EXCEPTION
+            WHEN duplicate_object OR unique_violation THEN null;
+        END $$;
+        GRANT SELECT, INSERT, UPDATE, DELETE ON todo, outbox, todo_import TO todo_tenant_test;
+        GRANT USAGE ON SEQUENCE outbox_seq_seq TO todo_tenant_test;
+    "#;
+
+    pool.execute(CREATE_ROLE_Q).await.unwrap();
+
+    PgPoolOptions::new()
+        .max_connections(2)
+        .after_connect(|conn, _| {
+            Box::pin(async move {
+                conn.execute("SET ROLE todo_tenant_test").await?;
+                Ok(())
+            })
+        })
+        .connect_with(pool.connect_options().as_ref().clone())
+        .await
+        .unwrap()
+}
\ No newline at end of file
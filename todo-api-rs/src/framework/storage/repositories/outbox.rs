@@ -0,0 +1,164 @@
+use std::error::Error;
+use std::time::Duration;
+
+use sqlx::types::time::OffsetDateTime;
+use sqlx::{Error as SqlxError, PgPool, Postgres, Transaction};
+use tracing::Instrument;
+
+use super::begin_tenant;
+use crate::application::publishers::todo::TodoChange;
+use crate::domain::types::Id;
+use crate::framework::events::todo::TodoEvent;
+use crate::framework::outbox::OutboxEvent;
+use crate::framework::storage::models::outbox::{BroadcastModel, OutboxModel};
+use crate::framework::storage::models::todo::TodoModel;
+use crate::framework::storage::{begin, statement_span};
+
+/// Channel every instance listens to for relayed events, so they reach the
+/// subscribers connected to any instance
+pub const BROADCAST_CHANNEL: &str = "todo_events";
+
+/// Write `change` to the outbox as part of `tx`, so it's only relayed when
+/// the transaction that made the change commits
+pub(super) async fn record(
+    tx: &mut Transaction<'static, Postgres>,
+    tenant_id: Option<Id>,
+    change: &TodoChange,
+) -> Result<(), Box<dyn Error>> {
+    const INSERT_Q: &str = r#"
+        INSERT INTO outbox (id, tenant_id, event, payload, next_attempt_at, created_at)
+        VALUES ($1, $2, $3, $4::jsonb, now(), now())
+    "#;
+
+    let payload = serde_json::to_string(&TodoModel::from(change.todo()))?;
+
+    sqlx::query(INSERT_Q)
+        .bind(Id::new().uuid())
+        .bind(tenant_id.map(|id| id.uuid()))
+        .bind(change.name())
+        .bind(payload)
+        .execute(&mut **tx)
+        .instrument(statement_span(INSERT_Q))
+        .await?;
+
+    Ok(())
+}
+
+/// Outbox of every tenant, which is meant to be used by the relay only.
+/// Events are claimed across tenants by a database function, while everything
+/// else is scoped to the tenant of the event
+#[derive(Clone)]
+pub struct PgOutbox {
+    pool: PgPool,
+}
+
+impl PgOutbox {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Claim up to `limit` due events in the order they were written, which are
+    /// hidden from other claims for `lease` so they are not relayed concurrently.
+    /// Events that cannot be decoded are dead, as they never could be relayed
+    pub async fn claim(&self, limit: i64, lease: Duration) -> Result<Vec<OutboxEvent>, SqlxError> {
+        const CLAIM_Q: &str = "SELECT * FROM claim_outbox_events($1, $2)";
+        const DEAD_Q: &str = "UPDATE outbox SET status = 'dead', last_error = $2 WHERE id = $1";
+
+        let mut tx = begin(&self.pool).await?;
+        let models = sqlx::query_as::<_, OutboxModel>(CLAIM_Q)
+            .bind(limit)
+            .bind(lease.as_secs_f64())
+            .fetch_all(&mut *tx)
+            .instrument(statement_span(CLAIM_Q))
+            .await?;
+        tx.commit().await?;
+
+        let mut events = Vec::with_capacity(models.len());
+        for model in models {
+            let (id, tenant_id) = (model.id, model.tenant_id);
+            match model.try_into_event().map_err(|err| err.to_string()) {
+                Ok(event) => events.push(event),
+                Err(err) => {
+                    tracing::error!(outbox.id = %id, tenant.id = %tenant_id, error = err, "Invalid outbox event is dead");
+                    let mut tx = begin_tenant(&self.pool, Some(tenant_id.into())).await?;
+                    sqlx::query(DEAD_Q)
+                        .bind(id)
+                        .bind(format!("Invalid event: {err}"))
+                        .execute(&mut *tx)
+                        .instrument(statement_span(DEAD_Q))
+                        .await?;
+                    tx.commit().await?;
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Notify every instance listening to [`BROADCAST_CHANNEL`] of `event`,
+    /// once it's relayed
+    pub async fn broadcast(&self, event: &OutboxEvent) -> Result<(), SqlxError> {
+        const NOTIFY_Q: &str = r#"
+            SELECT pg_notify($2, json_build_object(
+                'seq', seq, 'tenant_id', tenant_id, 'event', event, 'payload', payload
+            )::text)
+            FROM outbox
+            WHERE id = $1
+        "#;
+
+        let mut tx = begin_tenant(&self.pool, Some(event.tenant_id)).await?;
+        sqlx::query(NOTIFY_Q)
+            .bind(event.id.uuid())
+            .bind(BROADCAST_CHANNEL)
+            .execute(&mut *tx)
+            .instrument(statement_span(NOTIFY_Q))
+            .await?;
+        tx.commit().await
+    }
+
+    /// Remove `event`, which every sink received
+    pub async fn complete(&self, event: &OutboxEvent) -> Result<(), SqlxError> {
+        const DELETE_Q: &str = "DELETE FROM outbox WHERE id = $1";
+
+        let mut tx = begin_tenant(&self.pool, Some(event.tenant_id)).await?;
+        sqlx::query(DELETE_Q)
+            .bind(event.id.uuid())
+            .execute(&mut *tx)
+            .instrument(statement_span(DELETE_Q))
+            .await?;
+        tx.commit().await
+    }
+
+    /// Record that relaying `event` failed, and relay it again at `retry_at`,
+    /// or never when there is none as the event is dead
+    pub async fn fail(
+        &self,
+        event: &OutboxEvent,
+        error: &str,
+        retry_at: Option<OffsetDateTime>,
+    ) -> Result<(), SqlxError> {
+        const FAIL_Q: &str = r#"
+            UPDATE outbox
+            SET attempt_count = attempt_count + 1, last_error = $2,
+                next_attempt_at = COALESCE($3, next_attempt_at),
+                status = CASE WHEN $3 IS NULL THEN 'dead' ELSE status END
+            WHERE id = $1
+        "#;
+
+        let mut tx = begin_tenant(&self.pool, Some(event.tenant_id)).await?;
+        sqlx::query(FAIL_Q)
+            .bind(event.id.uuid())
+            .bind(error)
+            .bind(retry_at)
+            .execute(&mut *tx)
+            .instrument(statement_span(FAIL_Q))
+            .await?;
+        tx.commit().await
+    }
+}
+
+/// Decode a notification of [`BROADCAST_CHANNEL`] into an event of the tenant
+/// with the returned id
+pub fn decode_broadcast(payload: &str) -> Result<(Id, TodoEvent), Box<dyn Error>> {
+    serde_json::from_str::<BroadcastModel>(payload)?.try_into_event()
+}
@@ -1,44 +1,99 @@
 use std::error::Error;
 
+use futures_util::{stream, StreamExt, TryStreamExt};
 use sqlx::types::time::OffsetDateTime;
-use sqlx::types::uuid::Uuid;
-use sqlx::{Error as SqlxError, PgPool, Postgres, QueryBuilder};
+use sqlx::types::Uuid;
+use sqlx::{Error as SqlxError, PgPool, Postgres, QueryBuilder, Transaction};
+use tracing::Instrument;
 
+use super::{begin_tenant, outbox};
+use crate::application::publishers::todo::TodoChange;
 use crate::application::repositories::todo::{
-    CreateError, DeleteError, FindError, ListError, ListQuery, PaginatedList, TodoRepository,
-    UpdateError, UpdateQuery,
+    CreateError, DeleteError, FindError, ImportError, ImportOrigin, ListError, ListQuery,
+    PaginatedList, StreamQuery, TodoRepository, TodoStream, UpdateError,
 };
-use crate::domain::entities::todo::TodoEntity;
-use crate::domain::types::Id;
+use crate::domain::entities::todo::{Title, TodoEntity};
+use crate::domain::types::{DateTime, Id};
 use crate::framework::storage::models::todo::{Status as TodoModelStatus, TodoModel};
+use crate::framework::storage::statement_span;
 
 #[derive(Clone)]
 pub struct PgTodoRepository {
     pool: PgPool,
+    tenant_id: Option<Id>,
 }
 
 impl PgTodoRepository {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            tenant_id: None,
+        }
+    }
+
+    /// Scope every query of the repository to the tenant with `tenant_id`.
+    /// Without a tenant, row level security hides every row.
+    pub fn with_tenant(mut self, tenant_id: Id) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
+    async fn begin(&self) -> Result<Transaction<'static, Postgres>, SqlxError> {
+        begin_tenant(&self.pool, self.tenant_id).await
     }
 }
 
+/// Number of todos fetched at once by [`PgTodoRepository::stream`]
+const STREAM_BATCH_SIZE: i64 = 500;
+
 impl TodoRepository for PgTodoRepository {
-    async fn create(&mut self, todo: TodoEntity) -> Result<(), CreateError> {
-        const INSERT_Q: &str = r#"
-            INSERT INTO todo (id, title, description, todo_at, status, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+    #[tracing::instrument(name = "PgTodoRepository::check_import", level = "debug", skip_all)]
+    async fn check_import(
+        &self,
+        title: &Title,
+        origin: Option<&ImportOrigin>,
+    ) -> Result<(), ImportError> {
+        const CHECK_Q: &str = r#"
+            SELECT
+                EXISTS (SELECT 1 FROM todo_import WHERE format = $1 AND uid = $2),
+                EXISTS (SELECT 1 FROM todo WHERE title = $3)
         "#;
 
-        sqlx::query(INSERT_Q)
-            .bind(todo.id().uuid())
-            .bind(todo.title.as_str())
-            .bind(todo.description.as_ref().map(|d| d.as_str()))
-            .bind(todo.todo_at.map(|at| at.time()))
-            .bind(TodoModelStatus::from(&todo.status))
-            .bind(todo.created_at().time())
-            .bind(todo.updated_at().time())
-            .fetch_one(&self.pool)
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| ImportError::Internal(err.into()))?;
+
+        let (imported, duplicated) = sqlx::query_as::<_, (bool, bool)>(CHECK_Q)
+            .bind(origin.map(|origin| origin.format))
+            .bind(origin.map(|origin| origin.uid.as_str()))
+            .bind(title.as_str())
+            .fetch_one(&mut *tx)
+            .instrument(statement_span(CHECK_Q))
+            .await
+            .map_err(|err| ImportError::Internal(err.into()))?;
+
+        tx.commit()
+            .await
+            .map_err(|err| ImportError::Internal(err.into()))?;
+
+        if imported {
+            Err(ImportError::AlreadyImported)
+        } else if duplicated {
+            Err(ImportError::DuplicatedTitle)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[tracing::instrument(name = "PgTodoRepository::create", level = "debug", skip_all, fields(todo.id = %todo.id()))]
+    async fn create(&mut self, todo: TodoEntity) -> Result<(), CreateError> {
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| CreateError::Internal(err.into()))?;
+
+        insert(&mut tx, self.tenant_id, &todo)
             .await
             .map_err(|err| match err {
                 SqlxError::Database(db_err) if db_err.is_unique_violation() => {
@@ -47,38 +102,142 @@ impl TodoRepository for PgTodoRepository {
                 _ => CreateError::Internal(err.into()),
             })?;
 
-        Ok(())
+        outbox::record(&mut tx, self.tenant_id, &TodoChange::Created(todo))
+            .await
+            .map_err(CreateError::Internal)?;
+
+        tx.commit()
+            .await
+            .map_err(|err| CreateError::Internal(err.into()))
     }
 
-    async fn delete(&mut self, todo_id: Id) -> Result<(), DeleteError> {
-        const DELETE_Q: &str = "DELETE FROM todo WHERE id = $1 RETURNING id";
-        sqlx::query_scalar::<_, Uuid>(DELETE_Q)
+    #[tracing::instrument(name = "PgTodoRepository::delete", level = "debug", skip_all, fields(todo.id = %todo_id))]
+    async fn delete(&mut self, todo_id: Id) -> Result<TodoEntity, DeleteError> {
+        const DELETE_Q: &str = "DELETE FROM todo WHERE id = $1 RETURNING *";
+
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| DeleteError::Internal(err.into()))?;
+
+        let model = sqlx::query_as::<_, TodoModel>(DELETE_Q)
             .bind(todo_id.uuid())
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
+            .instrument(statement_span(DELETE_Q))
             .await
             .map_err(|err| match err {
                 SqlxError::RowNotFound => DeleteError::NotFound,
                 _ => DeleteError::Internal(err.into()),
             })?;
 
-        Ok(())
+        let todo = model.try_into_entity().map_err(DeleteError::Internal)?;
+        outbox::record(&mut tx, self.tenant_id, &TodoChange::Deleted(todo.clone()))
+            .await
+            .map_err(DeleteError::Internal)?;
+
+        tx.commit()
+            .await
+            .map_err(|err| DeleteError::Internal(err.into()))?;
+
+        Ok(todo)
     }
 
+    #[tracing::instrument(name = "PgTodoRepository::find", level = "debug", skip_all, fields(todo.id = %todo_id))]
     async fn find(&self, todo_id: Id) -> Result<TodoEntity, FindError> {
         const FIND_Q: &str = r#" SELECT * FROM todo as t WHERE t.id = $1"#;
 
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| FindError::Internal(err.into()))?;
+
         let model = sqlx::query_as::<_, TodoModel>(FIND_Q)
             .bind(todo_id.uuid())
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
+            .instrument(statement_span(FIND_Q))
             .await
             .map_err(|err| match err {
                 SqlxError::RowNotFound => FindError::NotFound,
                 _ => FindError::Internal(err.into()),
             })?;
 
+        tx.commit()
+            .await
+            .map_err(|err| FindError::Internal(err.into()))?;
+
         model.try_into_entity().map_err(FindError::Internal)
     }
 
+    #[tracing::instrument(name = "PgTodoRepository::import", level = "debug", skip_all, fields(todo.id = %todo.id()))]
+    async fn import(
+        &mut self,
+        todo: TodoEntity,
+        origin: Option<ImportOrigin>,
+    ) -> Result<(), ImportError> {
+        const IMPORTED_Q: &str = r#"
+            SELECT EXISTS (SELECT 1 FROM todo_import WHERE format = $1 AND uid = $2)
+        "#;
+        const INSERT_ORIGIN_Q: &str = r#"
+            INSERT INTO todo_import (tenant_id, format, uid, todo_id, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+        "#;
+
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| ImportError::Internal(err.into()))?;
+
+        if let Some(origin) = &origin {
+            let imported = sqlx::query_scalar::<_, bool>(IMPORTED_Q)
+                .bind(origin.format)
+                .bind(&origin.uid)
+                .fetch_one(&mut *tx)
+                .instrument(statement_span(IMPORTED_Q))
+                .await
+                .map_err(|err| ImportError::Internal(err.into()))?;
+            if imported {
+                return Err(ImportError::AlreadyImported);
+            }
+        }
+
+        insert(&mut tx, self.tenant_id, &todo)
+            .await
+            .map_err(|err| match err {
+                SqlxError::Database(db_err) if db_err.is_unique_violation() => {
+                    ImportError::DuplicatedTitle
+                }
+                _ => ImportError::Internal(err.into()),
+            })?;
+
+        if let Some(origin) = &origin {
+            sqlx::query(INSERT_ORIGIN_Q)
+                .bind(self.tenant_id.map(|id| id.uuid()))
+                .bind(origin.format)
+                .bind(&origin.uid)
+                .bind(todo.id().uuid())
+                .bind(todo.created_at().time())
+                .execute(&mut *tx)
+                .instrument(statement_span(INSERT_ORIGIN_Q))
+                .await
+                .map_err(|err| match err {
+                    // another import of the same entry committed in the meantime
+                    SqlxError::Database(db_err) if db_err.is_unique_violation() => {
+                        ImportError::AlreadyImported
+                    }
+                    _ => ImportError::Internal(err.into()),
+                })?;
+        }
+
+        outbox::record(&mut tx, self.tenant_id, &TodoChange::Created(todo))
+            .await
+            .map_err(ImportError::Internal)?;
+
+        tx.commit()
+            .await
+            .map_err(|err| ImportError::Internal(err.into()))
+    }
+
+    #[tracing::instrument(name = "PgTodoRepository::list", level = "debug", skip_all)]
     async fn list(&self, query: ListQuery) -> Result<PaginatedList, ListError> {
         let mut count_q = QueryBuilder::<Postgres>::new(r#" SELECT COUNT(*) FROM todo as t "#);
         let mut list_q = QueryBuilder::<Postgres>::new(r#" SELECT * FROM todo "#);
@@ -89,9 +248,16 @@ impl TodoRepository for PgTodoRepository {
             list_q.push(" WHERE title ILIKE ").push_bind(constraint);
         }
 
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| ListError::Internal(err.into()))?;
+
+        let count_span = statement_span(count_q.sql());
         let count = count_q
             .build_query_scalar::<i64>()
-            .fetch_one(&self.pool)
+            .fetch_one(&mut *tx)
+            .instrument(count_span)
             .await
             .map_err(|e| ListError::Internal(e.into()))?;
 
@@ -99,13 +265,20 @@ impl TodoRepository for PgTodoRepository {
         let page: i64 = u32::from(query.page).into();
         let offset = (page - 1) * limit;
 
-        let models = list_q
+        list_q
             .push(" ORDER BY created_at DESC LIMIT ")
             .push_bind(limit)
             .push(" OFFSET ")
-            .push_bind(offset)
+            .push_bind(offset);
+        let list_span = statement_span(list_q.sql());
+        let models = list_q
             .build_query_as::<TodoModel>()
-            .fetch_all(&self.pool)
+            .fetch_all(&mut *tx)
+            .instrument(list_span)
+            .await
+            .map_err(|err| ListError::Internal(err.into()))?;
+
+        tx.commit()
             .await
             .map_err(|err| ListError::Internal(err.into()))?;
 
@@ -121,30 +294,373 @@ impl TodoRepository for PgTodoRepository {
         })
     }
 
-    async fn update(&mut self, query: UpdateQuery) -> Result<(), UpdateError> {
+    fn stream(&self, query: StreamQuery) -> TodoStream {
+        let batches = Batches {
+            pool: self.pool.clone(),
+            tenant_id: self.tenant_id,
+            title_filter: query.title.map(|t| format!("%{}%", t.as_str())),
+            after: None,
+            exhausted: false,
+        };
+
+        stream::try_unfold(batches, Batches::next)
+            .map_ok(|models| stream::iter(models.into_iter().map(Ok)))
+            .try_flatten()
+            .map(|model: Result<TodoModel, SqlxError>| match model {
+                Ok(model) => model.try_into_entity().map_err(ListError::Internal),
+                Err(err) => Err(ListError::Internal(err.into())),
+            })
+            .boxed()
+    }
+
+    #[tracing::instrument(name = "PgTodoRepository::update", level = "debug", skip_all, fields(todo.id = %todo.id()))]
+    async fn update(&mut self, todo: TodoEntity, found_at: DateTime) -> Result<(), UpdateError> {
+        // the todo is only updated when it is still the version that was found,
+        // so concurrent updates conflict instead of overwriting each other
         const UPDATE_Q: &str = r#"
             UPDATE todo
             SET title = $1, description = $2, todo_at = $3, status = $4, updated_at = $5
-            WHERE id = $6
+            WHERE id = $6 AND updated_at = $7
         "#;
+        const EXISTS_Q: &str = r#"SELECT EXISTS (SELECT 1 FROM todo WHERE id = $1)"#;
 
-        sqlx::query(UPDATE_Q)
-            .bind(query.title.into_inner())
-            .bind(query.description.map(|d| d.into_inner()))
-            .bind(query.todo_at.map(|at| at.time()))
-            .bind(TodoModelStatus::from(query.status))
-            .bind(OffsetDateTime::now_utc())
-            .bind(query.id.uuid())
-            .fetch_one(&self.pool)
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| UpdateError::Internal(err.into()))?;
+
+        let result = sqlx::query(UPDATE_Q)
+            .bind(todo.title().as_str())
+            .bind(todo.description().map(|d| d.as_str()))
+            .bind(todo.todo_at().map(|at| at.time()))
+            .bind(TodoModelStatus::from(todo.status()))
+            .bind(todo.updated_at().time())
+            .bind(todo.id().uuid())
+            .bind(found_at.time())
+            .execute(&mut *tx)
+            .instrument(statement_span(UPDATE_Q))
             .await
             .map_err(|err| match err {
                 SqlxError::Database(db_err) if db_err.is_unique_violation() => {
                     UpdateError::DuplicatedTitle
                 }
-                SqlxError::RowNotFound => UpdateError::NotFound,
                 _ => UpdateError::Internal(err.into()),
             })?;
 
-        Ok(())
+        if result.rows_affected() == 0 {
+            let exists = sqlx::query_scalar::<_, bool>(EXISTS_Q)
+                .bind(todo.id().uuid())
+                .fetch_one(&mut *tx)
+                .instrument(statement_span(EXISTS_Q))
+                .await
+                .map_err(|err| UpdateError::Internal(err.into()))?;
+
+            return Err(match exists {
+                true => UpdateError::Conflict,
+                false => UpdateError::NotFound,
+            });
+        }
+
+        outbox::record(&mut tx, self.tenant_id, &TodoChange::Updated(todo))
+            .await
+            .map_err(UpdateError::Internal)?;
+
+        tx.commit()
+            .await
+            .map_err(|err| UpdateError::Internal(err.into()))
+    }
+}
+
+/// Keyset pagination over the todos of a tenant, newest first. Every batch
+/// runs in its own transaction, so a slow consumer does not hold a connection
+struct Batches {
+    pool: PgPool,
+    tenant_id: Option<Id>,
+    title_filter: Option<String>,
+    /// Creation time and id of the last todo of the previous batch
+    after: Option<(OffsetDateTime, Uuid)>,
+    /// Whether the previous batch was partial, and so the last one
+    exhausted: bool,
+}
+
+impl Batches {
+    #[tracing::instrument(name = "PgTodoRepository::stream_batch", level = "debug", skip_all)]
+    async fn next(mut self) -> Result<Option<(Vec<TodoModel>, Self)>, SqlxError> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let mut batch_q = QueryBuilder::<Postgres>::new(r#" SELECT * FROM todo WHERE TRUE "#);
+        if let Some(constraint) = &self.title_filter {
+            batch_q
+                .push(" AND title ILIKE ")
+                .push_bind(constraint.clone());
+        }
+        if let Some((created_at, id)) = self.after {
+            batch_q
+                .push(" AND (created_at, id) < (")
+                .push_bind(created_at)
+                .push(", ")
+                .push_bind(id)
+                .push(")");
+        }
+
+        batch_q
+            .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+            .push_bind(STREAM_BATCH_SIZE);
+        let batch_span = statement_span(batch_q.sql());
+
+        let mut tx = begin_tenant(&self.pool, self.tenant_id).await?;
+        let models = batch_q
+            .build_query_as::<TodoModel>()
+            .fetch_all(&mut *tx)
+            .instrument(batch_span)
+            .await?;
+        tx.commit().await?;
+
+        if models.is_empty() {
+            return Ok(None);
+        }
+
+        self.after = models.last().map(|model| (model.created_at, model.id));
+        self.exhausted = (models.len() as i64) < STREAM_BATCH_SIZE;
+        Ok(Some((models, self)))
+    }
+}
+
+async fn insert(
+    tx: &mut Transaction<'static, Postgres>,
+    tenant_id: Option<Id>,
+    todo: &TodoEntity,
+) -> Result<(), SqlxError> {
+    const INSERT_Q: &str = r#"
+        INSERT INTO todo (id, tenant_id, title, description, todo_at, status, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    "#;
+
+    sqlx::query(INSERT_Q)
+        .bind(todo.id().uuid())
+        .bind(tenant_id.map(|id| id.uuid()))
+        .bind(todo.title().as_str())
+        .bind(todo.description().map(|d| d.as_str()))
+        .bind(todo.todo_at().map(|at| at.time()))
+        .bind(TodoModelStatus::from(todo.status()))
+        .bind(todo.created_at().time())
+        .bind(todo.updated_at().time())
+        .execute(&mut **tx)
+        .instrument(statement_span(INSERT_Q))
+        .await
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use super::*;
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::entities::todo::{Status, Title};
+    use crate::framework::storage::repositories::tenant_pool;
+
+    fn list_query() -> ListQuery {
+        ListQuery {
+            page: NonZeroU32::new(1).unwrap(),
+            per_page: NonZeroU32::new(10).unwrap(),
+            title: None,
+        }
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn tenants_are_isolated(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let mut tenant_a = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let mut tenant_b = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+
+        let todo_a = todo("Tenant A todo", Status::Todo);
+        tenant_a.create(todo_a.clone()).await.unwrap();
+
+        let list = tenant_b.list(list_query()).await.unwrap();
+        assert_eq!(list.count, 0);
+        assert!(list.items.is_empty());
+
+        assert!(matches!(
+            tenant_b.find(todo_a.id()).await,
+            Err(FindError::NotFound)
+        ));
+
+        let mut hijacked = todo_a.clone();
+        hijacked.rename(Title::new("Hijacked").unwrap());
+        assert!(matches!(
+            tenant_b.update(hijacked, todo_a.updated_at()).await,
+            Err(UpdateError::NotFound)
+        ));
+
+        assert!(matches!(
+            tenant_b.delete(todo_a.id()).await,
+            Err(DeleteError::NotFound)
+        ));
+
+        let found = tenant_a.find(todo_a.id()).await.unwrap();
+        assert_eq!(found.title().as_str(), "Tenant A todo");
+        assert_eq!(tenant_a.list(list_query()).await.unwrap().count, 1);
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn concurrent_updates_conflict(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let mut repository = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let created = todo("Concurrent todo", Status::Todo);
+        repository.create(created.clone()).await.unwrap();
+
+        let mut first = repository.find(created.id()).await.unwrap();
+        let mut second = first.clone();
+        let found_at = first.updated_at();
+
+        first.rename(Title::new("First").unwrap());
+        repository.update(first, found_at).await.unwrap();
+
+        second.rename(Title::new("Second").unwrap());
+        assert!(matches!(
+            repository.update(second, found_at).await,
+            Err(UpdateError::Conflict)
+        ));
+
+        let found = repository.find(created.id()).await.unwrap();
+        assert_eq!(found.title().as_str(), "First");
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn titles_are_unique_per_tenant(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let mut tenant_a = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let mut tenant_b = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+
+        tenant_a
+            .create(todo("Shared title", Status::Todo))
+            .await
+            .unwrap();
+        tenant_b
+            .create(todo("Shared title", Status::Todo))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            tenant_a.create(todo("Shared title", Status::Todo)).await,
+            Err(CreateError::DuplicatedTitle)
+        ));
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn missing_tenant_sees_nothing(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let mut tenant = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let mut no_tenant = PgTodoRepository::new(pool.clone());
+
+        tenant
+            .create(todo("Tenant todo", Status::Todo))
+            .await
+            .unwrap();
+
+        assert_eq!(no_tenant.list(list_query()).await.unwrap().count, 0);
+        assert!(matches!(
+            no_tenant.create(todo("Orphan todo", Status::Todo)).await,
+            Err(CreateError::Internal(..))
+        ));
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn imported_entries_are_remembered(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let tenant_id = Id::new();
+        let mut tenant = PgTodoRepository::new(pool.clone()).with_tenant(tenant_id);
+        let origin = ImportOrigin {
+            format: "ics",
+            uid: String::from("todo@example.com"),
+        };
+
+        let imported = todo("Imported todo", Status::Todo);
+        tenant
+            .import(imported.clone(), Some(origin.clone()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            tenant
+                .import(todo("Renamed upstream", Status::Todo), Some(origin.clone()))
+                .await,
+            Err(ImportError::AlreadyImported)
+        ));
+
+        let other_origin = ImportOrigin {
+            uid: String::from("other@example.com"),
+            ..origin.clone()
+        };
+        assert!(matches!(
+            tenant
+                .import(todo("Imported todo", Status::Todo), Some(other_origin))
+                .await,
+            Err(ImportError::DuplicatedTitle)
+        ));
+
+        // entries are remembered per tenant
+        let mut other_tenant = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        other_tenant
+            .import(todo("Imported todo", Status::Todo), Some(origin.clone()))
+            .await
+            .unwrap();
+
+        // deleted todos are imported again
+        tenant.delete(imported.id()).await.unwrap();
+        tenant
+            .check_import(&Title::new("Imported todo").unwrap(), Some(&origin))
+            .await
+            .unwrap();
+        tenant
+            .import(todo("Imported todo", Status::Todo), Some(origin.clone()))
+            .await
+            .unwrap();
+        assert_eq!(tenant.list(list_query()).await.unwrap().count, 1);
+        assert!(matches!(
+            tenant
+                .check_import(&Title::new("Other todo").unwrap(), Some(&origin))
+                .await,
+            Err(ImportError::AlreadyImported)
+        ));
+        assert!(matches!(
+            tenant
+                .check_import(&Title::new("Imported todo").unwrap(), None)
+                .await,
+            Err(ImportError::DuplicatedTitle)
+        ));
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn streams_are_filtered_and_ordered(pool: PgPool) {
+        let pool = tenant_pool(&pool).await;
+        let mut tenant = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+        let other_tenant = PgTodoRepository::new(pool.clone()).with_tenant(Id::new());
+
+        for title in ["Buy milk", "Walk the dog", "Buy eggs"] {
+            tenant.create(todo(title, Status::Todo)).await.unwrap();
+        }
+
+        let query = StreamQuery {
+            title: Some(Title::new("buy").unwrap()),
+        };
+        let titles = tenant
+            .stream(query.clone())
+            .map_ok(|todo| todo.title().to_string())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(titles, ["Buy eggs", "Buy milk"]);
+
+        let others = other_tenant.stream(query).try_collect::<Vec<_>>().await;
+        assert!(others.unwrap().is_empty());
     }
 }
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::Duration;
+
+use sqlx::types::time::OffsetDateTime;
+use sqlx::types::Uuid;
+use sqlx::{Error as SqlxError, FromRow, PgPool, Postgres, QueryBuilder, Transaction};
+use tracing::Instrument;
+
+use super::begin_tenant;
+use crate::application::repositories::webhook::{
+    CreateError, DeleteError, DeliveriesQuery, FindError, ListDeliveriesError, ListError,
+    ReplayError, UpdateError, WebhookRepository,
+};
+use crate::domain::entities::webhook::{
+    DeliveryAttempt, DeliveryEntity, WebhookEntity, WebhookEvent,
+};
+use crate::domain::types::Id;
+use crate::framework::storage::models::webhook::{
+    AttemptModel, DeliveryModel, DeliveryStatus as DeliveryModelStatus, WebhookModel,
+};
+use crate::framework::storage::{begin, statement_span};
+
+#[derive(Clone)]
+pub struct PgWebhookRepository {
+    pool: PgPool,
+    tenant_id: Option<Id>,
+}
+
+impl PgWebhookRepository {
+    /// Maximum amount of deliveries listed at once, starting from the newest
+    const DELIVERIES_LIMIT: i64 = 100;
+
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            tenant_id: None,
+        }
+    }
+
+    /// Scope every query of the repository to the tenant with `tenant_id`.
+    /// Without a tenant, row level security hides every row.
+    pub fn with_tenant(mut self, tenant_id: Id) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
+    async fn begin(&self) -> Result<Transaction<'static, Postgres>, SqlxError> {
+        begin_tenant(&self.pool, self.tenant_id).await
+    }
+}
+
+impl WebhookRepository for PgWebhookRepository {
+    async fn create(&mut self, webhook: WebhookEntity) -> Result<(), CreateError> {
+        const INSERT_Q: &str = r#"
+            INSERT INTO webhook (id, tenant_id, url, events, secret, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+        "#;
+
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| CreateError::Internal(err.into()))?;
+
+        let events = webhook
+            .events
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>();
+
+        sqlx::query(INSERT_Q)
+            .bind(webhook.id().uuid())
+            .bind(self.tenant_id.map(|id| id.uuid()))
+            .bind(webhook.url.as_str())
+            .bind(events)
+            .bind(webhook.secret.as_str())
+            .bind(webhook.created_at().time())
+            .execute(&mut *tx)
+            .instrument(statement_span(INSERT_Q))
+            .await
+            .map_err(|err| CreateError::Internal(err.into()))?;
+
+        tx.commit()
+            .await
+            .map_err(|err| CreateError::Internal(err.into()))
+    }
+
+    async fn delete(&mut self, webhook_id: Id) -> Result<(), DeleteError> {
+        const DELETE_Q: &str = "DELETE FROM webhook WHERE id = $1 RETURNING id";
+
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| DeleteError::Internal(err.into()))?;
+
+        sqlx::query_scalar::<_, Uuid>(DELETE_Q)
+            .bind(webhook_id.uuid())
+            .fetch_one(&mut *tx)
+            .instrument(statement_span(DELETE_Q))
+            .await
+            .map_err(|err| match err {
+                SqlxError::RowNotFound => DeleteError::NotFound,
+                _ => DeleteError::Internal(err.into()),
+            })?;
+
+        tx.commit()
+            .await
+            .map_err(|err| DeleteError::Internal(err.into()))
+    }
+
+    async fn find(&self, webhook_id: Id) -> Result<WebhookEntity, FindError> {
+        const FIND_Q: &str = "SELECT * FROM webhook WHERE id = $1";
+
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| FindError::Internal(err.into()))?;
+
+        let model = sqlx::query_as::<_, WebhookModel>(FIND_Q)
+            .bind(webhook_id.uuid())
+            .fetch_one(&mut *tx)
+            .instrument(statement_span(FIND_Q))
+            .await
+            .map_err(|err| match err {
+                SqlxError::RowNotFound => FindError::NotFound,
+                _ => FindError::Internal(err.into()),
+            })?;
+
+        tx.commit()
+            .await
+            .map_err(|err| FindError::Internal(err.into()))?;
+
+        model.try_into_entity().map_err(FindError::Internal)
+    }
+
+    async fn list(&self) -> Result<Vec<WebhookEntity>, ListError> {
+        const LIST_Q: &str = "SELECT * FROM webhook ORDER BY created_at";
+
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| ListError::Internal(err.into()))?;
+
+        let models = sqlx::query_as::<_, WebhookModel>(LIST_Q)
+            .fetch_all(&mut *tx)
+            .instrument(statement_span(LIST_Q))
+            .await
+            .map_err(|err| ListError::Internal(err.into()))?;
+
+        tx.commit()
+            .await
+            .map_err(|err| ListError::Internal(err.into()))?;
+
+        models
+            .into_iter()
+            .map(WebhookModel::try_into_entity)
+            .collect::<Result<Vec<WebhookEntity>, Box<dyn Error>>>()
+            .map_err(ListError::Internal)
+    }
+
+    async fn list_deliveries(
+        &self,
+        query: DeliveriesQuery,
+    ) -> Result<Vec<DeliveryEntity>, ListDeliveriesError> {
+        const EXISTS_Q: &str = "SELECT EXISTS (SELECT 1 FROM webhook WHERE id = $1)";
+        const ATTEMPTS_Q: &str = r#"
+            SELECT * FROM webhook_attempt
+            WHERE delivery_id = ANY($1)
+            ORDER BY attempted_at
+        "#;
+
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| ListDeliveriesError::Internal(err.into()))?;
+
+        let exists = sqlx::query_scalar::<_, bool>(EXISTS_Q)
+            .bind(query.webhook_id.uuid())
+            .fetch_one(&mut *tx)
+            .instrument(statement_span(EXISTS_Q))
+            .await
+            .map_err(|err| ListDeliveriesError::Internal(err.into()))?;
+
+        if !exists {
+            return Err(ListDeliveriesError::NotFound);
+        }
+
+        let mut deliveries_q =
+            QueryBuilder::<Postgres>::new(" SELECT * FROM webhook_delivery WHERE webhook_id = ");
+        deliveries_q.push_bind(query.webhook_id.uuid());
+        if let Some(status) = query.status {
+            deliveries_q
+                .push(" AND status = ")
+                .push_bind(DeliveryModelStatus::from(status));
+        }
+
+        deliveries_q
+            .push(" ORDER BY created_at DESC LIMIT ")
+            .push_bind(Self::DELIVERIES_LIMIT);
+        let deliveries_span = statement_span(deliveries_q.sql());
+        let deliveries = deliveries_q
+            .build_query_as::<DeliveryModel>()
+            .fetch_all(&mut *tx)
+            .instrument(deliveries_span)
+            .await
+            .map_err(|err| ListDeliveriesError::Internal(err.into()))?;
+
+        let delivery_ids = deliveries.iter().map(|d| d.id).collect::<Vec<Uuid>>();
+        let attempts = sqlx::query_as::<_, AttemptModel>(ATTEMPTS_Q)
+            .bind(delivery_ids)
+            .fetch_all(&mut *tx)
+            .instrument(statement_span(ATTEMPTS_Q))
+            .await
+            .map_err(|err| ListDeliveriesError::Internal(err.into()))?;
+
+        tx.commit()
+            .await
+            .map_err(|err| ListDeliveriesError::Internal(err.into()))?;
+
+        let mut attempts_by_delivery = HashMap::<Uuid, Vec<DeliveryAttempt>>::new();
+        for attempt in attempts {
+            let delivery_id = attempt.delivery_id;
+            let attempt = attempt
+                .try_into_entity()
+                .map_err(ListDeliveriesError::Internal)?;
+            attempts_by_delivery
+                .entry(delivery_id)
+                .or_default()
+                .push(attempt);
+        }
+
+        deliveries
+            .into_iter()
+            .map(|delivery| {
+                let attempts = attempts_by_delivery
+                    .remove(&delivery.id)
+                    .unwrap_or_default();
+                delivery.try_into_entity(attempts)
+            })
+            .collect::<Result<Vec<DeliveryEntity>, Box<dyn Error>>>()
+            .map_err(ListDeliveriesError::Internal)
+    }
+
+    async fn replay_delivery(&mut self, delivery_id: Id) -> Result<(), ReplayError> {
+        const REPLAY_Q: &str = r#"
+            UPDATE webhook_delivery
+            SET status = 'pending', attempt_count = 0, next_attempt_at = now(),
+                last_error = NULL, updated_at = now()
+            WHERE id = $1
+            RETURNING id
+        "#;
+
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| ReplayError::Internal(err.into()))?;
+
+        sqlx::query_scalar::<_, Uuid>(REPLAY_Q)
+            .bind(delivery_id.uuid())
+            .fetch_one(&mut *tx)
+            .instrument(statement_span(REPLAY_Q))
+            .await
+            .map_err(|err| match err {
+                SqlxError::RowNotFound => ReplayError::NotFound,
+                _ => ReplayError::Internal(err.into()),
+            })?;
+
+        tx.commit()
+            .await
+            .map_err(|err| ReplayError::Internal(err.into()))
+    }
+
+    async fn update(&mut self, webhook: WebhookEntity) -> Result<(), UpdateError> {
+        const UPDATE_Q: &str = r#"
+            UPDATE webhook
+            SET url = $2, events = $3, secret = $4, disabled_reason = $5
+            WHERE id = $1
+            RETURNING id
+        "#;
+
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|err| UpdateError::Internal(err.into()))?;
+
+        let events = webhook
+            .events
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>();
+
+        sqlx::query_scalar::<_, Uuid>(UPDATE_Q)
+            .bind(webhook.id().uuid())
+            .bind(webhook.url.as_str())
+            .bind(events)
+            .bind(webhook.secret.as_str())
+            .bind(webhook.disabled_reason())
+            .fetch_one(&mut *tx)
+            .instrument(statement_span(UPDATE_Q))
+            .await
+            .map_err(|err| match err {
+                SqlxError::RowNotFound => UpdateError::NotFound,
+                _ => UpdateError::Internal(err.into()),
+            })?;
+
+        tx.commit()
+            .await
+            .map_err(|err| UpdateError::Internal(err.into()))
+    }
+}
+
+/// Delivery claimed by the worker, along with where and how to deliver it
+#[derive(Clone, Debug, FromRow)]
+pub struct DueDelivery {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub event: String,
+    /// JSON body of the delivery
+    pub payload: String,
+    pub attempt_count: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Result of attempting a delivery
+#[derive(Clone, Debug)]
+pub struct AttemptOutcome {
+    pub attempted_at: OffsetDateTime,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub duration: Duration,
+    pub next: NextStep,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NextStep {
+    Delivered,
+    RetryAt(OffsetDateTime),
+    Dead,
+}
+
+/// Queue of webhook deliveries shared by every tenant, which is meant to be
+/// used by the delivery worker only. Deliveries are claimed across tenants by
+/// a database function, while everything else is scoped to their tenant
+#[derive(Clone)]
+pub struct PgDeliveryQueue {
+    pool: PgPool,
+}
+
+impl PgDeliveryQueue {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create a pending delivery of `payload` for every enabled webhook of the
+    /// tenant interested in `event`, returning how many were created. Webhooks
+    /// that already have a delivery of the outbox event with `outbox_id` are
+    /// skipped
+    pub async fn enqueue(
+        &self,
+        outbox_id: Id,
+        tenant_id: Id,
+        event: WebhookEvent,
+        payload: &str,
+    ) -> Result<u64, SqlxError> {
+        const ENQUEUE_Q: &str = r#"
+            INSERT INTO webhook_delivery (
+                id, tenant_id, webhook_id, outbox_id, event, payload, status,
+                attempt_count, next_attempt_at, created_at, updated_at
+            )
+            SELECT gen_random_uuid(), w.tenant_id, w.id, $1, $2, $3::jsonb, 'pending', 0, now(), now(), now()
+            FROM webhook AS w
+            WHERE w.disabled_reason IS NULL
+                AND (cardinality(w.events) = 0 OR $2 = ANY(w.events))
+            ON CONFLICT (webhook_id, outbox_id) DO NOTHING
+        "#;
+
+        let mut tx = begin_tenant(&self.pool, Some(tenant_id)).await?;
+        let result = sqlx::query(ENQUEUE_Q)
+            .bind(outbox_id.uuid())
+            .bind(event.to_string())
+            .bind(payload)
+            .execute(&mut *tx)
+            .instrument(statement_span(ENQUEUE_Q))
+            .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Claim up to `limit` due deliveries of every tenant, which are hidden
+    /// from other claims for `lease` so the same delivery is not attempted
+    /// concurrently
+    pub async fn claim(&self, limit: i64, lease: Duration) -> Result<Vec<DueDelivery>, SqlxError> {
+        const CLAIM_Q: &str = "SELECT * FROM claim_webhook_deliveries($1, $2)";
+
+        let mut tx = begin(&self.pool).await?;
+        let deliveries = sqlx::query_as::<_, DueDelivery>(CLAIM_Q)
+            .bind(limit)
+            .bind(lease.as_secs_f64())
+            .fetch_all(&mut *tx)
+            .instrument(statement_span(CLAIM_Q))
+            .await?;
+        tx.commit().await?;
+
+        Ok(deliveries)
+    }
+
+    /// Record an attempt of `delivery` and schedule what happens next
+    pub async fn record(
+        &self,
+        delivery: &DueDelivery,
+        outcome: &AttemptOutcome,
+    ) -> Result<(), SqlxError> {
+        const INSERT_ATTEMPT_Q: &str = r#"
+            INSERT INTO webhook_attempt (id, tenant_id, delivery_id, attempted_at, status_code, error, duration_ms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#;
+        const UPDATE_DELIVERY_Q: &str = r#"
+            UPDATE webhook_delivery
+            SET status = $2, attempt_count = attempt_count + 1, next_attempt_at = $3,
+                last_error = $4, updated_at = now()
+            WHERE id = $1
+        "#;
+
+        let (status, next_attempt_at) = match outcome.next {
+            NextStep::Delivered => (DeliveryModelStatus::Delivered, None),
+            NextStep::RetryAt(at) => (DeliveryModelStatus::Pending, Some(at)),
+            NextStep::Dead => (DeliveryModelStatus::Dead, None),
+        };
+        let duration_ms = i32::try_from(outcome.duration.as_millis()).unwrap_or(i32::MAX);
+
+        let mut tx = begin_tenant(&self.pool, Some(delivery.tenant_id.into())).await?;
+        sqlx::query(INSERT_ATTEMPT_Q)
+            .bind(Id::new().uuid())
+            .bind(delivery.tenant_id)
+            .bind(delivery.id)
+            .bind(outcome.attempted_at)
+            .bind(outcome.status_code.map(i32::from))
+            .bind(outcome.error.as_deref())
+            .bind(duration_ms)
+            .execute(&mut *tx)
+            .instrument(statement_span(INSERT_ATTEMPT_Q))
+            .await?;
+
+        sqlx::query(UPDATE_DELIVERY_Q)
+            .bind(delivery.id)
+            .bind(status)
+            .bind(next_attempt_at)
+            .bind(outcome.error.as_deref())
+            .execute(&mut *tx)
+            .instrument(statement_span(UPDATE_DELIVERY_Q))
+            .await?;
+
+        tx.commit().await
+    }
+}
@@ -0,0 +1,280 @@
+use std::time::Duration;
+
+use axum::extract::MatchedPath;
+use axum::http::{HeaderMap, HeaderName, Request, Response};
+use opentelemetry::propagation::{Extractor, TextMapPropagator};
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use tower_http::trace::{DefaultOnResponse, MakeSpan, OnResponse};
+use tracing::{Level, Span, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::framework::config::TelemetryConfig;
+use crate::framework::rest_api::request_id::RequestId;
+
+/// Path of the collector that receives traces, under its base URL
+const TRACES_PATH: &str = "/v1/traces";
+
+/// Target of the spans of the server, the only ones exported
+const TARGET: &str = env!("CARGO_CRATE_NAME");
+
+/// Create the provider of tracers that export spans in batches to the
+/// collector of `config`, none when no collector is configured
+pub fn create_provider(config: &TelemetryConfig) -> Result<Option<TracerProvider>, TraceError> {
+    let Some(endpoint) = &config.otlp_endpoint else {
+        return Ok(None);
+    };
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(format!("{endpoint}{TRACES_PATH}"))
+        .build()?;
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio)));
+
+    Ok(Some(
+        TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_sampler(sampler)
+            .with_resource(Resource::new([KeyValue::new(
+                "service.name",
+                config.service_name.clone(),
+            )]))
+            .build(),
+    ))
+}
+
+/// Layer exporting the spans of the server with the tracers of `provider`,
+/// including the debug ones of controllers, use cases and repositories
+/// which are too detailed for logs
+pub fn layer<S>(provider: &TracerProvider) -> impl Layer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer(TARGET))
+        .with_filter(Targets::new().with_target(TARGET, Level::DEBUG))
+}
+
+/// Flush the spans not exported yet and stop exporting
+pub async fn shutdown(provider: TracerProvider) {
+    // the batch exporter blocks until it is flushed
+    let shutdown = tokio::task::spawn_blocking(move || provider.shutdown());
+    match shutdown.await {
+        Ok(Ok(())) => tracing::info!("Traces flushed"),
+        Ok(Err(err)) => tracing::error!("Failed flushing traces: {err}"),
+        Err(err) => tracing::error!("Failed flushing traces: {err}"),
+    }
+}
+
+/// Makes the span of HTTP requests, named after their route template and
+/// continuing the trace of their W3C `traceparent` header when they have one.
+/// It records the request id, which every span and log of the request is
+/// nested under
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RequestSpan;
+
+impl<B> MakeSpan<B> for RequestSpan {
+    fn make_span(&mut self, req: &Request<B>) -> Span {
+        let route = req
+            .extensions()
+            .get::<MatchedPath>()
+            .map(MatchedPath::as_str);
+        let request_id = req.extensions().get::<RequestId>().map(RequestId::as_str);
+        let name = match route {
+            Some(route) => format!("{} {route}", req.method()),
+            None => req.method().to_string(),
+        };
+
+        let span = tracing::info_span!(
+            "request",
+            request_id,
+            method = %req.method(),
+            uri = %req.uri(),
+            version = ?req.version(),
+            otel.name = name,
+            otel.kind = "server",
+            http.route = route,
+            http.response.status_code = tracing::field::Empty,
+            otel.status_code = tracing::field::Empty,
+        );
+
+        let parent = TraceContextPropagator::new().extract(&HeaderExtractor(req.headers()));
+        span.set_parent(parent);
+        span
+    }
+}
+
+/// Records the status of responses on the span of their request, which
+/// fails on server errors, before logging them
+#[derive(Clone, Debug)]
+pub struct ResponseStatus(pub DefaultOnResponse);
+
+impl<B> OnResponse<B> for ResponseStatus {
+    fn on_response(self, res: &Response<B>, latency: Duration, span: &Span) {
+        span.record("http.response.status_code", res.status().as_u16());
+        if res.status().is_server_error() {
+            span.record("otel.status_code", "ERROR");
+        }
+
+        self.0.on_response(res, latency, span);
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::middleware;
+    use opentelemetry::trace::{SpanId, TraceId};
+    use opentelemetry_sdk::export::trace::SpanData;
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use sqlx::postgres::PgPoolOptions;
+    use sqlx::PgPool;
+    use tokio_util::sync::CancellationToken;
+    use tower::ServiceExt;
+    use tower_http::trace::TraceLayer;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::domain::types::Id;
+    use crate::framework::events::todo::TodoBroadcaster;
+    use crate::framework::rest_api::redaction::Redaction;
+    use crate::framework::rest_api::routes::todo;
+    use crate::framework::rest_api::tenant::{self, TENANT_HEADER};
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    fn span<'a>(spans: &'a [SpanData], name: &str) -> &'a SpanData {
+        spans
+            .iter()
+            .find(|span| span.name == name)
+            .unwrap_or_else(|| panic!("No span named {name}"))
+    }
+
+    fn attribute(span: &SpanData, key: &str) -> String {
+        span.attributes
+            .iter()
+            .find(|attribute| attribute.key.as_str() == key)
+            .map(|attribute| attribute.value.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Find a todo of a new tenant through the todo routes, returning the
+    /// status of the response and the spans it was traced with
+    async fn trace_find(pool: PgPool) -> (StatusCode, Vec<SpanData>) {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry().with(layer(&provider));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = todo::create_router(
+            pool,
+            TodoBroadcaster::new(1),
+            CancellationToken::new(),
+            Redaction::new(true),
+            64 << 10,
+        )
+        .layer(middleware::from_fn_with_state(
+            tenant::tests::trusting_header(),
+            tenant::authenticate,
+        ))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(RequestSpan)
+                .on_response(ResponseStatus(DefaultOnResponse::new())),
+        );
+        let req = Request::builder()
+            .uri(format!("/todos/{}", Id::new()))
+            .header(TENANT_HEADER, Id::new().to_string())
+            .header("traceparent", TRACEPARENT)
+            .body(Body::empty())
+            .unwrap();
+        let res = app.oneshot(req).await.unwrap();
+        let status = res.status();
+        drop(res);
+
+        let _ = provider.force_flush();
+        (status, exporter.get_finished_spans().unwrap())
+    }
+
+    /// Assert the spans of the request are nested from the incoming request
+    /// down to the repository, returning the span of the repository
+    fn assert_nested<'a>(spans: &'a [SpanData], status: &str) -> &'a SpanData {
+        let request = span(spans, "GET /todos/:id");
+        assert_eq!(
+            request.span_context.trace_id(),
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap()
+        );
+        assert_eq!(
+            request.parent_span_id,
+            SpanId::from_hex("00f067aa0ba902b7").unwrap()
+        );
+        assert_eq!(attribute(request, "http.response.status_code"), status);
+
+        let controller = span(spans, "FindTodoController::run");
+        assert_eq!(controller.parent_span_id, request.span_context.span_id());
+        let use_case = span(spans, "FindTodoUseCase::exec");
+        assert_eq!(use_case.parent_span_id, controller.span_context.span_id());
+        let repository = span(spans, "PgTodoRepository::find");
+        assert_eq!(repository.parent_span_id, use_case.span_context.span_id());
+
+        assert!(spans
+            .iter()
+            .all(|span| span.span_context.trace_id() == request.span_context.trace_id()));
+        repository
+    }
+
+    #[tokio::test]
+    async fn requests_are_traced_down_to_repositories() {
+        // nothing listens on the port, so the repository fails on connecting
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("postgres://localhost:1/unused")
+            .unwrap();
+
+        let (status, spans) = trace_find(pool).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_nested(&spans, "500");
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn requests_are_traced_down_to_statements(pool: PgPool) {
+        let (status, spans) = trace_find(pool).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let repository = assert_nested(&spans, "404");
+        let statements: Vec<_> = spans
+            .iter()
+            .filter(|span| span.parent_span_id == repository.span_context.span_id())
+            .map(|span| attribute(span, "db.statement"))
+            .collect();
+        assert!(statements
+            .iter()
+            .any(|statement| statement.starts_with("SELECT set_config('app.tenant_id'")));
+        assert!(statements
+            .iter()
+            .any(|statement| statement.contains("FROM todo as t WHERE t.id = $1")));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod receivers;
+pub mod signature;
+pub mod worker;
\ No newline at end of file
@@ -0,0 +1,153 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+
+/// Receivers deliveries may be sent to. Urls are checked when delivering
+/// rather than only when webhooks are created, as the addresses their host
+/// resolves to may have changed since, and hosts are resolved by the http
+/// client through [`Resolve`] so the checked addresses are the ones connected
+/// to
+#[derive(Clone, Copy, Debug)]
+pub struct Receivers {
+    allow_local: bool,
+}
+
+impl Receivers {
+    /// Receivers served over https from public addresses, or any receiver
+    /// when `allow_local`, which only suits tests and local development
+    pub fn new(allow_local: bool) -> Self {
+        Self { allow_local }
+    }
+
+    /// Check the scheme of a receiver url, and its address when its host is
+    /// one, since only domains are resolved
+    pub fn check(&self, url: &str) -> Result<(), String> {
+        let url = Url::parse(url).map_err(|err| format!("Receiver url is invalid: {err}"))?;
+        if self.allow_local {
+            return Ok(());
+        }
+
+        if url.scheme() != "https" {
+            return Err(String::from("Receiver url must use https"));
+        }
+        let host = url.host_str().unwrap_or_default();
+        match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+            Ok(ip) if !is_public(ip) => Err(format!("Receiver address {ip} is not public")),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Resolve for Receivers {
+    fn resolve(&self, name: Name) -> Resolving {
+        let allow_local = self.allow_local;
+        let host = name.as_str().to_owned();
+
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| allow_local || is_public(addr.ip()))
+                .collect::<Vec<_>>();
+
+            if addrs.is_empty() {
+                return Err(
+                    format!("Receiver host {host} does not resolve to a public address").into(),
+                );
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether an address is reachable from the internet, rather than being a
+/// loopback, private, link-local, unspecified or otherwise reserved address
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    // 0.0.0.0/8 is this network and 100.64.0.0/10 is shared by carriers
+    let reserved = first == 0 || (first == 100 && second & 0xc0 == 64);
+
+    !(reserved
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation())
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    // fc00::/7 is unique local and fe80::/10 is link-local
+    let reserved = first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80;
+
+    !(reserved || ip.is_loopback() || ip.is_unspecified() || ip.is_multicast())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_public_addresses_are_public() {
+        for ip in ["93.184.215.14", "2606:2800:21f:cb07:6820:80da:af6b:8b2c"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+
+        let local = [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ];
+        for ip in local {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn urls_must_be_https_to_public_addresses() {
+        let receivers = Receivers::new(false);
+        assert!(receivers.check("https://example.com/hooks").is_ok());
+
+        for url in [
+            "http://example.com/hooks",
+            "https://127.0.0.1/hooks",
+            "https://[::1]:8443/hooks",
+            "https://169.254.169.254/latest/meta-data",
+        ] {
+            assert!(receivers.check(url).is_err(), "{url}");
+        }
+
+        assert!(Receivers::new(true).check("http://127.0.0.1/hooks").is_ok());
+    }
+
+    #[tokio::test]
+    async fn hosts_resolving_to_local_addresses_are_rejected() {
+        let name = "localhost".parse::<Name>().unwrap();
+        assert!(Receivers::new(false).resolve(name).await.is_err());
+
+        let name = "localhost".parse::<Name>().unwrap();
+        let addrs = Receivers::new(true).resolve(name).await.unwrap();
+        assert!(addrs.into_iter().all(|addr| addr.ip().is_loopback()));
+    }
+}
@@ -0,0 +1,651 @@
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::header;
+use futures_util::future::{self, BoxFuture};
+use reqwest::redirect::Policy;
+use reqwest::Client;
+use serde_json::json;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::PgPool;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant};
+use tokio_util::sync::CancellationToken;
+
+use super::receivers::Receivers;
+use super::signature::{self, EVENT_HEADER, ID_HEADER, SIGNATURE_HEADER};
+use crate::adapters::presenters::json::todo::TodoView;
+use crate::application::publishers::todo::TodoChange;
+use crate::domain::entities::webhook::WebhookEvent;
+use crate::framework::outbox::{OutboxEvent, OutboxSink, SinkError};
+use crate::framework::storage::repositories::webhook::{
+    AttemptOutcome, DueDelivery, NextStep, PgDeliveryQueue,
+};
+
+#[derive(Clone, Debug)]
+pub struct WebhookConfig {
+    /// Attempts before a delivery is dead
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on every following retry
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Time the receiver has to respond
+    pub timeout: Duration,
+    /// Deliveries attempted concurrently
+    pub batch_size: i64,
+    /// How often due deliveries are checked when none were created
+    pub poll_interval: Duration,
+    /// Whether receivers may be served over http or from loopback, private
+    /// and link-local addresses, which only suits tests and local development
+    pub allow_local_receivers: bool,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 8,
+            base_backoff: Duration::from_secs(10),
+            max_backoff: Duration::from_secs(60 * 60),
+            timeout: Duration::from_secs(10),
+            batch_size: 16,
+            poll_interval: Duration::from_secs(1),
+            allow_local_receivers: false,
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Delay before the attempt following `attempt`, starting from 1
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// [`OutboxSink`] that turns relayed changes into deliveries of the tenant
+/// webhooks, creating a single delivery per webhook for each change
+#[derive(Clone)]
+pub struct WebhookSink {
+    queue: PgDeliveryQueue,
+    notify: Arc<Notify>,
+}
+
+impl OutboxSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhooks"
+    }
+
+    fn dispatch<'a>(&'a self, event: &'a OutboxEvent) -> BoxFuture<'a, Result<(), SinkError>> {
+        Box::pin(async move {
+            let webhook_event = match event.change {
+                TodoChange::Created(_) => WebhookEvent::TodoCreated,
+                TodoChange::Updated(_) => WebhookEvent::TodoUpdated,
+                TodoChange::Deleted(_) => WebhookEvent::TodoDeleted,
+            };
+
+            let payload = json!({
+                "event": webhook_event.to_string(),
+                "occurredAt": event.occurred_at.to_rfc3339(),
+                "todo": TodoView::from(event.change.todo().clone()),
+            });
+
+            let created = self
+                .queue
+                .enqueue(
+                    event.id,
+                    event.tenant_id,
+                    webhook_event,
+                    &payload.to_string(),
+                )
+                .await?;
+            if created > 0 {
+                self.notify.notify_one();
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Spawn the worker that attempts webhook deliveries, returning the sink that
+/// creates them and the task of the worker.
+///
+/// Once `shutdown` is cancelled, the worker finishes the attempts it started
+/// and stops, leaving the other deliveries for the next start
+pub fn start(
+    pool: PgPool,
+    config: WebhookConfig,
+    shutdown: CancellationToken,
+) -> (WebhookSink, JoinHandle<()>) {
+    let queue = PgDeliveryQueue::new(pool);
+    let notify = Arc::new(Notify::new());
+
+    let receivers = Receivers::new(config.allow_local_receivers);
+    let client = create_client(receivers, config.timeout);
+
+    let worker = tokio::spawn(attempt_deliveries(
+        queue.clone(),
+        client,
+        receivers,
+        config,
+        notify.clone(),
+        shutdown,
+    ));
+
+    (WebhookSink { queue, notify }, worker)
+}
+
+/// Create the client of deliveries, which only connects to allowed receivers.
+/// Redirects are not followed, as they could lead to any address, and proxies
+/// are not used, as they would resolve the hosts instead of `receivers`
+fn create_client(receivers: Receivers, timeout: Duration) -> Client {
+    Client::builder()
+        .redirect(Policy::none())
+        .no_proxy()
+        .dns_resolver(Arc::new(receivers))
+        .timeout(timeout)
+        .build()
+        .expect("Failed building webhook http client")
+}
+
+async fn attempt_deliveries(
+    queue: PgDeliveryQueue,
+    client: Client,
+    receivers: Receivers,
+    config: WebhookConfig,
+    notify: Arc<Notify>,
+    shutdown: CancellationToken,
+) {
+    // a claimed delivery is hidden from other workers for longer than an attempt can take
+    let lease = config.timeout * 2;
+
+    while !shutdown.is_cancelled() {
+        match queue.claim(config.batch_size, lease).await {
+            Ok(deliveries) => {
+                let claimed = deliveries.len();
+                let attempts = deliveries
+                    .into_iter()
+                    .map(|delivery| attempt(&queue, &client, receivers, &config, delivery));
+                future::join_all(attempts).await;
+
+                // keep going while there may be more due deliveries
+                if claimed as i64 == config.batch_size {
+                    continue;
+                }
+            }
+            Err(err) => tracing::error!("Failed claiming webhook deliveries: {err}"),
+        }
+
+        tokio::select! {
+            _ = notify.notified() => {}
+            _ = time::sleep(config.poll_interval) => {}
+            _ = shutdown.cancelled() => {}
+        }
+    }
+
+    tracing::info!("Webhook worker stopped");
+}
+
+async fn attempt(
+    queue: &PgDeliveryQueue,
+    client: &Client,
+    receivers: Receivers,
+    config: &WebhookConfig,
+    delivery: DueDelivery,
+) {
+    let attempted_at = OffsetDateTime::now_utc();
+    let started = Instant::now();
+    let (status_code, error) = deliver(client, receivers, &delivery, attempted_at).await;
+    let duration = started.elapsed();
+
+    let attempt = u32::try_from(delivery.attempt_count).unwrap_or(0) + 1;
+    let next = match error {
+        None => NextStep::Delivered,
+        Some(_) if attempt >= config.max_attempts => NextStep::Dead,
+        Some(_) => NextStep::RetryAt(attempted_at + config.backoff(attempt)),
+    };
+
+    match next {
+        NextStep::Delivered => tracing::info!(
+            delivery.id = %delivery.id,
+            tenant.id = %delivery.tenant_id,
+            attempt,
+            "Webhook delivery delivered"
+        ),
+        NextStep::RetryAt(at) => tracing::warn!(
+            delivery.id = %delivery.id,
+            tenant.id = %delivery.tenant_id,
+            attempt,
+            retry_at = %at,
+            "Webhook delivery attempt failed, retrying"
+        ),
+        NextStep::Dead => tracing::error!(
+            delivery.id = %delivery.id,
+            tenant.id = %delivery.tenant_id,
+            attempt,
+            "Webhook delivery failed after its last attempt"
+        ),
+    }
+
+    let outcome = AttemptOutcome {
+        attempted_at,
+        status_code,
+        error,
+        duration,
+        next,
+    };
+
+    if let Err(err) = queue.record(&delivery, &outcome).await {
+        tracing::error!(
+            delivery.id = %delivery.id,
+            tenant.id = %delivery.tenant_id,
+            error = %err,
+            "Failed recording webhook delivery attempt"
+        );
+    }
+}
+
+/// Post a delivery to its receiver, returning the status code of the response
+/// when there is one and the error when it failed
+async fn deliver(
+    client: &Client,
+    receivers: Receivers,
+    delivery: &DueDelivery,
+    attempted_at: OffsetDateTime,
+) -> (Option<u16>, Option<String>) {
+    if let Err(reason) = receivers.check(&delivery.url) {
+        return (None, Some(reason));
+    }
+
+    let signature = signature::sign(
+        &delivery.secret,
+        attempted_at.unix_timestamp(),
+        delivery.payload.as_bytes(),
+    );
+    let result = client
+        .post(&delivery.url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(ID_HEADER, delivery.id.to_string())
+        .header(EVENT_HEADER, &delivery.event)
+        .header(SIGNATURE_HEADER, signature)
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    match result {
+        Ok(res) if res.status().is_success() => (Some(res.status().as_u16()), None),
+        Ok(res) => {
+            let status = res.status();
+            (
+                Some(status.as_u16()),
+                Some(format!("Receiver responded with {status}")),
+            )
+        }
+        Err(err) => (None, Some(describe(&err))),
+    }
+}
+
+/// Message of an error followed by the ones of its sources, which tell why
+/// requests could not be sent
+fn describe(err: &dyn Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(&format!(": {err}"));
+        source = err.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::response::Redirect;
+    use axum::routing::post;
+    use axum::Router;
+    use sqlx::Executor;
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::application::repositories::webhook::{DeliveriesQuery, WebhookRepository};
+    use crate::domain::entities::todo::fixtures::todo;
+    use crate::domain::entities::todo::{Status, TodoEntity};
+    use crate::domain::entities::webhook::{
+        DeliveryEntity, DeliveryStatus, NewProps, WebhookEntity, WebhookSecret, WebhookUrl,
+    };
+    use crate::domain::types::{DateTime, Id};
+    use crate::framework::storage::repositories::webhook::PgWebhookRepository;
+
+    const SECRET: &str = "0123456789abcdef";
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let config = WebhookConfig {
+            base_backoff: Duration::from_secs(10),
+            max_backoff: Duration::from_secs(60),
+            ..WebhookConfig::default()
+        };
+
+        let delays = (1..=5).map(|attempt| config.backoff(attempt).as_secs());
+        assert_eq!(delays.collect::<Vec<_>>(), [10, 20, 40, 60, 60]);
+    }
+
+    #[derive(Clone, Default)]
+    struct Receiver {
+        /// Status codes to respond with, in order, responding `200` when empty
+        responses: Arc<Mutex<Vec<StatusCode>>>,
+        received: Arc<Mutex<Vec<(HeaderMap, Bytes)>>>,
+    }
+
+    async fn receive(
+        State(receiver): State<Receiver>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        receiver.received.lock().unwrap().push((headers, body));
+        let mut responses = receiver.responses.lock().unwrap();
+        match responses.is_empty() {
+            true => StatusCode::OK,
+            false => responses.remove(0),
+        }
+    }
+
+    /// Serve a stand-in receiver, returning its url. Deliveries posted to
+    /// `/redirect` are redirected to `/hooks`
+    async fn serve(receiver: Receiver) -> String {
+        let app = Router::new()
+            .route("/hooks", post(receive))
+            .route(
+                "/redirect",
+                post(|| async { Redirect::temporary("/hooks") }),
+            )
+            .with_state(receiver);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app.into_make_service()).await });
+        format!("http://{addr}/hooks")
+    }
+
+    fn config() -> WebhookConfig {
+        WebhookConfig {
+            max_attempts: 3,
+            base_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_millis(50),
+            timeout: Duration::from_secs(2),
+            poll_interval: Duration::from_millis(20),
+            allow_local_receivers: true,
+            ..WebhookConfig::default()
+        }
+    }
+
+    fn due_delivery(url: String) -> DueDelivery {
+        DueDelivery {
+            id: Id::new().uuid(),
+            tenant_id: Id::new().uuid(),
+            event: String::from("todo.created"),
+            payload: String::from("{}"),
+            attempt_count: 0,
+            url,
+            secret: String::from(SECRET),
+        }
+    }
+
+    #[tokio::test]
+    async fn deliveries_only_reach_allowed_receivers() {
+        let receiver = Receiver::default();
+        let url = serve(receiver.clone()).await;
+        let local = Receivers::new(true);
+        let client = create_client(local, Duration::from_secs(2));
+        let now = OffsetDateTime::now_utc();
+
+        let (status, error) = deliver(&client, local, &due_delivery(url.clone()), now).await;
+        assert_eq!((status, error), (Some(200), None));
+
+        // redirects are not followed, as they could lead to a local address
+        let redirect = due_delivery(url.replace("/hooks", "/redirect"));
+        let (status, error) = deliver(&client, local, &redirect, now).await;
+        assert_eq!(status, Some(307));
+        assert!(error.is_some());
+        assert_eq!(receiver.received.lock().unwrap().len(), 1);
+
+        let public = Receivers::new(false);
+        let client = create_client(public, Duration::from_secs(2));
+        let https = due_delivery(url.replace("http://127.0.0.1", "https://localhost"));
+        for delivery in [due_delivery(url), https] {
+            let (status, error) = deliver(&client, public, &delivery, now).await;
+            assert_eq!(status, None);
+            let error = error.unwrap();
+            assert!(
+                error.contains("https") || error.contains("public address"),
+                "{error}"
+            );
+        }
+        assert_eq!(receiver.received.lock().unwrap().len(), 1);
+    }
+
+    /// Event of a `change` to a todo of the tenant with `tenant_id`
+    fn event(tenant_id: Id, change: fn(TodoEntity) -> TodoChange) -> OutboxEvent {
+        OutboxEvent {
+            id: Id::new(),
+            tenant_id,
+            change: change(todo("Webhook todo", Status::Todo)),
+            attempt_count: 0,
+            occurred_at: DateTime::now(),
+        }
+    }
+
+    async fn create_webhook(
+        pool: &PgPool,
+        tenant_id: Id,
+        url: &str,
+        disabled_reason: Option<&str>,
+    ) -> Id {
+        const SET_TENANT_Q: &str = "SELECT set_config('app.tenant_id', $1, true)";
+        const SET_URL_Q: &str = "UPDATE webhook SET url = $1, disabled_reason = $2 WHERE id = $3";
+
+        let webhook = WebhookEntity::new(NewProps {
+            url: WebhookUrl::new("https://example.com/hooks").unwrap(),
+            events: vec![WebhookEvent::TodoCreated],
+            secret: WebhookSecret::new(SECRET).unwrap(),
+        });
+
+        let mut repository = PgWebhookRepository::new(pool.clone()).with_tenant(tenant_id);
+        repository.create(webhook.clone()).await.unwrap();
+
+        // webhooks only accept https urls, while the stand-in receiver is served
+        // over http. Webhooks are only disabled by migrations
+        let mut tx = pool.begin().await.unwrap();
+        sqlx::query(SET_TENANT_Q)
+            .bind(tenant_id.to_string())
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        sqlx::query(SET_URL_Q)
+            .bind(url)
+            .bind(disabled_reason)
+            .bind(webhook.id().uuid())
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        webhook.id()
+    }
+
+    /// Wait until the only delivery of the webhook is no longer pending
+    async fn settled_delivery(pool: &PgPool, tenant_id: Id, webhook_id: Id) -> DeliveryEntity {
+        let repository = PgWebhookRepository::new(pool.clone()).with_tenant(tenant_id);
+        let query = DeliveriesQuery {
+            webhook_id,
+            status: None,
+        };
+
+        for _ in 0..200 {
+            let deliveries = repository.list_deliveries(query.clone()).await.unwrap();
+            if let [delivery] = deliveries.as_slice() {
+                if delivery.status != DeliveryStatus::Pending {
+                    return delivery.clone();
+                }
+            }
+            time::sleep(Duration::from_millis(20)).await;
+        }
+
+        panic!("Webhook delivery did not settle");
+    }
+
+    /// Superusers bypass row level security, so the worker runs as a regular
+    /// role which only reaches other tenants through the claim function
+    async fn worker_pool(pool: &PgPool) -> PgPool {
+        const CREATE_ROLE_Q: &str = r#"
+            DO $$ BEGIN
+                CREATE ROLE webhook_worker_test NOLOGIN NOSUPERUSER NOBYPASSRLS;
+            EXCEPTION
+                WHEN duplicate_object OR unique_violation THEN null;
+            END $$;
+            GRANT SELECT, INSERT, UPDATE, DELETE ON webhook, webhook_delivery, webhook_attempt
+                TO webhook_worker_test;
+        "#;
+
+        pool.execute(CREATE_ROLE_Q).await.unwrap();
+
+        sqlx::postgres::PgPoolOptions::new()
+            .max_connections(4)
+            .after_connect(|conn, _| {
+                Box::pin(async move {
+                    conn.execute("SET ROLE webhook_worker_test").await?;
+                    Ok(())
+                })
+            })
+            .connect_with(pool.connect_options().as_ref().clone())
+            .await
+            .unwrap()
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn deliveries_are_signed_and_retried(pool: PgPool) {
+        let pool = worker_pool(&pool).await;
+        let receiver = Receiver {
+            responses: Arc::new(Mutex::new(vec![StatusCode::INTERNAL_SERVER_ERROR])),
+            ..Receiver::default()
+        };
+        let url = serve(receiver.clone()).await;
+
+        let tenant_id = Id::new();
+        let webhook_id = create_webhook(&pool, tenant_id, &url, None).await;
+        let (sink, _) = start(pool.clone(), config(), CancellationToken::new());
+
+        // relaying the same event again does not deliver it twice
+        let created = event(tenant_id, TodoChange::Created);
+        sink.dispatch(&created).await.unwrap();
+        sink.dispatch(&created).await.unwrap();
+
+        let delivery = settled_delivery(&pool, tenant_id, webhook_id).await;
+        assert_eq!(delivery.status, DeliveryStatus::Delivered);
+        assert_eq!(delivery.attempt_count, 2);
+        let codes = delivery
+            .attempts
+            .iter()
+            .map(|a| a.status_code)
+            .collect::<Vec<_>>();
+        assert_eq!(codes, [Some(500), Some(200)]);
+
+        let received = receiver.received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        for (headers, body) in received.iter() {
+            assert_eq!(headers[ID_HEADER], delivery.id.to_string().as_str());
+            assert_eq!(headers[EVENT_HEADER], "todo.created");
+            let signature = headers[SIGNATURE_HEADER].to_str().unwrap();
+            assert!(signature::verify(SECRET, signature, body));
+        }
+
+        let payload: serde_json::Value = serde_json::from_slice(&received[0].1).unwrap();
+        assert_eq!(payload["event"], "todo.created");
+        assert_eq!(payload["todo"]["title"], "Webhook todo");
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn dead_deliveries_can_be_replayed(pool: PgPool) {
+        let pool = worker_pool(&pool).await;
+        let receiver = Receiver {
+            responses: Arc::new(Mutex::new(vec![StatusCode::SERVICE_UNAVAILABLE; 3])),
+            ..Receiver::default()
+        };
+        let url = serve(receiver.clone()).await;
+
+        let tenant_id = Id::new();
+        let webhook_id = create_webhook(&pool, tenant_id, &url, None).await;
+        let (sink, _) = start(pool.clone(), config(), CancellationToken::new());
+
+        // other tenants and events the webhook is not interested in are not delivered
+        let other_tenant = event(Id::new(), TodoChange::Created);
+        let deleted = event(tenant_id, TodoChange::Deleted);
+        let created = event(tenant_id, TodoChange::Created);
+        for event in [other_tenant, deleted, created] {
+            sink.dispatch(&event).await.unwrap();
+        }
+
+        let dead = settled_delivery(&pool, tenant_id, webhook_id).await;
+        assert_eq!(dead.status, DeliveryStatus::Dead);
+        assert_eq!(dead.attempt_count, 3);
+        assert!(dead.last_error.is_some());
+
+        let mut repository = PgWebhookRepository::new(pool.clone()).with_tenant(tenant_id);
+        repository.replay_delivery(dead.id).await.unwrap();
+
+        let replayed = settled_delivery(&pool, tenant_id, webhook_id).await;
+        assert_eq!(replayed.status, DeliveryStatus::Delivered);
+        assert_eq!(replayed.attempt_count, 1);
+        assert_eq!(replayed.attempts.len(), 4);
+        assert_eq!(receiver.received.lock().unwrap().len(), 4);
+    }
+
+    #[ignore = "requires a postgres database at DATABASE_URL"]
+    #[sqlx::test]
+    async fn disabled_webhooks_are_not_delivered_until_changed(pool: PgPool) {
+        let pool = worker_pool(&pool).await;
+        let receiver = Receiver::default();
+        let url = serve(receiver.clone()).await;
+
+        let tenant_id = Id::new();
+        let reason = "Webhook url must be an absolute https url";
+        let webhook_id = create_webhook(&pool, tenant_id, &url, Some(reason)).await;
+        let (sink, _) = start(pool.clone(), config(), CancellationToken::new());
+
+        let created = event(tenant_id, TodoChange::Created);
+        sink.dispatch(&created).await.unwrap();
+
+        let mut repository = PgWebhookRepository::new(pool.clone()).with_tenant(tenant_id);
+        let query = DeliveriesQuery {
+            webhook_id,
+            status: None,
+        };
+        assert!(repository.list_deliveries(query).await.unwrap().is_empty());
+        assert!(receiver.received.lock().unwrap().is_empty());
+
+        // disabled webhooks keep their url until their tenant changes it
+        let mut webhook = repository.find(webhook_id).await.unwrap();
+        assert_eq!(webhook.disabled_reason(), Some(reason));
+        assert_eq!(webhook.url.as_str(), url);
+
+        webhook.change(NewProps {
+            url: WebhookUrl::new("https://example.com/hooks").unwrap(),
+            events: vec![WebhookEvent::TodoCreated],
+            secret: WebhookSecret::new(SECRET).unwrap(),
+        });
+        repository.update(webhook).await.unwrap();
+
+        let webhook = repository.find(webhook_id).await.unwrap();
+        assert_eq!(webhook.disabled_reason(), None);
+        assert_eq!(webhook.url.as_str(), "https://example.com/hooks");
+    }
+}
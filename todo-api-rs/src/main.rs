@@ -5,90 +5,317 @@ mod application;
 mod domain;
 mod framework;
 
-use std::collections::HashMap;
 use std::error::Error;
+use std::future::IntoFuture;
 use std::net::SocketAddr;
+use std::sync::Arc;
 
-use axum::Router;
+use axum::extract::DefaultBodyLimit;
+use axum::{middleware, Router};
+use opentelemetry_sdk::trace::TracerProvider;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
 use tokio::net::TcpListener;
+use tokio::time::{self, timeout_at, Instant};
+use tokio_util::sync::CancellationToken;
 use tower_http::classify::{ServerErrorsAsFailures, SharedClassifier};
-use tower_http::cors::CorsLayer;
-use tower_http::trace::{DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse, TraceLayer};
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tower_http::trace::{DefaultOnRequest, DefaultOnResponse, TraceLayer};
 use tracing::Level;
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
-use framework::rest_api::routes::todo;
+use framework::config::{
+    CompressionConfig, Config, DatabaseConfig, LogFormat, RateLimitBackend,
+};
+use framework::events::listener;
+use framework::events::todo::TodoBroadcaster;
+use framework::health::{HealthChecker, Readiness};
+use framework::outbox::relay::{self, RelayConfig};
+use framework::outbox::sinks::{BroadcastSink, LogSink};
+use framework::rate_limit::memory::MemoryStore;
+use framework::rate_limit::{self, RateLimitStore, RateLimiter};
+use framework::rest_api::caching::{self, Caching};
+use framework::rest_api::cors;
+use framework::rest_api::in_flight::{self, InFlight};
+use framework::rest_api::load::{self, ConcurrencyLimit, RequestTimeout};
+use framework::rest_api::openapi;
+use framework::rest_api::redaction::Redaction;
+use framework::rest_api::request_id;
+use framework::rest_api::routes::{backup, health, metrics, todo, webhook};
+use framework::rest_api::tenant::{self, TenantAuth};
+use framework::shutdown;
+use framework::storage::rate_limit::PgRateLimitStore;
+use framework::storage::MIGRATOR;
+use framework::telemetry::{self, RequestSpan, ResponseStatus};
+use framework::webhooks::worker::{self, WebhookConfig};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    tracing_subscriber::fmt()
-        .without_time()
-        .with_target(false)
-        .with_env_filter(EnvFilter::from_default_env())
-        .with_max_level(Level::INFO)
-        .init();
+    // env vars of the .env file are a layer of the config, so it is loaded first
+    let dotenv = dotenvy::dotenv();
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(report) => {
+            eprintln!("Invalid configuration:");
+            for err in report.errors() {
+                eprintln!("  {err}");
+            }
+            std::process::exit(2);
+        }
+    };
 
-    if let Err(err) = dotenvy::dotenv() {
+    let tracer_provider =
+        telemetry::create_provider(&config.telemetry).expect("Failed creating trace exporter");
+    init_tracing(config.log.format, tracer_provider.as_ref());
+    if let Err(err) = dotenv {
         tracing::error!("Failed loading .env {err}");
     }
 
-    let pool = create_db_pool(5).await;
-    sqlx::migrate!("./migrations")
+    let pool = create_db_pool(&config.database).await;
+    MIGRATOR
         .run(&pool)
         .await
         .expect("Failed running migrations");
 
+    let shutdown = CancellationToken::new();
+    let events = TodoBroadcaster::new(1024);
+    let (webhooks, webhook_worker) =
+        worker::start(pool.clone(), WebhookConfig::default(), shutdown.clone());
+    let outbox_relay = relay::start(
+        pool.clone(),
+        RelayConfig::default(),
+        vec![
+            Arc::new(LogSink),
+            Arc::new(BroadcastSink::new(pool.clone())),
+            Arc::new(webhooks),
+        ],
+        shutdown.clone(),
+    );
+    let events_listener = listener::start(pool.clone(), events.clone(), shutdown.clone());
+
+    let readiness = Readiness::new();
+    let checker = HealthChecker::new(pool.clone(), readiness.clone(), config.health.check_timeout);
+    let in_flight = InFlight::new();
+    let rate_limit_store: Arc<dyn RateLimitStore> = match config.rate_limit.backend {
+        RateLimitBackend::Memory => Arc::new(MemoryStore::new()),
+        RateLimitBackend::Postgres => Arc::new(PgRateLimitStore::new(pool.clone())),
+    };
+    let limiter = RateLimiter::new(rate_limit_store, &config.rate_limit);
+    // probes are merged past the limits of the load, so an overloaded
+    // instance is not restarted for failing its liveness probe
     let app = Router::new()
-        .merge(todo::create_router(pool))
-        .layer(CorsLayer::very_permissive())
-        .layer(create_tracing_layer());
+        .merge(todo::create_router(
+            pool.clone(),
+            events,
+            shutdown.clone(),
+            Redaction::new(config.log.redact_descriptions),
+            config.server.todo_body_limit,
+        ))
+        .merge(webhook::create_router(pool.clone()))
+        .merge(backup::create_router(
+            pool.clone(),
+            config.server.restore_body_limit,
+        ))
+        .merge(openapi::create_router())
+        .layer(middleware::from_fn_with_state(limiter, rate_limit::limit))
+        .layer(middleware::from_fn_with_state(
+            TenantAuth::new(&config.auth),
+            tenant::authenticate,
+        ))
+        .layer(middleware::from_fn_with_state(
+            RequestTimeout::new(
+                config.server.request_timeout,
+                config.server.bulk_request_timeout,
+            ),
+            load::timeout,
+        ))
+        .layer(middleware::from_fn_with_state(
+            ConcurrencyLimit::new(config.server.max_concurrent_requests),
+            load::shed,
+        ))
+        .merge(health::create_router(checker))
+        .layer(middleware::from_fn_with_state(
+            Caching::new(&config.cache),
+            caching::cache_headers,
+        ))
+        .layer(create_compression_layer(&config.compression))
+        .layer(DefaultBodyLimit::max(config.server.body_limit))
+        .layer(middleware::from_fn(framework::metrics::track))
+        .layer(middleware::from_fn_with_state(
+            in_flight.clone(),
+            in_flight::track,
+        ))
+        .layer(cors::create_layer(&config.cors))
+        .layer(create_tracing_layer())
+        .layer(middleware::from_fn(request_id::assign));
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], 8000));
+    let addr = config.server.bind;
     let listener = TcpListener::bind(addr)
         .await
         .unwrap_or_else(|_| panic!("Failed binding tcp listener to address {addr}"));
 
     tracing::info!("Server listening on {addr}");
 
-    axum::serve(listener, app.into_make_service())
+    let admin_addr = config.server.admin_bind;
+    let admin_listener = TcpListener::bind(admin_addr)
         .await
-        .expect("Failed initiating server");
+        .unwrap_or_else(|_| panic!("Failed binding tcp listener to address {admin_addr}"));
+    tracing::info!("Serving metrics on {admin_addr}");
+
+    let admin_app = metrics::create_router(pool.clone(), config.health.check_timeout);
+    let admin_server = tokio::spawn({
+        let serve = axum::serve(admin_listener, admin_app)
+            .with_graceful_shutdown(shutdown.clone().cancelled_owned());
+        async move {
+            if let Err(err) = serve.await {
+                tracing::error!("Failed serving metrics: {err}");
+            }
+        }
+    });
+
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        let drain_delay = config.server.drain_delay;
+        async move {
+            let signal = shutdown::signal().await;
+            tracing::info!("Received {signal}, shutting down");
+            // keep serving until load balancers noticed the failing readiness
+            readiness.drain();
+            time::sleep(drain_delay).await;
+            shutdown.cancel();
+        }
+    });
+
+    let mut server = tokio::spawn(
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown.clone().cancelled_owned())
+        .into_future(),
+    );
+
+    // the server only stops on its own when it fails
+    tokio::select! {
+        res = &mut server => {
+            res.expect("Server task panicked")
+                .expect("Failed initiating server");
+            return Ok(());
+        }
+        _ = shutdown.cancelled() => {}
+    }
+
+    // new connections are refused from now on, in-flight requests and the
+    // workers share the same deadline to finish
+    let deadline = Instant::now() + config.server.shutdown_timeout;
+    tracing::info!("Draining {} in-flight requests", in_flight.running().len());
+    if timeout_at(deadline, &mut server).await.is_err() {
+        let interrupted = in_flight.running();
+        tracing::warn!(
+            "Shutdown deadline exceeded, interrupting {} requests",
+            interrupted.len()
+        );
+        for request in interrupted {
+            tracing::warn!(
+                "Interrupted {} {} running for {:?}",
+                request.method,
+                request.path,
+                request.elapsed()
+            );
+        }
+        server.abort();
+    }
+
+    let workers = [
+        ("outbox relay", outbox_relay),
+        ("webhook worker", webhook_worker),
+        ("todo events listener", events_listener),
+        ("admin server", admin_server),
+    ];
+    for (name, worker) in workers {
+        if timeout_at(deadline, worker).await.is_err() {
+            tracing::warn!("Shutdown deadline exceeded, interrupting the {name}");
+        }
+    }
+
+    pool.close().await;
+    if let Some(provider) = tracer_provider {
+        telemetry::shutdown(provider).await;
+    }
+    tracing::info!("Server stopped");
 
     Ok(())
 }
 
-async fn create_db_pool(connections: u32) -> Pool<Postgres> {
-    let env = std::env::vars().collect::<HashMap<String, String>>();
-    let user = env.get("DB_USER").expect("Missing DB_USER env");
-    let password = env.get("DB_PASSWORD").expect("Missing DB_PASSWORD env");
-    let host = env.get("DB_HOST").expect("Missing DB_HOST env");
-    let port = env.get("DB_PORT").expect("Missing DB_PORT env");
-    let db_name = env.get("DB_NAME").expect("Missing DB_NAME env");
+/// Log in `format`, and export traces with `provider` when there is one
+fn init_tracing(format: LogFormat, provider: Option<&TracerProvider>) {
+    let logs = tracing_subscriber::fmt::layer()
+        .without_time()
+        .with_target(false);
+    let logs = match format {
+        LogFormat::Text => logs.boxed(),
+        LogFormat::Json => logs.json().boxed(),
+    };
+
+    tracing_subscriber::registry()
+        .with(logs.with_filter(LevelFilter::INFO))
+        .with(provider.map(telemetry::layer))
+        .init();
+}
 
-    let url = format!("postgres://{user}:{password}@{host}:{port}/{db_name}");
+/// Connect to the database, whose statements are cancelled once they run
+/// longer than the statement timeout so slow queries cannot hold connections
+async fn create_db_pool(config: &DatabaseConfig) -> Pool<Postgres> {
+    let name = config.connection.get_database().unwrap_or_default();
+    tracing::info!("Connecting to database {name}");
 
-    tracing::info!("Connecting to database at {url}");
+    let statement_timeout = config.statement_timeout.as_millis().to_string();
+    let connection = config
+        .connection
+        .clone()
+        .options([("statement_timeout", statement_timeout.as_str())]);
 
     PgPoolOptions::new()
-        .max_connections(connections)
-        .connect(url.as_str())
+        .min_connections(config.min_connections)
+        .max_connections(config.max_connections)
+        .acquire_timeout(config.connect_timeout)
+        .idle_timeout(config.idle_timeout)
+        .connect_with(connection)
         .await
         .expect("Failed connecting to postgres database")
 }
 
-fn create_tracing_layer() -> TraceLayer<SharedClassifier<ServerErrorsAsFailures>> {
+/// Compress responses larger than the minimum size with the encoding the
+/// client accepts, except event streams which must be flushed as they go
+fn create_compression_layer(config: &CompressionConfig) -> CompressionLayer<impl Predicate> {
+    let predicate = SizeAbove::new(config.min_size)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::const_new("text/event-stream"));
+
+    CompressionLayer::new()
+        .gzip(config.enabled)
+        .br(config.enabled)
+        .zstd(config.enabled)
+        .compress_when(predicate)
+}
+
+fn create_tracing_layer() -> TraceLayer<
+    SharedClassifier<ServerErrorsAsFailures>,
+    RequestSpan,
+    DefaultOnRequest,
+    ResponseStatus,
+> {
     TraceLayer::new_for_http()
-        .make_span_with(
-            DefaultMakeSpan::new()
-                .include_headers(false)
-                .level(Level::INFO),
-        )
+        .make_span_with(RequestSpan)
         .on_request(DefaultOnRequest::new().level(Level::INFO))
-        .on_response(
+        .on_response(ResponseStatus(
             DefaultOnResponse::new()
                 .level(Level::INFO)
                 .latency_unit(tower_http::LatencyUnit::Micros),
-        )
+        ))
 }